@@ -0,0 +1,46 @@
+use std::process::Command;
+
+/// Bakes the git commit of the build into the program as `GIT_COMMIT`, and the enabled
+/// Cargo features as `ENABLED_FEATURES`
+///
+/// `GIT_COMMIT` can be set explicitly for builds without a git checkout,
+/// such as verifiable builds.
+fn main() {
+    println!("cargo:rerun-if-env-changed=GIT_COMMIT");
+    println!("cargo:rerun-if-changed=../../.git/HEAD");
+    println!("cargo:rerun-if-changed=../../.git/refs");
+
+    let commit = std::env::var("GIT_COMMIT").ok().or_else(|| {
+        Command::new("git")
+            .args(["rev-parse", "HEAD"])
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .and_then(|output| String::from_utf8(output.stdout).ok())
+            .map(|commit| commit.trim().to_string())
+    });
+
+    println!(
+        "cargo:rustc-env=GIT_COMMIT={}",
+        commit.unwrap_or_else(|| "unknown".to_string())
+    );
+
+    println!("cargo:rustc-env=ENABLED_FEATURES={}", enabled_features());
+}
+
+/// Lists the features of `Cargo.toml` enabled in this build, comma separated
+///
+/// Cargo passes each enabled feature as `CARGO_FEATURE_<NAME>`, upper-cased with dashes
+/// replaced by underscores. The program's feature names only use dashes, so they are
+/// recovered by reversing that.
+fn enabled_features() -> String {
+    let mut features: Vec<String> = std::env::vars()
+        .filter_map(|(key, _)| {
+            key.strip_prefix("CARGO_FEATURE_")
+                .map(|name| name.to_lowercase().replace('_', "-"))
+        })
+        .filter(|name| name != "default")
+        .collect();
+    features.sort();
+    features.join(",")
+}
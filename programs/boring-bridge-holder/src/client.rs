@@ -0,0 +1,300 @@
+//! Off-chain helpers for building the holder's transactions
+//!
+//! Mirrors `scripts/create_lookup_table.ts` and `scripts/transfer_remote.ts`: the holder's
+//! address lookup table is created and extended with the static addresses of its
+//! `ConfigurationData`, and `transfer_remote` is compiled into a v0 message against it.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::address_lookup_table::instruction::{
+    create_lookup_table, extend_lookup_table,
+};
+use anchor_lang::solana_program::address_lookup_table::AddressLookupTableAccount;
+use anchor_lang::solana_program::hash::Hash;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::message::{v0, CompileError, VersionedMessage};
+use anchor_lang::solana_program::system_program;
+use anchor_lang::{InstructionData, ToAccountMetas};
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+
+use crate::ConfigurationData;
+
+/// Every address of `transfer_remote` that doesn't change between transfers
+///
+/// These are the programs, the holder and its token account, and the static addresses of the
+/// configuration. The unique message and the PDAs derived from it change with each transfer.
+///
+/// # Arguments
+/// * `boring_account` - The holder the table is for
+/// * `config` - The configuration currently approved for the holder
+pub fn lookup_table_addresses(boring_account: &Pubkey, config: &ConfigurationData) -> Vec<Pubkey> {
+    vec![
+        crate::ID,
+        system_program::ID,
+        *boring_account,
+        get_associated_token_address_with_program_id(
+            boring_account,
+            &config.mint,
+            &config.token_2022_program,
+        ),
+        config.target_program,
+        config.noop,
+        config.token_pda,
+        config.mailbox_program,
+        config.mailbox_outbox,
+        config.message_dispatch_authority,
+        config.igp_program,
+        config.igp_program_data,
+        config.igp_account,
+        config.token_sender,
+        config.token_2022_program,
+        config.mint_auth,
+        config.mint,
+    ]
+}
+
+/// Instructions creating the holder's lookup table, extending it with
+/// `lookup_table_addresses` and recording it with `set_lookup_table`
+///
+/// The owner signs as the table's authority and the holder's owner.
+///
+/// # Arguments
+/// * `boring_account` - The holder the table is for
+/// * `config` - The configuration currently approved for the holder
+/// * `owner` - The holder's owner
+/// * `payer` - The account paying the table's rent
+/// * `recent_slot` - A recent slot, which seeds the table's address
+///
+/// # Returns
+/// * `(Pubkey, Vec<Instruction>)` - The table's address and the instructions, in order
+pub fn create_lookup_table_instructions(
+    boring_account: &Pubkey,
+    config: &ConfigurationData,
+    owner: &Pubkey,
+    payer: &Pubkey,
+    recent_slot: u64,
+) -> (Pubkey, Vec<Instruction>) {
+    let (create, lookup_table) = create_lookup_table(*owner, *payer, recent_slot);
+    let extend = extend_lookup_table(
+        lookup_table,
+        *owner,
+        Some(*payer),
+        lookup_table_addresses(boring_account, config),
+    );
+    let record = Instruction {
+        program_id: crate::ID,
+        accounts: crate::accounts::UpdateOwner {
+            boring_account: *boring_account,
+            signer: *owner,
+        }
+        .to_account_metas(None),
+        data: crate::instruction::SetLookupTable { lookup_table }.data(),
+    };
+    (lookup_table, vec![create, extend, record])
+}
+
+/// Builds the strategist's `transfer_remote` instruction for a configuration
+///
+/// # Arguments
+/// * `boring_account` - The holder transferring
+/// * `message_nonce` - The message nonce currently stored in the holder's `BoringState`
+/// * `config` - The configuration currently approved for the holder
+/// * `strategist` - The holder's strategist, who signs the transfer
+/// * `amount` - The amount of tokens to transfer, or `SWEEP_ALL` to transfer the full balance
+/// * `max_gas_payment_lamports` - The most lamports the strategist may spend on gas payment
+///   and message rent
+pub fn transfer_remote_instruction(
+    boring_account: &Pubkey,
+    message_nonce: u64,
+    config: &ConfigurationData,
+    strategist: &Pubkey,
+    amount: u64,
+    max_gas_payment_lamports: u64,
+) -> Instruction {
+    let (unique_message, _) = Pubkey::find_program_address(
+        &[
+            b"unique_message",
+            boring_account.as_ref(),
+            &message_nonce.to_le_bytes(),
+        ],
+        &crate::ID,
+    );
+    let (message_storage_pda, _) = Pubkey::find_program_address(
+        &[
+            b"hyperlane",
+            b"-",
+            b"dispatched_message",
+            b"-",
+            unique_message.as_ref(),
+        ],
+        &config.mailbox_program,
+    );
+    let (gas_payment_pda, _) = Pubkey::find_program_address(
+        &[
+            b"hyperlane_igp",
+            b"-",
+            b"gas_payment",
+            b"-",
+            unique_message.as_ref(),
+        ],
+        &config.igp_program,
+    );
+    let transfer = crate::accounts::WarpRouteTransferAccounts {
+        boring_account: *boring_account,
+        signer: *strategist,
+        target_program: config.target_program,
+        system_program: system_program::ID,
+        noop: config.noop,
+        token_pda: config.token_pda,
+        mailbox_program: config.mailbox_program,
+        mailbox_outbox: config.mailbox_outbox,
+        message_dispatch_authority: config.message_dispatch_authority,
+        unique_message,
+        message_storage_pda,
+        igp_program: config.igp_program,
+        igp_program_data: config.igp_program_data,
+        gas_payment_pda,
+        igp_account: config.igp_account,
+        token_sender: config.token_sender,
+        token_2022: config.token_2022_program,
+        mint_auth: config.mint_auth,
+        mint: config.mint,
+        boring_account_ata: get_associated_token_address_with_program_id(
+            boring_account,
+            &config.mint,
+            &config.token_2022_program,
+        ),
+        strategist_ata: get_associated_token_address_with_program_id(
+            strategist,
+            &config.mint,
+            &config.token_2022_program,
+        ),
+    };
+
+    Instruction {
+        program_id: crate::ID,
+        accounts: crate::accounts::TransferRemoteContext { transfer }.to_account_metas(None),
+        data: crate::instruction::TransferRemote {
+            destination_domain: config.destination_domain,
+            recipient: config.recipient,
+            remote_decimals: config.remote_decimals,
+            amount,
+            max_gas_payment_lamports,
+        }
+        .data(),
+    }
+}
+
+/// Compiles the strategist's `transfer_remote` into a v0 message against the holder's lookup
+/// table
+///
+/// # Arguments
+/// * `payer` - The fee payer, who signs the transaction
+/// * `lookup_table` - The holder's lookup table, as fetched from the cluster
+/// * `recent_blockhash` - A recent blockhash
+/// * `instruction` - The instruction built by `transfer_remote_instruction`
+///
+/// # Errors
+/// * `CompileError` - If the message can't be compiled, for example with too many accounts
+pub fn transfer_remote_message(
+    payer: &Pubkey,
+    lookup_table: &AddressLookupTableAccount,
+    recent_blockhash: Hash,
+    instruction: Instruction,
+) -> std::result::Result<VersionedMessage, CompileError> {
+    v0::Message::try_compile(
+        payer,
+        &[instruction],
+        std::slice::from_ref(lookup_table),
+        recent_blockhash,
+    )
+    .map(VersionedMessage::V0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ConfigurationBridgeKind, Recipient};
+
+    fn config() -> ConfigurationData {
+        ConfigurationData {
+            target_program: Pubkey::new_unique(),
+            noop: Pubkey::new_unique(),
+            token_pda: Pubkey::new_unique(),
+            mailbox_program: Pubkey::new_unique(),
+            mailbox_outbox: Pubkey::new_unique(),
+            message_dispatch_authority: Pubkey::new_unique(),
+            igp_program: Pubkey::new_unique(),
+            igp_program_data: Pubkey::new_unique(),
+            igp_account: Pubkey::new_unique(),
+            token_sender: Pubkey::new_unique(),
+            token_2022_program: anchor_spl::token_2022::ID,
+            mint_auth: Pubkey::new_unique(),
+            destination_domain: 1,
+            recipient: Recipient::Evm20([0; 32]),
+            decimals: 6,
+            remote_decimals: 6,
+            bridge_kind: ConfigurationBridgeKind::HyperlaneWarpRoute,
+            mint: Pubkey::new_unique(),
+        }
+    }
+
+    #[test]
+    fn creates_extends_and_records_the_lookup_table() {
+        let boring_account = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let config = config();
+
+        let (lookup_table, instructions) =
+            create_lookup_table_instructions(&boring_account, &config, &owner, &owner, 42);
+
+        assert_eq!(instructions.len(), 3);
+        let (create, extend, record) = (&instructions[0], &instructions[1], &instructions[2]);
+        assert_eq!(create.accounts[0].pubkey, lookup_table);
+        assert_eq!(extend.accounts[0].pubkey, lookup_table);
+        let addresses = lookup_table_addresses(&boring_account, &config);
+        let encoded: Vec<u8> = addresses.iter().flat_map(|key| key.to_bytes()).collect();
+        assert!(extend.data.ends_with(&encoded));
+
+        assert_eq!(record.program_id, crate::ID);
+        assert_eq!(record.accounts[0].pubkey, boring_account);
+        assert!(record.accounts[1].is_signer);
+        assert!(record.data.ends_with(lookup_table.as_ref()));
+    }
+
+    #[test]
+    fn loads_static_accounts_of_transfer_remote_from_the_lookup_table() {
+        let boring_account = Pubkey::new_unique();
+        let strategist = Pubkey::new_unique();
+        let payer = Pubkey::new_unique();
+        let config = config();
+        let lookup_table = AddressLookupTableAccount {
+            key: Pubkey::new_unique(),
+            addresses: lookup_table_addresses(&boring_account, &config),
+        };
+
+        let instruction =
+            transfer_remote_instruction(&boring_account, 7, &config, &strategist, 1_000, 10);
+        assert_eq!(instruction.accounts.len(), 21);
+        let message =
+            transfer_remote_message(&payer, &lookup_table, Hash::default(), instruction).unwrap();
+
+        let VersionedMessage::V0(message) = message else {
+            panic!("expected a v0 message");
+        };
+        assert_eq!(message.address_table_lookups.len(), 1);
+        assert_eq!(
+            message.address_table_lookups[0].account_key,
+            lookup_table.key
+        );
+
+        // Only the signers, the invoked program and the accounts that change with each transfer
+        // stay in the message itself.
+        assert_eq!(message.account_keys[0], payer);
+        assert!(message.account_keys.contains(&strategist));
+        assert!(message.account_keys.contains(&crate::ID));
+        assert_eq!(message.account_keys.len(), 7);
+        for address in &lookup_table.addresses[1..] {
+            assert!(!message.account_keys.contains(address));
+        }
+    }
+}
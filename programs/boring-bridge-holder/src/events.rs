@@ -1,5 +1,9 @@
 use anchor_lang::prelude::*;
 
+use crate::{
+    AllowedRecipient, AllowedSender, BridgeKind, ConfigurationBridgeKind, NttMode, Recipient,
+};
+
 #[event]
 pub struct Initialized {
     pub boring_account: Pubkey,
@@ -37,13 +41,171 @@ pub struct ConfigurationUpdated {
     pub token_2022_program: Pubkey,
     pub mint_auth: Pubkey,
     pub destination_domain: u32,
-    pub evm_recipient: [u8; 32],
+    pub recipient: Recipient,
     pub decimals: u8,
+    pub remote_decimals: u8,
+    pub bridge_kind: ConfigurationBridgeKind,
+    pub mint: Pubkey,
 }
 
 #[event]
 pub struct TransferRemote {
+    /// Bridge the transfer went through, which `destination_domain` is numbered by
+    pub bridge_kind: BridgeKind,
     pub destination_domain: u32,
     pub evm_recipient: [u8; 32],
+    /// Amount bridged, net of any Token-2022 transfer fee
+    pub amount: u64,
+    /// Token-2022 transfer fee withheld when moving the tokens to the signer
+    pub transfer_fee: u64,
+    pub total_bridged: u64,
+    pub transfer_count: u64,
+    /// Total bridged to `destination_domain` through `bridge_kind`, or 0 if the pair is not
+    /// tracked
+    pub domain_total_bridged: u64,
+    pub slot: u64,
+    pub timestamp: i64,
+    /// Nonce the unique message account of this transfer was derived from
+    pub message_nonce: u64,
+}
+
+#[event]
+pub struct StateMigrated {
+    pub boring_account: Pubkey,
+    pub old_version: u8,
+    pub new_version: u8,
+}
+
+#[event]
+pub struct AutoBridgePolicyUpdated {
+    pub enabled: bool,
+    pub min_balance: u64,
+    pub amount: u64,
+    pub min_interval_seconds: i64,
+    pub keeper_bounty_lamports: u64,
+}
+
+#[event]
+pub struct KeeperRewarded {
+    pub keeper: Pubkey,
+    pub bounty_lamports: u64,
+}
+
+#[event]
+pub struct SweepReserveUpdated {
+    pub old_sweep_reserve: u64,
+    pub new_sweep_reserve: u64,
+}
+
+#[event]
+pub struct RouteUpdated {
+    pub route: Pubkey,
+    pub route_id: u8,
+    pub target_program: Pubkey,
+    pub mint_auth: Pubkey,
+    pub mint: Pubkey,
+    pub destination_domain: u32,
+    pub recipient: Recipient,
+}
+
+#[event]
+pub struct CctpConfigurationUpdated {
+    pub config_hash: [u8; 32],
+    pub token_messenger_minter_program: Pubkey,
+    pub message_transmitter_program: Pubkey,
+    pub message_transmitter: Pubkey,
+    pub token_messenger: Pubkey,
+    pub remote_token_messenger: Pubkey,
+    pub token_minter: Pubkey,
+    pub local_token: Pubkey,
+    pub burn_token_mint: Pubkey,
+    pub destination_domain: u32,
+    pub mint_recipient: [u8; 32],
+}
+
+#[event]
+pub struct NttConfigurationUpdated {
+    pub config_hash: [u8; 32],
+    pub ntt_manager_program: Pubkey,
+    pub manager_config: Pubkey,
+    pub mint: Pubkey,
+    pub custody: Pubkey,
+    pub outbox_rate_limit: Pubkey,
+    pub relayer_fee_recipient: Pubkey,
+    pub mode: NttMode,
+    pub recipient_chain: u16,
+    pub recipient_address: [u8; 32],
+}
+
+#[event]
+pub struct OftConfigurationUpdated {
+    pub config_hash: [u8; 32],
+    pub oft_program: Pubkey,
+    pub endpoint_program: Pubkey,
+    pub oft_store: Pubkey,
+    pub peer: Pubkey,
+    pub token_escrow: Pubkey,
+    pub token_mint: Pubkey,
+    pub dst_eid: u32,
+    pub to: [u8; 32],
+}
+
+#[event]
+pub struct GovernorUpdated {
+    pub enabled: bool,
+    pub origin: u32,
+    pub sender: [u8; 32],
+}
+
+#[event]
+pub struct LookupTableUpdated {
+    pub boring_account: Pubkey,
+    pub lookup_table: Pubkey,
+}
+
+#[event]
+pub struct InboundConfigUpdated {
+    pub boring_account: Pubkey,
+    pub mailbox: Pubkey,
+    pub ism: Option<Pubkey>,
+    pub allowed_senders: Vec<AllowedSender>,
+}
+
+#[event]
+pub struct DispatchAllowlistUpdated {
+    pub boring_account: Pubkey,
+    pub allowed_recipients: Vec<AllowedRecipient>,
+}
+
+#[event]
+pub struct TransferPayloadDispatched {
+    pub transfer_message_id: [u8; 32],
+    pub payload_message_id: [u8; 32],
+    pub payload_recipient: [u8; 32],
+    pub payload_message_nonce: u64,
+}
+
+#[event]
+pub struct MessageDispatched {
+    pub destination_domain: u32,
+    pub recipient: [u8; 32],
+    pub message_id: [u8; 32],
+    pub gas_amount: Option<u64>,
+    pub message_nonce: u64,
+}
+
+#[event]
+pub struct RecipientIsmUpdated {
+    pub ism: Option<Pubkey>,
+}
+
+#[event]
+pub struct TokensReceived {
+    pub boring_account: Pubkey,
+    pub origin: u32,
+    pub sender: [u8; 32],
+    pub mint: Pubkey,
     pub amount: u64,
+    /// The holder's token account balance when the message was handled
+    pub balance: u64,
 }
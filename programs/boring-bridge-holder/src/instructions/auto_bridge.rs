@@ -0,0 +1,117 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+
+use crate::instructions::migrate_state::LoadState;
+use crate::{
+    AutoBridgePolicy, BoringState, CrankTransferRemoteContext, CustomError,
+    MAX_KEEPER_BOUNTY_LAMPORTS,
+};
+
+/// Checks the auto-bridge policy against the holder's balance and the current time
+///
+/// # Returns
+/// * `Result<u64>` - The amount of tokens the keeper may bridge
+pub fn bridge_amount(state: &BoringState, balance: u64, now: i64) -> Result<u64> {
+    let policy = AutoBridgePolicy::from(state.auto_bridge_policy);
+    require!(policy.enabled, CustomError::AutoBridgeDisabled);
+    // Policies stored before positive intervals were required are not cranked.
+    require!(
+        policy.min_interval_seconds > 0,
+        CustomError::InvalidAutoBridgePolicy
+    );
+
+    let next_allowed = state
+        .last_transfer_timestamp
+        .saturating_add(policy.min_interval_seconds);
+    require!(
+        state.transfer_count == 0 || now >= next_allowed,
+        CustomError::AutoBridgeTooSoon
+    );
+
+    require!(
+        balance > 0 && balance >= policy.min_balance,
+        CustomError::AutoBridgeBelowThreshold
+    );
+
+    // An amount of 0 sweeps the full balance, leaving the sweep reserve.
+    if policy.amount == 0 {
+        let amount = balance.saturating_sub(state.sweep_reserve);
+        require!(amount > 0, CustomError::AutoBridgeBelowThreshold);
+        Ok(amount)
+    } else {
+        require!(
+            balance >= policy.amount,
+            CustomError::AutoBridgeBelowThreshold
+        );
+        Ok(policy.amount)
+    }
+}
+
+/// Validates an auto-bridge policy before it is stored
+///
+/// An enabled policy needs a positive minimum interval, since each transfer pays the keeper
+/// bounty and without an interval keepers could crank repeatedly to drain the fee vault.
+pub fn validate_policy(policy: &AutoBridgePolicy) -> Result<()> {
+    require!(
+        policy.min_interval_seconds >= 0,
+        CustomError::InvalidAutoBridgePolicy
+    );
+    require!(
+        !policy.enabled || policy.min_interval_seconds > 0,
+        CustomError::InvalidAutoBridgePolicy
+    );
+    require!(
+        policy.keeper_bounty_lamports <= MAX_KEEPER_BOUNTY_LAMPORTS,
+        CustomError::InvalidAutoBridgePolicy
+    );
+    Ok(())
+}
+
+/// Pays the keeper bounty from the holder's fee vault
+///
+/// # Returns
+/// * `Result<u64>` - The bounty paid in lamports
+pub fn pay_keeper_bounty(accounts: &CrankTransferRemoteContext, fee_vault_bump: u8) -> Result<u64> {
+    let bounty = accounts
+        .transfer
+        .boring_account
+        .load_state()?
+        .auto_bridge_policy
+        .keeper_bounty_lamports;
+    if bounty == 0 {
+        return Ok(0);
+    }
+
+    // The vault must stay rent exempt, or be emptied entirely.
+    let vault_balance = accounts.fee_vault.lamports();
+    let remaining = vault_balance
+        .checked_sub(bounty)
+        .ok_or(error!(CustomError::FeeVaultInsufficient))?;
+    require!(
+        remaining == 0 || remaining >= Rent::get()?.minimum_balance(0),
+        CustomError::FeeVaultInsufficient
+    );
+
+    let boring_account_key = accounts.transfer.boring_account.key();
+    let bump_bytes = &[fee_vault_bump];
+    let seeds = &[
+        b"fee_vault" as &[u8],
+        boring_account_key.as_ref(),
+        bump_bytes,
+    ];
+    let signer_seeds = &[&seeds[..]];
+
+    system_program::transfer(
+        CpiContext::new_with_signer(
+            accounts.transfer.system_program.to_account_info(),
+            system_program::Transfer {
+                from: accounts.fee_vault.to_account_info(),
+                to: accounts.transfer.signer.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        bounty,
+    )?;
+
+    Ok(bounty)
+}
@@ -0,0 +1,65 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::invoke_signed;
+
+use crate::instructions::hyperlane_warp_route::HyperlaneWarpRoute;
+use crate::{BridgeKind, ConfigurationBridgeKind, ConfigurationData};
+
+/// A transfer made through a bridge, after tokens were moved to the strategist
+pub struct BridgeTransfer {
+    pub destination_domain: u32,
+    /// The 32-byte recipient address on the destination chain
+    pub recipient: [u8; 32],
+    /// The amount the bridge receives, net of any Token-2022 transfer fee
+    pub amount: u64,
+}
+
+/// A bridge program the holder can transfer tokens through
+///
+/// Adapters own everything specific to a bridge: which configurations it accepts and the
+/// accounts and data of its transfer instruction. Strategist checks, the configuration hash,
+/// moving tokens to the strategist and the bridging statistics stay in the shared transfer
+/// path, so adding a bridge does not touch them.
+pub trait BridgeAdapter<'info> {
+    /// The bridge the adapter transfers through
+    const KIND: BridgeKind;
+
+    /// The configuration committed to the holder's configuration hash for this bridge
+    type Config;
+
+    /// Validates a configuration before it is approved or bridged with
+    fn validate_config(config: &Self::Config) -> Result<()>;
+
+    /// The bridge program the transfer instruction is sent to
+    fn program_id(&self) -> Pubkey;
+
+    /// Builds the account metas of the bridge's transfer instruction
+    fn account_metas(&self) -> Vec<AccountMeta>;
+
+    /// Collects the account infos of the bridge's transfer instruction
+    fn account_infos(&self) -> Vec<AccountInfo<'info>>;
+
+    /// Builds the data of the bridge's transfer instruction
+    fn instruction_data(&self, transfer: &BridgeTransfer) -> Result<Vec<u8>>;
+
+    /// Invokes the bridge's transfer instruction, signed with `signer_seeds`
+    fn invoke(&self, transfer: &BridgeTransfer, signer_seeds: &[&[&[u8]]]) -> Result<()> {
+        let instruction = Instruction {
+            program_id: self.program_id(),
+            accounts: self.account_metas(),
+            data: self.instruction_data(transfer)?,
+        };
+        Ok(invoke_signed(
+            &instruction,
+            &self.account_infos(),
+            signer_seeds,
+        )?)
+    }
+}
+
+/// Validates a configuration with the adapter of its bridge kind
+pub fn validate_config(config: &ConfigurationData) -> Result<()> {
+    match config.bridge_kind {
+        ConfigurationBridgeKind::HyperlaneWarpRoute => HyperlaneWarpRoute::validate_config(config),
+    }
+}
@@ -0,0 +1,307 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::AccountMeta;
+use borsh::BorshSerialize;
+
+use crate::instructions::bridge_adapter::{BridgeAdapter, BridgeTransfer};
+use crate::instructions::migrate_state::LoadState;
+use crate::{BridgeKind, CctpConfigurationData, CustomError, DepositForBurnContext};
+
+/// `sha256("global:deposit_for_burn")[..8]`, the Anchor discriminator of `deposit_for_burn`
+const DEPOSIT_FOR_BURN_DISCRIMINATOR: [u8; 8] = [215, 60, 61, 46, 114, 55, 128, 176];
+
+/// Parameters of the TokenMessengerMinter's `deposit_for_burn`
+#[derive(BorshSerialize)]
+struct DepositForBurnParams {
+    amount: u64,
+    destination_domain: u32,
+    mint_recipient: Pubkey,
+}
+
+/// Adapter burning USDC through Circle's CCTP TokenMessengerMinter `deposit_for_burn`
+///
+/// Tokens are burned straight from the holder's token account, so the holder PDA signs as the
+/// depositor and the strategist only pays the rent of the message account.
+pub struct CircleCctp<'a, 'info> {
+    accounts: &'a DepositForBurnContext<'info>,
+}
+
+impl<'a, 'info> CircleCctp<'a, 'info> {
+    pub fn new(accounts: &'a DepositForBurnContext<'info>) -> Self {
+        Self { accounts }
+    }
+}
+
+impl<'info> BridgeAdapter<'info> for CircleCctp<'_, 'info> {
+    const KIND: BridgeKind = BridgeKind::CircleCctp;
+
+    type Config = CctpConfigurationData;
+
+    /// Minted USDC can't be recovered from the zero address, so a recipient must be set.
+    fn validate_config(config: &CctpConfigurationData) -> Result<()> {
+        require!(
+            config.mint_recipient != [0; 32],
+            CustomError::InvalidConfiguration
+        );
+        Ok(())
+    }
+
+    fn program_id(&self) -> Pubkey {
+        self.accounts.token_messenger_minter_program.key()
+    }
+
+    fn account_metas(&self) -> Vec<AccountMeta> {
+        let accounts = self.accounts;
+        vec![
+            AccountMeta::new_readonly(accounts.boring_account.key(), true),
+            AccountMeta::new(accounts.signer.key(), true),
+            AccountMeta::new_readonly(accounts.sender_authority_pda.key(), false),
+            AccountMeta::new(accounts.boring_account_ata.key(), false),
+            AccountMeta::new(accounts.message_transmitter.key(), false),
+            AccountMeta::new_readonly(accounts.token_messenger.key(), false),
+            AccountMeta::new_readonly(accounts.remote_token_messenger.key(), false),
+            AccountMeta::new_readonly(accounts.token_minter.key(), false),
+            AccountMeta::new(accounts.local_token.key(), false),
+            AccountMeta::new(accounts.burn_token_mint.key(), false),
+            AccountMeta::new(accounts.message_sent_event_data.key(), true),
+            AccountMeta::new_readonly(accounts.message_transmitter_program.key(), false),
+            AccountMeta::new_readonly(accounts.token_messenger_minter_program.key(), false),
+            AccountMeta::new_readonly(accounts.token_program.key(), false),
+            AccountMeta::new_readonly(accounts.system_program.key(), false),
+            AccountMeta::new_readonly(accounts.event_authority.key(), false),
+            AccountMeta::new_readonly(accounts.token_messenger_minter_program.key(), false),
+        ]
+    }
+
+    fn account_infos(&self) -> Vec<AccountInfo<'info>> {
+        let accounts = self.accounts;
+        vec![
+            // 0.  `[signer]` The depositor, which owns the burned tokens.
+            accounts.boring_account.to_account_info(),
+            // 1.  `[signer, writeable]` The payer of the message account's rent.
+            accounts.signer.to_account_info(),
+            // 2.  `[]` The TokenMessengerMinter's sender authority PDA.
+            accounts.sender_authority_pda.to_account_info(),
+            // 3.  `[writeable]` The token account tokens are burned from.
+            accounts.boring_account_ata.to_account_info(),
+            // 4.  `[writeable]` The MessageTransmitter state.
+            accounts.message_transmitter.to_account_info(),
+            // 5.  `[]` The TokenMessenger state.
+            accounts.token_messenger.to_account_info(),
+            // 6.  `[]` The TokenMessenger of the destination domain.
+            accounts.remote_token_messenger.to_account_info(),
+            // 7.  `[]` The TokenMinter state.
+            accounts.token_minter.to_account_info(),
+            // 8.  `[writeable]` The local token state of the burned mint.
+            accounts.local_token.to_account_info(),
+            // 9.  `[writeable]` The burned mint.
+            accounts.burn_token_mint.to_account_info(),
+            // 10. `[signer, writeable]` The message account, derived from the holder's message nonce.
+            accounts.message_sent_event_data.to_account_info(),
+            // 11. `[executable]` The MessageTransmitter program.
+            accounts.message_transmitter_program.to_account_info(),
+            // 12. `[executable]` The TokenMessengerMinter program.
+            accounts.token_messenger_minter_program.to_account_info(),
+            // 13. `[executable]` The token program of the burned mint.
+            accounts.token_program.to_account_info(),
+            // 14. `[executable]` The system program.
+            accounts.system_program.to_account_info(),
+            // 15. `[]` The TokenMessengerMinter's event authority PDA.
+            accounts.event_authority.to_account_info(),
+            // 16. `[executable]` The TokenMessengerMinter program, for its event CPI.
+            accounts.token_messenger_minter_program.to_account_info(),
+        ]
+    }
+
+    fn instruction_data(&self, transfer: &BridgeTransfer) -> Result<Vec<u8>> {
+        let params = DepositForBurnParams {
+            amount: transfer.amount,
+            destination_domain: transfer.destination_domain,
+            mint_recipient: Pubkey::new_from_array(transfer.recipient),
+        };
+
+        let mut data = DEPOSIT_FOR_BURN_DISCRIMINATOR.to_vec();
+        data.extend(params.try_to_vec()?);
+        Ok(data)
+    }
+}
+
+/// Verifies the CCTP configuration hash by hashing all inputs and comparing the result to the
+/// stored CCTP configuration hash
+pub fn verify_configuration(
+    accounts: &DepositForBurnContext,
+    destination_domain: u32,
+    mint_recipient: [u8; 32],
+) -> Result<()> {
+    let config = CctpConfigurationData {
+        token_messenger_minter_program: accounts.token_messenger_minter_program.key(),
+        message_transmitter_program: accounts.message_transmitter_program.key(),
+        message_transmitter: accounts.message_transmitter.key(),
+        token_messenger: accounts.token_messenger.key(),
+        remote_token_messenger: accounts.remote_token_messenger.key(),
+        token_minter: accounts.token_minter.key(),
+        local_token: accounts.local_token.key(),
+        burn_token_mint: accounts.burn_token_mint.key(),
+        destination_domain,
+        mint_recipient,
+    };
+
+    require!(
+        config.compute_hash(&accounts.boring_account.key()) == accounts.cctp_config.config_hash,
+        CustomError::InvalidConfiguration
+    );
+    Ok(())
+}
+
+/// Burns tokens from the holder through the TokenMessengerMinter
+///
+/// The holder signs as the depositor, and for the message account derived from its message
+/// nonce.
+pub fn execute_deposit_for_burn(
+    accounts: &DepositForBurnContext,
+    message_sent_event_data_bump: u8,
+    destination_domain: u32,
+    mint_recipient: [u8; 32],
+    amount: u64,
+) -> Result<()> {
+    let transfer = BridgeTransfer {
+        destination_domain,
+        recipient: mint_recipient,
+        amount,
+    };
+
+    let boring_account = &accounts.boring_account;
+    let (creator, bump) = boring_account.creator_and_bump()?;
+    let holder_bump = &[bump];
+    let holder_seeds = &[b"boring_state" as &[u8], creator.as_ref(), holder_bump];
+    let boring_account_key = boring_account.key();
+    let nonce_bytes = boring_account.load_state()?.message_nonce.to_le_bytes();
+    let message_bump = &[message_sent_event_data_bump];
+    let message_seeds = &[
+        b"unique_message" as &[u8],
+        boring_account_key.as_ref(),
+        &nonce_bytes,
+        message_bump,
+    ];
+
+    CircleCctp::new(accounts).invoke(&transfer, &[&holder_seeds[..], &message_seeds[..]])
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::solana_program::hash::hash;
+
+    use super::*;
+    use crate::instructions::test_accounts;
+    use crate::BoringCctpConfig;
+
+    fn accounts() -> DepositForBurnContext<'static> {
+        DepositForBurnContext {
+            boring_account: test_accounts::boring_state(),
+            cctp_config: test_accounts::program_account(&BoringCctpConfig {
+                bump: 0,
+                config_hash: [0; 32],
+            }),
+            signer: test_accounts::signer(),
+            sender_authority_pda: test_accounts::unchecked(),
+            boring_account_ata: test_accounts::token_account(),
+            message_transmitter: test_accounts::unchecked(),
+            token_messenger: test_accounts::unchecked(),
+            remote_token_messenger: test_accounts::unchecked(),
+            token_minter: test_accounts::unchecked(),
+            local_token: test_accounts::unchecked(),
+            burn_token_mint: test_accounts::mint(),
+            message_sent_event_data: test_accounts::unchecked(),
+            message_transmitter_program: test_accounts::unchecked(),
+            token_messenger_minter_program: test_accounts::unchecked(),
+            token_program: test_accounts::token_program(),
+            system_program: test_accounts::system_program(),
+            event_authority: test_accounts::unchecked(),
+        }
+    }
+
+    #[test]
+    fn builds_deposit_for_burn_account_metas() {
+        let accounts = accounts();
+        let adapter = CircleCctp::new(&accounts);
+
+        // The accounts of the TokenMessengerMinter's `deposit_for_burn`, as
+        // `(key, is_signer, is_writable)`.
+        let expected = [
+            // owner
+            (accounts.boring_account.key(), true, false),
+            // event_rent_payer
+            (accounts.signer.key(), true, true),
+            // sender_authority_pda
+            (accounts.sender_authority_pda.key(), false, false),
+            // burn_token_account
+            (accounts.boring_account_ata.key(), false, true),
+            // message_transmitter
+            (accounts.message_transmitter.key(), false, true),
+            // token_messenger
+            (accounts.token_messenger.key(), false, false),
+            // remote_token_messenger
+            (accounts.remote_token_messenger.key(), false, false),
+            // token_minter
+            (accounts.token_minter.key(), false, false),
+            // local_token
+            (accounts.local_token.key(), false, true),
+            // burn_token_mint
+            (accounts.burn_token_mint.key(), false, true),
+            // message_sent_event_data
+            (accounts.message_sent_event_data.key(), true, true),
+            // message_transmitter_program
+            (accounts.message_transmitter_program.key(), false, false),
+            // token_messenger_minter_program
+            (accounts.token_messenger_minter_program.key(), false, false),
+            // token_program
+            (accounts.token_program.key(), false, false),
+            // system_program
+            (accounts.system_program.key(), false, false),
+            // event_authority
+            (accounts.event_authority.key(), false, false),
+            // program
+            (accounts.token_messenger_minter_program.key(), false, false),
+        ];
+        let metas = adapter.account_metas();
+        assert_eq!(metas.len(), expected.len());
+        for (meta, (key, is_signer, is_writable)) in metas.iter().zip(expected) {
+            assert_eq!(meta.pubkey, key);
+            assert_eq!(meta.is_signer, is_signer, "{key}");
+            assert_eq!(meta.is_writable, is_writable, "{key}");
+        }
+
+        let infos = adapter.account_infos();
+        assert_eq!(infos.len(), metas.len());
+        for (info, meta) in infos.iter().zip(&metas) {
+            assert_eq!(*info.key, meta.pubkey);
+        }
+        assert_eq!(
+            adapter.program_id(),
+            accounts.token_messenger_minter_program.key()
+        );
+    }
+
+    #[test]
+    fn encodes_deposit_for_burn_data() {
+        assert_eq!(
+            DEPOSIT_FOR_BURN_DISCRIMINATOR,
+            hash(b"global:deposit_for_burn").to_bytes()[..8]
+        );
+
+        let accounts = accounts();
+        let transfer = BridgeTransfer {
+            destination_domain: 3,
+            recipient: [7; 32],
+            amount: 1_000_000,
+        };
+        let data = CircleCctp::new(&accounts)
+            .instruction_data(&transfer)
+            .unwrap();
+
+        assert_eq!(data.len(), 8 + 8 + 4 + 32);
+        assert_eq!(data[..8], DEPOSIT_FOR_BURN_DISCRIMINATOR);
+        assert_eq!(data[8..16], 1_000_000u64.to_le_bytes());
+        assert_eq!(data[16..20], 3u32.to_le_bytes());
+        assert_eq!(data[20..52], [7; 32]);
+    }
+}
@@ -0,0 +1,274 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::keccak;
+use anchor_lang::solana_program::program::{get_return_data, invoke_signed};
+use borsh::BorshSerialize;
+
+use crate::instructions::migrate_state::LoadState;
+use crate::{BoringState, CustomError, DispatchMessageContext, TransferRemoteWithPayloadContext};
+
+/// Index of `OutboxDispatch` in the Hyperlane mailbox's instruction enum
+const OUTBOX_DISPATCH_INSTRUCTION: u8 = 4;
+/// Index of `PayForGas` in the Hyperlane IGP's instruction enum
+const PAY_FOR_GAS_INSTRUCTION: u8 = 3;
+
+/// Offset of the encoded message in a mailbox dispatched message account: the initialized
+/// flag, the `DISPATCH` discriminator, the outbox nonce, the slot and the unique message
+const DISPATCHED_MESSAGE_OFFSET: usize = 1 + 8 + 4 + 8 + 32;
+
+/// Accounts used to dispatch a message from the holder and pay for its gas
+///
+/// Borrowed from each context that dispatches, so `dispatch_message` and the companion message
+/// of `transfer_remote_with_payload` share the same CPIs. `unique_message` is the PDA derived
+/// from `message_nonce`, and `unique_message_bump` its bump.
+pub struct DispatchAccounts<'a, 'info> {
+    pub boring_account: &'a AccountLoader<'info, BoringState>,
+    pub signer: &'a Signer<'info>,
+    pub system_program: &'a Program<'info, System>,
+    pub noop: &'a AccountInfo<'info>,
+    pub mailbox_program: &'a AccountInfo<'info>,
+    pub mailbox_outbox: &'a AccountInfo<'info>,
+    pub unique_message: &'a AccountInfo<'info>,
+    pub unique_message_bump: u8,
+    pub message_nonce: u64,
+    pub message_storage_pda: &'a AccountInfo<'info>,
+    pub igp_program: &'a AccountInfo<'info>,
+    pub igp_program_data: &'a AccountInfo<'info>,
+    pub gas_payment_pda: &'a AccountInfo<'info>,
+    pub igp_account: &'a AccountInfo<'info>,
+    pub token_sender: &'a AccountInfo<'info>,
+}
+
+impl<'info> DispatchMessageContext<'info> {
+    pub fn dispatch_accounts(
+        &self,
+        unique_message_bump: u8,
+        message_nonce: u64,
+    ) -> DispatchAccounts<'_, 'info> {
+        DispatchAccounts {
+            boring_account: &self.boring_account,
+            signer: &self.signer,
+            system_program: &self.system_program,
+            noop: &self.noop,
+            mailbox_program: &self.mailbox_program,
+            mailbox_outbox: &self.mailbox_outbox,
+            unique_message: &self.unique_message,
+            unique_message_bump,
+            message_nonce,
+            message_storage_pda: &self.message_storage_pda,
+            igp_program: &self.igp_program,
+            igp_program_data: &self.igp_program_data,
+            gas_payment_pda: &self.gas_payment_pda,
+            igp_account: &self.igp_account,
+            token_sender: &self.token_sender,
+        }
+    }
+}
+
+impl<'info> TransferRemoteWithPayloadContext<'info> {
+    /// Accounts of the companion message, derived from the nonce after the transfer's
+    pub fn payload_dispatch_accounts(
+        &self,
+        payload_message_bump: u8,
+        payload_message_nonce: u64,
+    ) -> DispatchAccounts<'_, 'info> {
+        DispatchAccounts {
+            boring_account: &self.transfer.boring_account,
+            signer: &self.transfer.signer,
+            system_program: &self.transfer.system_program,
+            noop: &self.transfer.noop,
+            mailbox_program: &self.transfer.mailbox_program,
+            mailbox_outbox: &self.transfer.mailbox_outbox,
+            unique_message: &self.payload_message,
+            unique_message_bump: payload_message_bump,
+            message_nonce: payload_message_nonce,
+            message_storage_pda: &self.payload_message_storage_pda,
+            igp_program: &self.transfer.igp_program,
+            igp_program_data: &self.transfer.igp_program_data,
+            gas_payment_pda: &self.payload_gas_payment_pda,
+            igp_account: &self.transfer.igp_account,
+            token_sender: &self.transfer.token_sender,
+        }
+    }
+}
+
+/// Arguments of the mailbox's `OutboxDispatch`
+#[derive(BorshSerialize)]
+struct OutboxDispatch {
+    /// The address the message is sent from, the holder
+    sender: Pubkey,
+    destination_domain: u32,
+    recipient: [u8; 32],
+    message_body: Vec<u8>,
+}
+
+/// Arguments of the IGP's `PayForGas`
+#[derive(BorshSerialize)]
+struct PayForGas {
+    message_id: [u8; 32],
+    destination_domain: u32,
+    gas_amount: u64,
+}
+
+/// Dispatches a message through the mailbox with the holder as its sender
+///
+/// The holder PDA signs as the sender itself, so the message's sender on the destination is
+/// the holder address.
+///
+/// # Returns
+/// * `Result<[u8; 32]>` - The ID of the dispatched message
+pub fn dispatch(
+    accounts: &DispatchAccounts,
+    destination_domain: u32,
+    recipient: [u8; 32],
+    message_body: Vec<u8>,
+) -> Result<[u8; 32]> {
+    let boring_account = &accounts.boring_account;
+    let boring_account_key = boring_account.key();
+    let mut data = vec![OUTBOX_DISPATCH_INSTRUCTION];
+    data.extend(
+        OutboxDispatch {
+            sender: boring_account_key,
+            destination_domain,
+            recipient,
+            message_body,
+        }
+        .try_to_vec()?,
+    );
+
+    let instruction = Instruction {
+        program_id: accounts.mailbox_program.key(),
+        accounts: vec![
+            AccountMeta::new(accounts.mailbox_outbox.key(), false),
+            AccountMeta::new_readonly(boring_account_key, true),
+            AccountMeta::new_readonly(accounts.system_program.key(), false),
+            AccountMeta::new_readonly(accounts.noop.key(), false),
+            AccountMeta::new(accounts.signer.key(), true),
+            AccountMeta::new_readonly(accounts.unique_message.key(), true),
+            AccountMeta::new(accounts.message_storage_pda.key(), false),
+        ],
+        data,
+    };
+
+    let (creator, bump) = boring_account.creator_and_bump()?;
+    let holder_bump = &[bump];
+    let holder_seeds = &[b"boring_state" as &[u8], creator.as_ref(), holder_bump];
+    let nonce_bytes = accounts.message_nonce.to_le_bytes();
+    let unique_message_bump = &[accounts.unique_message_bump];
+    let unique_message_seeds = &[
+        b"unique_message" as &[u8],
+        boring_account_key.as_ref(),
+        &nonce_bytes,
+        unique_message_bump,
+    ];
+
+    invoke_signed(
+        &instruction,
+        &[
+            // 0. `[writeable]` The outbox.
+            accounts.mailbox_outbox.to_account_info(),
+            // 1. `[signer]` The holder, the message sender.
+            boring_account.to_account_info(),
+            // 2. `[executable]` The system program.
+            accounts.system_program.to_account_info(),
+            // 3. `[executable]` The SPL Noop program.
+            accounts.noop.to_account_info(),
+            // 4. `[signer, writeable]` The payer of the dispatched message PDA's rent.
+            accounts.signer.to_account_info(),
+            // 5. `[signer]` The unique message account.
+            accounts.unique_message.to_account_info(),
+            // 6. `[writeable]` The dispatched message PDA.
+            accounts.message_storage_pda.to_account_info(),
+        ],
+        &[&holder_seeds[..], &unique_message_seeds[..]],
+    )?;
+
+    // The mailbox returns the ID of the dispatched message.
+    match get_return_data() {
+        Some((program_id, data)) if program_id == accounts.mailbox_program.key() => data
+            .get(..32)
+            .and_then(|id| id.try_into().ok())
+            .ok_or_else(|| error!(CustomError::InvalidMessage)),
+        _ => err!(CustomError::InvalidMessage),
+    }
+}
+
+/// Pays the IGP for the destination gas of a dispatched message
+///
+/// The signer pays, and the unique message account doubles as the unique gas payment account.
+/// The configuration's token sender is the inner IGP that is paid, and its IGP account the
+/// overhead IGP wrapping it, as for the warp route's own gas payment.
+pub fn pay_for_gas(
+    accounts: &DispatchAccounts,
+    message_id: [u8; 32],
+    destination_domain: u32,
+    gas_amount: u64,
+) -> Result<()> {
+    let mut data = vec![PAY_FOR_GAS_INSTRUCTION];
+    data.extend(
+        PayForGas {
+            message_id,
+            destination_domain,
+            gas_amount,
+        }
+        .try_to_vec()?,
+    );
+
+    let instruction = Instruction {
+        program_id: accounts.igp_program.key(),
+        accounts: vec![
+            AccountMeta::new_readonly(accounts.system_program.key(), false),
+            AccountMeta::new(accounts.signer.key(), true),
+            AccountMeta::new(accounts.igp_program_data.key(), false),
+            AccountMeta::new_readonly(accounts.unique_message.key(), true),
+            AccountMeta::new(accounts.gas_payment_pda.key(), false),
+            AccountMeta::new(accounts.token_sender.key(), false),
+            AccountMeta::new_readonly(accounts.igp_account.key(), false),
+        ],
+        data,
+    };
+
+    let boring_account_key = accounts.boring_account.key();
+    let nonce_bytes = accounts.message_nonce.to_le_bytes();
+    let unique_message_bump = &[accounts.unique_message_bump];
+    let unique_message_seeds = &[
+        b"unique_message" as &[u8],
+        boring_account_key.as_ref(),
+        &nonce_bytes,
+        unique_message_bump,
+    ];
+
+    Ok(invoke_signed(
+        &instruction,
+        &[
+            // 0. `[executable]` The system program.
+            accounts.system_program.to_account_info(),
+            // 1. `[signer, writeable]` The payer.
+            accounts.signer.to_account_info(),
+            // 2. `[writeable]` The IGP program data.
+            accounts.igp_program_data.to_account_info(),
+            // 3. `[signer]` The unique gas payment account.
+            accounts.unique_message.to_account_info(),
+            // 4. `[writeable]` The gas payment PDA.
+            accounts.gas_payment_pda.to_account_info(),
+            // 5. `[writeable]` The IGP account.
+            accounts.token_sender.to_account_info(),
+            // 6. `[]` The overhead IGP account.
+            accounts.igp_account.to_account_info(),
+        ],
+        &[&unique_message_seeds[..]],
+    )?)
+}
+
+/// Reads the ID of a message from the mailbox's dispatched message account
+///
+/// Used for messages dispatched by another program, such as the warp route, whose return data
+/// is overwritten by its later CPIs.
+pub fn dispatched_message_id(message_storage_pda: &AccountInfo) -> Result<[u8; 32]> {
+    let data = message_storage_pda.try_borrow_data()?;
+    let encoded_message = data
+        .get(DISPATCHED_MESSAGE_OFFSET..)
+        .filter(|message| !message.is_empty())
+        .ok_or(CustomError::InvalidMessage)?;
+    Ok(keccak::hash(encoded_message).to_bytes())
+}
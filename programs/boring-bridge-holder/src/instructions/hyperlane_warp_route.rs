@@ -0,0 +1,245 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::AccountMeta;
+use anchor_spl::token_2022::spl_token_2022;
+use borsh::BorshSerialize;
+
+use crate::instructions::bridge_adapter::{BridgeAdapter, BridgeTransfer};
+use crate::instructions::transfer_remote::TransferRemoteAccounts;
+use crate::u256::U256;
+use crate::{BridgeKind, ConfigurationData, CustomError, TransferRemote};
+
+/// First 8 bytes of every warp route instruction, the `PROGRAM_INSTRUCTION_DISCRIMINATOR`
+const PROGRAM_INSTRUCTION_DISCRIMINATOR: [u8; 8] = [1; 8];
+/// Instruction type of the warp route's `transfer_remote`
+const TRANSFER_REMOTE_INSTRUCTION: u8 = 1;
+
+/// Verifies an amount in the mint's decimals converts to the token's decimals on the destination
+/// chain without losing precision
+///
+/// The warp route scales `amount_or_id` to the remote decimals itself, silently dropping any
+/// remainder, so amounts that would lose dust are rejected before they are sent.
+///
+/// # Errors
+/// * `CustomError::AmountLosesPrecision` - If scaling down would drop a non-zero remainder
+/// * `ProgramError::ArithmeticOverflow` - If scaling up overflows 256 bits
+pub fn verify_remote_amount(amount: u64, local_decimals: u8, remote_decimals: u8) -> Result<()> {
+    if remote_decimals >= local_decimals {
+        let mut remote_amount = U256::from(amount);
+        for _ in local_decimals..remote_decimals {
+            remote_amount = remote_amount
+                .checked_mul_u64(10)
+                .ok_or(ProgramError::ArithmeticOverflow)?;
+        }
+        return Ok(());
+    }
+
+    // Scaling down by more than 19 digits leaves nothing of any non-zero `u64` amount.
+    let Some(scale) = 10u64.checked_pow(u32::from(local_decimals - remote_decimals)) else {
+        require!(amount == 0, CustomError::AmountLosesPrecision);
+        return Ok(());
+    };
+    require!(
+        amount.checked_rem(scale) == Some(0),
+        CustomError::AmountLosesPrecision
+    );
+    Ok(())
+}
+
+/// Builds the data of the warp route's `transfer_remote`
+///
+/// `amount_or_id` carries the amount in the mint's decimals; the warp route scales it to the
+/// remote decimals.
+fn transfer_remote_data(transfer: &BridgeTransfer) -> Result<Vec<u8>> {
+    let transfer_data = TransferRemote {
+        destination_domain: transfer.destination_domain,
+        recipient: transfer.recipient,
+        amount_or_id: U256::from(transfer.amount).to_le_bytes(),
+    };
+
+    let mut data = PROGRAM_INSTRUCTION_DISCRIMINATOR.to_vec();
+    data.push(TRANSFER_REMOTE_INSTRUCTION);
+    data.extend(transfer_data.try_to_vec()?);
+    Ok(data)
+}
+
+/// Adapter transferring through a Hyperlane warp route's `transfer_remote`
+pub struct HyperlaneWarpRoute<'a, 'info> {
+    accounts: &'a TransferRemoteAccounts<'a, 'info>,
+}
+
+impl<'a, 'info> HyperlaneWarpRoute<'a, 'info> {
+    pub fn new(accounts: &'a TransferRemoteAccounts<'a, 'info>) -> Self {
+        Self { accounts }
+    }
+}
+
+impl<'info> BridgeAdapter<'info> for HyperlaneWarpRoute<'_, 'info> {
+    const KIND: BridgeKind = BridgeKind::HyperlaneWarpRoute;
+
+    type Config = ConfigurationData;
+
+    /// Warp routes burn or lock Token-2022 tokens, so the configured token program must be
+    /// Token-2022. The recipient must follow the encoding rules of its format.
+    fn validate_config(config: &ConfigurationData) -> Result<()> {
+        require_keys_eq!(
+            config.token_2022_program,
+            spl_token_2022::ID,
+            CustomError::InvalidConfiguration
+        );
+        config.recipient.validate()
+    }
+
+    fn program_id(&self) -> Pubkey {
+        self.accounts.target_program.key()
+    }
+
+    fn account_metas(&self) -> Vec<AccountMeta> {
+        let accounts = self.accounts;
+        vec![
+            AccountMeta::new_readonly(accounts.system_program.key(), false),
+            AccountMeta::new_readonly(accounts.noop.key(), false),
+            AccountMeta::new_readonly(accounts.token_pda.key(), false),
+            AccountMeta::new_readonly(accounts.mailbox_program.key(), false),
+            AccountMeta::new(accounts.mailbox_outbox.key(), false),
+            AccountMeta::new_readonly(accounts.message_dispatch_authority.key(), false),
+            AccountMeta::new(accounts.signer.key(), true),
+            AccountMeta::new_readonly(accounts.unique_message.key(), true),
+            AccountMeta::new(accounts.message_storage_pda.key(), false),
+            AccountMeta::new_readonly(accounts.igp_program.key(), false),
+            AccountMeta::new(accounts.igp_program_data.key(), false),
+            AccountMeta::new(accounts.gas_payment_pda.key(), false),
+            AccountMeta::new_readonly(accounts.igp_account.key(), false),
+            AccountMeta::new(accounts.token_sender.key(), false),
+            AccountMeta::new_readonly(accounts.token_2022.key(), false),
+            AccountMeta::new(accounts.mint_auth.key(), false),
+            AccountMeta::new(accounts.strategist_ata.key(), false),
+        ]
+    }
+
+    fn account_infos(&self) -> Vec<AccountInfo<'info>> {
+        let accounts = self.accounts;
+        vec![
+            // 0.  `[executable]` The system program.
+            accounts.system_program.to_account_info(),
+            // 1.  `[executable]` The spl_noop program.
+            accounts.noop.to_account_info(),
+            // 2.  `[]` The token PDA account.
+            accounts.token_pda.to_account_info(),
+            // 3.  `[executable]` The mailbox program.
+            accounts.mailbox_program.to_account_info(),
+            // 4.  `[writeable]` The mailbox outbox account.
+            accounts.mailbox_outbox.to_account_info(),
+            // 5.  `[]` Message dispatch authority.
+            accounts.message_dispatch_authority.to_account_info(),
+            // 6.  `[signer]` The token sender and mailbox payer.
+            accounts.signer.to_account_info(),
+            // 7.  `[signer]` Unique message / gas payment account.
+            accounts.unique_message.to_account_info(),
+            // 8.  `[writeable]` Message storage PDA.
+            accounts.message_storage_pda.to_account_info(),
+            //     ---- If using an IGP ----
+            // 9.  `[executable]` The IGP program.
+            accounts.igp_program.to_account_info(),
+            // 10. `[writeable]` The IGP program data.
+            accounts.igp_program_data.to_account_info(),
+            // 11. `[writeable]` Gas payment PDA.
+            accounts.gas_payment_pda.to_account_info(),
+            // 12. `[]` OPTIONAL - The Overhead IGP program, if the configured IGP is an Overhead IGP.
+            accounts.igp_account.to_account_info(),
+            // 13. `[writeable]` The IGP account.
+            accounts.token_sender.to_account_info(),
+            //      ---- End if ----
+            // 14. `[executable]` The spl_token_2022 program.
+            accounts.token_2022.to_account_info(),
+            // 15. `[writeable]` The mint / mint authority PDA account.
+            accounts.mint_auth.to_account_info(),
+            // 16. `[writeable]` The token sender's associated token account, from which tokens will be burned.
+            accounts.strategist_ata.to_account_info(),
+        ]
+    }
+
+    fn instruction_data(&self, transfer: &BridgeTransfer) -> Result<Vec<u8>> {
+        transfer_remote_data(transfer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_transfer_remote_data() {
+        let transfer = BridgeTransfer {
+            destination_domain: 1,
+            recipient: [7; 32],
+            amount: 1_000_000,
+        };
+        let data = transfer_remote_data(&transfer).unwrap();
+
+        assert_eq!(data.len(), 8 + 1 + 4 + 32 + 32);
+        assert_eq!(data[..8], PROGRAM_INSTRUCTION_DISCRIMINATOR);
+        assert_eq!(data[8], TRANSFER_REMOTE_INSTRUCTION);
+        assert_eq!(data[9..13], 1u32.to_le_bytes());
+        assert_eq!(data[13..45], [7; 32]);
+        assert_eq!(data[45..77], U256::from(1_000_000u64).to_le_bytes());
+    }
+
+    #[test]
+    fn accepts_equal_decimals() {
+        assert!(verify_remote_amount(123_456_789, 6, 6).is_ok());
+    }
+
+    #[test]
+    fn synthetic_route_accepts_more_remote_decimals() {
+        // A 9 decimal Solana token bridged to an 18 decimal synthetic.
+        assert!(verify_remote_amount(1_500_000_000, 9, 18).is_ok());
+    }
+
+    #[test]
+    fn scaling_up_accepts_amounts_beyond_64_bits() {
+        assert!(verify_remote_amount(u64::MAX, 0, 18).is_ok());
+
+        // u64::MAX * 10^40 needs more than 128 bits but fits in 256 bits.
+        assert!(verify_remote_amount(u64::MAX, 0, 40).is_ok());
+    }
+
+    #[test]
+    fn scaling_up_rejects_overflow() {
+        // 10^78 exceeds 2^256.
+        assert!(verify_remote_amount(1, 0, 78).is_err());
+        assert!(verify_remote_amount(0, 0, 78).is_ok());
+    }
+
+    #[test]
+    fn scaling_down_accepts_exact_amounts() {
+        // A 9 decimal Solana token bridged to a 6 decimal collateral.
+        assert!(verify_remote_amount(2_000_000_000, 9, 6).is_ok());
+    }
+
+    #[test]
+    fn scaling_down_rejects_dust() {
+        assert_eq!(
+            verify_remote_amount(2_000_000_001, 9, 6).unwrap_err(),
+            CustomError::AmountLosesPrecision.into()
+        );
+        assert_eq!(
+            verify_remote_amount(1, 20, 0).unwrap_err(),
+            CustomError::AmountLosesPrecision.into()
+        );
+        assert!(verify_remote_amount(0, 20, 0).is_ok());
+    }
+
+    #[test]
+    fn scaling_down_beyond_64_bits_rejects_any_amount() {
+        // 10^20 doesn't fit in a `u64`, so even the largest amount is all dust.
+        assert_eq!(
+            verify_remote_amount(u64::MAX, 20, 0).unwrap_err(),
+            CustomError::AmountLosesPrecision.into()
+        );
+        assert_eq!(
+            verify_remote_amount(u64::MAX, 255, 0).unwrap_err(),
+            CustomError::AmountLosesPrecision.into()
+        );
+        assert!(verify_remote_amount(0, 255, 0).is_ok());
+    }
+}
@@ -0,0 +1,126 @@
+use std::collections::HashMap;
+
+use anchor_lang::prelude::*;
+use borsh::BorshDeserialize;
+
+use crate::u256::U256;
+use crate::CustomError;
+
+/// Discriminator of Hyperlane IGP accounts
+const IGP_DISCRIMINATOR: [u8; 8] = *b"IGP_____";
+/// Discriminator of Hyperlane overhead IGP accounts
+const OVERHEAD_IGP_DISCRIMINATOR: [u8; 8] = *b"OVRHDIGP";
+/// Scale of the token exchange rates stored in IGP gas oracles
+const TOKEN_EXCHANGE_RATE_SCALE: u64 = 10_000_000_000_000_000_000;
+/// Decimals of the native token gas payments are made in
+const LOCAL_DECIMALS: u8 = 9;
+
+/// Gas oracle data of a remote domain, as stored by the Hyperlane IGP
+#[derive(BorshDeserialize)]
+struct RemoteGasData {
+    token_exchange_rate: u128,
+    gas_price: u128,
+    token_decimals: u8,
+}
+
+#[derive(BorshDeserialize)]
+enum GasOracle {
+    RemoteGasData(RemoteGasData),
+}
+
+/// Hyperlane IGP account
+#[derive(BorshDeserialize)]
+struct Igp {
+    _bump_seed: u8,
+    _salt: [u8; 32],
+    _owner: Option<Pubkey>,
+    _beneficiary: Pubkey,
+    gas_oracles: HashMap<u32, GasOracle>,
+}
+
+/// Hyperlane overhead IGP account, which adds a per-domain gas overhead to an inner IGP
+#[derive(BorshDeserialize)]
+struct OverheadIgp {
+    _bump_seed: u8,
+    _salt: [u8; 32],
+    _owner: Option<Pubkey>,
+    inner: Pubkey,
+    gas_overheads: HashMap<u32, u64>,
+}
+
+/// Decodes an initialized, discriminator prefixed Hyperlane account
+fn decode<T: BorshDeserialize>(account: &AccountInfo, discriminator: &[u8; 8]) -> Result<T> {
+    let data = account.try_borrow_data()?;
+    let mut buf: &[u8] = &data;
+    let initialized =
+        bool::deserialize(&mut buf).map_err(|_| error!(CustomError::InvalidIgpAccount))?;
+    require!(
+        initialized && buf.len() >= 8 && buf[..8] == discriminator[..],
+        CustomError::InvalidIgpAccount
+    );
+    buf = &buf[8..];
+    T::deserialize(&mut buf).map_err(|_| error!(CustomError::InvalidIgpAccount))
+}
+
+/// Estimates the gas payment the IGP charges for a message
+///
+/// Mirrors the IGP's own quote: the destination gas cost is converted with the oracle's
+/// exchange rate and scaled from the remote token's decimals to the local decimals.
+///
+/// # Arguments
+/// * `igp` - The IGP account pricing the payment
+/// * `overhead_igp` - The overhead IGP wrapping `igp`, if any
+/// * `destination_domain` - The domain ID of the destination chain
+/// * `gas_amount` - The destination gas amount of the message, before any overhead
+///
+/// # Errors
+/// * `CustomError::InvalidIgpAccount` - If the IGP accounts cannot be decoded or have no gas
+///   oracle for the destination domain
+/// * `CustomError::GasQuoteOverflow` - If the payment overflows 256 bits or exceeds a `u64`
+///
+/// # Returns
+/// * `Result<u64>` - The estimated gas payment in lamports
+pub fn quote_gas_payment(
+    igp: &AccountInfo,
+    overhead_igp: Option<&AccountInfo>,
+    destination_domain: u32,
+    gas_amount: u64,
+) -> Result<u64> {
+    let mut gas_amount = gas_amount;
+    if let Some(overhead_igp) = overhead_igp {
+        let overhead: OverheadIgp = decode(overhead_igp, &OVERHEAD_IGP_DISCRIMINATOR)?;
+        require_keys_eq!(overhead.inner, igp.key(), CustomError::InvalidIgpAccount);
+        let overhead_amount = overhead
+            .gas_overheads
+            .get(&destination_domain)
+            .copied()
+            .unwrap_or_default();
+        gas_amount = gas_amount.saturating_add(overhead_amount);
+    }
+
+    let igp: Igp = decode(igp, &IGP_DISCRIMINATOR)?;
+    let GasOracle::RemoteGasData(remote) = igp
+        .gas_oracles
+        .get(&destination_domain)
+        .ok_or(CustomError::InvalidIgpAccount)?;
+
+    // Computed in 256 bits like the IGP itself, as the product overflows 128 bits at
+    // realistic gas prices and exchange rates.
+    let origin_cost = U256::from(gas_amount)
+        .checked_mul(U256::from(remote.gas_price))
+        .and_then(|cost| cost.checked_mul(U256::from(remote.token_exchange_rate)))
+        .and_then(|cost| cost.checked_div_u64(TOKEN_EXCHANGE_RATE_SCALE))
+        .and_then(|cost| convert_decimals(cost, remote.token_decimals, LOCAL_DECIMALS))
+        .ok_or(CustomError::GasQuoteOverflow)?;
+
+    u64::try_from(origin_cost).map_err(|_| error!(CustomError::GasQuoteOverflow))
+}
+
+/// Converts an amount between decimal precisions, rounding down
+fn convert_decimals(amount: U256, from_decimals: u8, to_decimals: u8) -> Option<U256> {
+    if from_decimals > to_decimals {
+        (to_decimals..from_decimals).try_fold(amount, |amount, _| amount.checked_div_u64(10))
+    } else {
+        (from_decimals..to_decimals).try_fold(amount, |amount, _| amount.checked_mul_u64(10))
+    }
+}
@@ -0,0 +1,317 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::AccountMeta;
+use borsh::BorshSerialize;
+
+use crate::instructions::bridge_adapter::{BridgeAdapter, BridgeTransfer};
+use crate::instructions::migrate_state::LoadState;
+use crate::{BridgeKind, CustomError, OftConfigurationData, TransferRemoteOftContext};
+
+/// `sha256("global:send")[..8]`, the Anchor discriminator of the OFT program's `send`
+const SEND_DISCRIMINATOR: [u8; 8] = [102, 251, 20, 187, 65, 75, 12, 69];
+
+/// Arguments of the OFT program's `send`
+#[derive(BorshSerialize)]
+struct SendParams {
+    /// The LayerZero endpoint ID of the destination chain
+    dst_eid: u32,
+    to: [u8; 32],
+    amount_ld: u64,
+    min_amount_ld: u64,
+    /// Executor options, such as the gas limit of the `lzReceive` call on the destination
+    options: Vec<u8>,
+    compose_msg: Option<Vec<u8>>,
+    native_fee: u64,
+    lz_token_fee: u64,
+}
+
+/// Strategist-chosen parameters of an OFT send that are not part of the configuration
+pub struct OftSendOptions {
+    /// The least amount received on the destination, after the OFT removes dust
+    pub min_amount: u64,
+    /// Executor options passed to the endpoint
+    pub options: Vec<u8>,
+    /// The native fee quoted with the OFT program's `quote_send`
+    pub native_fee: u64,
+}
+
+/// Adapter sending tokens through a LayerZero V2 OFT program's `send`
+///
+/// The holder signs as the OFT sender and owner of the source token account. The endpoint
+/// program and the accounts its `send` needs follow the OFT accounts, and are passed through
+/// with the signer and writable flags they were given, so the strategist pays the native fee.
+pub struct LayerZeroOft<'a, 'info> {
+    accounts: &'a TransferRemoteOftContext<'info>,
+    endpoint_accounts: &'a [AccountInfo<'info>],
+    send_options: OftSendOptions,
+}
+
+impl<'a, 'info> LayerZeroOft<'a, 'info> {
+    pub fn new(
+        accounts: &'a TransferRemoteOftContext<'info>,
+        endpoint_accounts: &'a [AccountInfo<'info>],
+        send_options: OftSendOptions,
+    ) -> Self {
+        Self {
+            accounts,
+            endpoint_accounts,
+            send_options,
+        }
+    }
+}
+
+impl<'info> BridgeAdapter<'info> for LayerZeroOft<'_, 'info> {
+    const KIND: BridgeKind = BridgeKind::LayerZeroOft;
+
+    type Config = OftConfigurationData;
+
+    /// Tokens sent to the zero address can't be recovered, so a recipient must be set.
+    fn validate_config(config: &OftConfigurationData) -> Result<()> {
+        require!(config.to != [0; 32], CustomError::InvalidConfiguration);
+        Ok(())
+    }
+
+    fn program_id(&self) -> Pubkey {
+        self.accounts.oft_program.key()
+    }
+
+    fn account_metas(&self) -> Vec<AccountMeta> {
+        let accounts = self.accounts;
+        let mut metas = vec![
+            AccountMeta::new_readonly(accounts.boring_account.key(), true),
+            AccountMeta::new(accounts.peer.key(), false),
+            AccountMeta::new(accounts.oft_store.key(), false),
+            AccountMeta::new(accounts.boring_account_ata.key(), false),
+            AccountMeta::new(accounts.token_escrow.key(), false),
+            AccountMeta::new(accounts.token_mint.key(), false),
+            AccountMeta::new_readonly(accounts.token_program.key(), false),
+            AccountMeta::new_readonly(accounts.event_authority.key(), false),
+            AccountMeta::new_readonly(accounts.oft_program.key(), false),
+            AccountMeta::new_readonly(accounts.endpoint_program.key(), false),
+        ];
+        metas.extend(self.endpoint_accounts.iter().map(|account| AccountMeta {
+            pubkey: account.key(),
+            is_signer: account.is_signer,
+            is_writable: account.is_writable,
+        }));
+        metas
+    }
+
+    fn account_infos(&self) -> Vec<AccountInfo<'info>> {
+        let accounts = self.accounts;
+        let mut infos = vec![
+            // 0. `[signer]` The holder, sending the tokens.
+            accounts.boring_account.to_account_info(),
+            // 1. `[writeable]` The OFT peer of the destination endpoint.
+            accounts.peer.to_account_info(),
+            // 2. `[writeable]` The OFT store.
+            accounts.oft_store.to_account_info(),
+            // 3. `[writeable]` The token account tokens are sent from.
+            accounts.boring_account_ata.to_account_info(),
+            // 4. `[writeable]` The OFT's token escrow.
+            accounts.token_escrow.to_account_info(),
+            // 5. `[writeable]` The mint of the sent token.
+            accounts.token_mint.to_account_info(),
+            // 6. `[executable]` The token program of the mint.
+            accounts.token_program.to_account_info(),
+            // 7. `[]` The OFT program's event authority.
+            accounts.event_authority.to_account_info(),
+            // 8. `[executable]` The OFT program.
+            accounts.oft_program.to_account_info(),
+            // 9. `[executable]` The LayerZero endpoint program.
+            accounts.endpoint_program.to_account_info(),
+        ];
+        // 10..N. The accounts of the endpoint's `send`.
+        infos.extend(self.endpoint_accounts.iter().cloned());
+        infos
+    }
+
+    fn instruction_data(&self, transfer: &BridgeTransfer) -> Result<Vec<u8>> {
+        let params = SendParams {
+            dst_eid: transfer.destination_domain,
+            to: transfer.recipient,
+            amount_ld: transfer.amount,
+            min_amount_ld: self.send_options.min_amount,
+            options: self.send_options.options.clone(),
+            compose_msg: None,
+            native_fee: self.send_options.native_fee,
+            // Fees are always paid in the native token.
+            lz_token_fee: 0,
+        };
+        let mut data = SEND_DISCRIMINATOR.to_vec();
+        data.extend(params.try_to_vec()?);
+        Ok(data)
+    }
+}
+
+/// Verifies the OFT configuration hash by hashing all inputs and comparing the result to the
+/// stored OFT configuration hash
+pub fn verify_configuration(
+    accounts: &TransferRemoteOftContext,
+    dst_eid: u32,
+    to: [u8; 32],
+) -> Result<()> {
+    let config = OftConfigurationData {
+        oft_program: accounts.oft_program.key(),
+        endpoint_program: accounts.endpoint_program.key(),
+        oft_store: accounts.oft_store.key(),
+        peer: accounts.peer.key(),
+        token_escrow: accounts.token_escrow.key(),
+        token_mint: accounts.token_mint.key(),
+        dst_eid,
+        to,
+    };
+
+    require!(
+        config.compute_hash(&accounts.boring_account.key()) == accounts.oft_config.config_hash,
+        CustomError::InvalidConfiguration
+    );
+    Ok(())
+}
+
+/// Sends tokens from the holder through the OFT program, signed by the holder
+pub fn execute_send<'info>(
+    accounts: &TransferRemoteOftContext<'info>,
+    endpoint_accounts: &[AccountInfo<'info>],
+    dst_eid: u32,
+    to: [u8; 32],
+    amount: u64,
+    send_options: OftSendOptions,
+) -> Result<()> {
+    let transfer = BridgeTransfer {
+        destination_domain: dst_eid,
+        recipient: to,
+        amount,
+    };
+
+    let boring_account = &accounts.boring_account;
+    let (creator, bump) = boring_account.creator_and_bump()?;
+    let holder_bump = &[bump];
+    let holder_seeds = &[b"boring_state" as &[u8], creator.as_ref(), holder_bump];
+
+    LayerZeroOft::new(accounts, endpoint_accounts, send_options)
+        .invoke(&transfer, &[&holder_seeds[..]])
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::solana_program::hash::hash;
+
+    use super::*;
+    use crate::instructions::test_accounts;
+    use crate::BoringOftConfig;
+
+    fn accounts() -> TransferRemoteOftContext<'static> {
+        TransferRemoteOftContext {
+            boring_account: test_accounts::boring_state(),
+            oft_config: test_accounts::program_account(&BoringOftConfig {
+                bump: 0,
+                config_hash: [0; 32],
+            }),
+            signer: test_accounts::signer(),
+            oft_program: test_accounts::unchecked(),
+            endpoint_program: test_accounts::unchecked(),
+            peer: test_accounts::unchecked(),
+            oft_store: test_accounts::unchecked(),
+            boring_account_ata: test_accounts::token_account(),
+            token_escrow: test_accounts::unchecked(),
+            token_mint: test_accounts::mint(),
+            token_program: test_accounts::token_program(),
+            event_authority: test_accounts::unchecked(),
+        }
+    }
+
+    fn send_options() -> OftSendOptions {
+        OftSendOptions {
+            min_amount: 999_000,
+            options: vec![1, 2, 3],
+            native_fee: 5_000,
+        }
+    }
+
+    #[test]
+    fn builds_send_account_metas_and_passes_endpoint_accounts_through() {
+        let accounts = accounts();
+        let endpoint_accounts = [
+            accounts.signer.to_account_info(),
+            AccountInfo {
+                is_writable: true,
+                ..test_accounts::unchecked()
+            },
+            test_accounts::unchecked(),
+        ];
+        let adapter = LayerZeroOft::new(&accounts, &endpoint_accounts, send_options());
+
+        // The accounts of the OFT program's `send`, as `(key, is_signer, is_writable)`,
+        // followed by those of the endpoint's `send` with the flags they were given.
+        let expected = [
+            // signer
+            (accounts.boring_account.key(), true, false),
+            // peer
+            (accounts.peer.key(), false, true),
+            // oft_store
+            (accounts.oft_store.key(), false, true),
+            // token_source
+            (accounts.boring_account_ata.key(), false, true),
+            // token_escrow
+            (accounts.token_escrow.key(), false, true),
+            // token_mint
+            (accounts.token_mint.key(), false, true),
+            // token_program
+            (accounts.token_program.key(), false, false),
+            // event_authority
+            (accounts.event_authority.key(), false, false),
+            // program
+            (accounts.oft_program.key(), false, false),
+            // endpoint program
+            (accounts.endpoint_program.key(), false, false),
+            // endpoint `send` accounts
+            (endpoint_accounts[0].key(), true, false),
+            (endpoint_accounts[1].key(), false, true),
+            (endpoint_accounts[2].key(), false, false),
+        ];
+        let metas = adapter.account_metas();
+        assert_eq!(metas.len(), expected.len());
+        for (meta, (key, is_signer, is_writable)) in metas.iter().zip(expected) {
+            assert_eq!(meta.pubkey, key);
+            assert_eq!(meta.is_signer, is_signer, "{key}");
+            assert_eq!(meta.is_writable, is_writable, "{key}");
+        }
+
+        let infos = adapter.account_infos();
+        assert_eq!(infos.len(), metas.len());
+        for (info, meta) in infos.iter().zip(&metas) {
+            assert_eq!(*info.key, meta.pubkey);
+        }
+        assert_eq!(adapter.program_id(), accounts.oft_program.key());
+    }
+
+    #[test]
+    fn encodes_send_data() {
+        assert_eq!(SEND_DISCRIMINATOR, hash(b"global:send").to_bytes()[..8]);
+
+        let accounts = accounts();
+        let transfer = BridgeTransfer {
+            destination_domain: 30101,
+            recipient: [7; 32],
+            amount: 1_000_000,
+        };
+        let data = LayerZeroOft::new(&accounts, &[], send_options())
+            .instruction_data(&transfer)
+            .unwrap();
+
+        let mut expected = SEND_DISCRIMINATOR.to_vec();
+        expected.extend(30101u32.to_le_bytes());
+        expected.extend([7; 32]);
+        expected.extend(1_000_000u64.to_le_bytes());
+        expected.extend(999_000u64.to_le_bytes());
+        // `options` as a length-prefixed byte vector
+        expected.extend(3u32.to_le_bytes());
+        expected.extend([1, 2, 3]);
+        // No `compose_msg`
+        expected.push(0);
+        expected.extend(5_000u64.to_le_bytes());
+        // No LayerZero token fee
+        expected.extend(0u64.to_le_bytes());
+        assert_eq!(data, expected);
+    }
+}
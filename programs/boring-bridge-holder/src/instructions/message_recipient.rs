@@ -0,0 +1,415 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::set_return_data;
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use anchor_spl::token_2022::ID as TOKEN_2022_PROGRAM_ID;
+use anchor_spl::token_interface::TokenAccount;
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::events::{OwnershipTransferred, StrategistUpdated, TokensReceived};
+use crate::instructions::bridge_adapter;
+use crate::instructions::migrate_state::LoadState;
+use crate::{
+    emit_configuration_updated, BoringConfig, BoringInbound, BoringState, ConfigurationData,
+    CustomError, Governor, RecipientIsm,
+};
+
+/// `sha256("hyperlane-message-recipient:interchain-security-module")[..8]`
+const INTERCHAIN_SECURITY_MODULE_DISCRIMINATOR: [u8; 8] = [45, 18, 245, 87, 234, 46, 246, 15];
+/// `sha256("hyperlane-message-recipient:interchain-security-module-account-metas")[..8]`
+const INTERCHAIN_SECURITY_MODULE_ACCOUNT_METAS_DISCRIMINATOR: [u8; 8] =
+    [190, 214, 218, 129, 67, 97, 4, 76];
+/// `sha256("hyperlane-message-recipient:handle")[..8]`
+const HANDLE_DISCRIMINATOR: [u8; 8] = [33, 210, 5, 66, 196, 212, 239, 142];
+/// `sha256("hyperlane-message-recipient:handle-account-metas")[..8]`
+const HANDLE_ACCOUNT_METAS_DISCRIMINATOR: [u8; 8] = [194, 141, 30, 82, 241, 41, 169, 52];
+
+/// Kind byte of a message reporting tokens sent to a holder
+const TOKENS_SENT: u8 = 0;
+/// Kind byte of a governance message updating a holder's configuration
+const UPDATE_CONFIGURATION: u8 = 1;
+/// Kind byte of a governance message updating a holder's strategist
+const UPDATE_STRATEGIST: u8 = 2;
+/// Kind byte of a governance message transferring a holder's ownership
+const TRANSFER_OWNERSHIP: u8 = 3;
+
+/// A message delivered by the Hyperlane mailbox
+#[derive(BorshDeserialize)]
+struct HandleInstruction {
+    origin: u32,
+    sender: [u8; 32],
+    message: Vec<u8>,
+}
+
+/// An account meta in the layout Hyperlane relayers expect
+#[derive(BorshSerialize)]
+struct SerializableAccountMeta {
+    pubkey: Pubkey,
+    is_signer: bool,
+    is_writable: bool,
+}
+
+/// Return data of an instruction simulated by Hyperlane relayers
+///
+/// The trailing byte stops the runtime from truncating trailing zeros of the return data.
+#[derive(BorshSerialize)]
+struct SimulationReturnData<T> {
+    return_data: T,
+    trailing_byte: u8,
+}
+
+/// Sets the return data in the layout Hyperlane relayers and the mailbox expect
+fn set_simulation_return_data<T: BorshSerialize>(return_data: T) -> Result<()> {
+    let data = SimulationReturnData {
+        return_data,
+        trailing_byte: u8::MAX,
+    }
+    .try_to_vec()?;
+    set_return_data(&data);
+    Ok(())
+}
+
+/// A message sent to a holder from the EVM side
+///
+/// Messages are packed as a kind byte, the 32-byte holder address and the kind's fields:
+/// * `0` - Tokens sent: the big-endian `u64` amount and the Token-2022 mint of the tokens
+/// * `1` - Update configuration: the Borsh-encoded `ConfigurationData`
+/// * `2` - Update strategist: the new strategist
+/// * `3` - Transfer ownership: the new owner
+///
+/// All kinds but tokens sent are governance messages, accepted only from the holder's governor.
+/// Messages don't carry arbitrary instructions: the holder only executes the owner actions above.
+enum InboundMessage {
+    TokensSent {
+        boring_account: Pubkey,
+        amount: u64,
+        mint: Pubkey,
+    },
+    UpdateConfiguration {
+        boring_account: Pubkey,
+        config: Box<ConfigurationData>,
+    },
+    UpdateStrategist {
+        boring_account: Pubkey,
+        new_strategist: Pubkey,
+    },
+    TransferOwnership {
+        boring_account: Pubkey,
+        new_owner: Pubkey,
+    },
+}
+
+impl InboundMessage {
+    fn parse(message: &[u8]) -> Result<Self> {
+        let (&kind, body) = message.split_first().ok_or(CustomError::InvalidMessage)?;
+        require!(body.len() >= 32, CustomError::InvalidMessage);
+        let (boring_account, body) = body.split_at(32);
+        let boring_account = Pubkey::try_from(boring_account).unwrap();
+        match kind {
+            TOKENS_SENT => {
+                require!(body.len() == 8 + 32, CustomError::InvalidMessage);
+                let (amount, mint) = body.split_at(8);
+                Ok(Self::TokensSent {
+                    boring_account,
+                    amount: u64::from_be_bytes(amount.try_into().unwrap()),
+                    mint: parse_pubkey(mint)?,
+                })
+            }
+            UPDATE_CONFIGURATION => {
+                let config = ConfigurationData::try_from_slice(body)
+                    .map(Box::new)
+                    .map_err(|_| error!(CustomError::InvalidMessage))?;
+                Ok(Self::UpdateConfiguration {
+                    boring_account,
+                    config,
+                })
+            }
+            UPDATE_STRATEGIST => Ok(Self::UpdateStrategist {
+                boring_account,
+                new_strategist: parse_pubkey(body)?,
+            }),
+            TRANSFER_OWNERSHIP => Ok(Self::TransferOwnership {
+                boring_account,
+                new_owner: parse_pubkey(body)?,
+            }),
+            _ => err!(CustomError::InvalidMessage),
+        }
+    }
+
+    /// The holder the message is addressed to
+    fn boring_account(&self) -> Pubkey {
+        match self {
+            Self::TokensSent { boring_account, .. }
+            | Self::UpdateConfiguration { boring_account, .. }
+            | Self::UpdateStrategist { boring_account, .. }
+            | Self::TransferOwnership { boring_account, .. } => *boring_account,
+        }
+    }
+
+    /// Whether the message executes an owner action
+    fn is_governance(&self) -> bool {
+        !matches!(self, Self::TokensSent { .. })
+    }
+}
+
+fn parse_pubkey(body: &[u8]) -> Result<Pubkey> {
+    Pubkey::try_from(body).map_err(|_| error!(CustomError::InvalidMessage))
+}
+
+/// The holder's token account that tokens of a tokens sent message are delivered to
+fn holder_ata(boring_account: &Pubkey, mint: &Pubkey) -> Pubkey {
+    get_associated_token_address_with_program_id(boring_account, mint, &TOKEN_2022_PROGRAM_ID)
+}
+
+/// The holder's `BoringConfig` PDA, updated by configuration updates if it is initialized
+fn stored_config(boring_account: &Pubkey, program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"boring_config", boring_account.as_ref()], program_id).0
+}
+
+/// Reads the ISM configured with `set_recipient_ism`, or `None` for the mailbox's default
+fn read_recipient_ism<'info>(
+    recipient_ism: &'info AccountInfo<'info>,
+    program_id: &Pubkey,
+) -> Result<Option<Pubkey>> {
+    let (expected, _) = Pubkey::find_program_address(&[b"recipient_ism"], program_id);
+    require_keys_eq!(recipient_ism.key(), expected, CustomError::InvalidMessage);
+
+    if recipient_ism.data_is_empty() {
+        Ok(None)
+    } else {
+        Ok(Account::<RecipientIsm>::try_from(recipient_ism)?.ism)
+    }
+}
+
+/// Dispatches an instruction of the Hyperlane message recipient interface
+pub fn process<'info>(
+    program_id: &Pubkey,
+    accounts: &'info [AccountInfo<'info>],
+    data: &[u8],
+) -> Result<()> {
+    require!(data.len() >= 8, ErrorCode::InstructionFallbackNotFound);
+    let (discriminator, mut data) = data.split_at(8);
+    match <[u8; 8]>::try_from(discriminator).unwrap() {
+        INTERCHAIN_SECURITY_MODULE_DISCRIMINATOR => {
+            interchain_security_module(program_id, accounts)
+        }
+        INTERCHAIN_SECURITY_MODULE_ACCOUNT_METAS_DISCRIMINATOR => {
+            interchain_security_module_account_metas(program_id)
+        }
+        HANDLE_DISCRIMINATOR => handle(program_id, accounts, &deserialize(&mut data)?),
+        HANDLE_ACCOUNT_METAS_DISCRIMINATOR => {
+            handle_account_metas(program_id, &deserialize(&mut data)?)
+        }
+        _ => err!(ErrorCode::InstructionFallbackNotFound),
+    }
+}
+
+fn deserialize(data: &mut &[u8]) -> Result<HandleInstruction> {
+    HandleInstruction::deserialize(data)
+        .map_err(|_| error!(ErrorCode::InstructionDidNotDeserialize))
+}
+
+/// Returns the ISM that verifies messages to this program, or `None` for the mailbox's default
+///
+/// Accounts:
+/// 0. `[]` The recipient ISM PDA, which may be uninitialized.
+fn interchain_security_module<'info>(
+    program_id: &Pubkey,
+    accounts: &'info [AccountInfo<'info>],
+) -> Result<()> {
+    let recipient_ism = accounts.first().ok_or(ErrorCode::AccountNotEnoughKeys)?;
+    set_simulation_return_data(read_recipient_ism(recipient_ism, program_id)?)
+}
+
+/// Returns the accounts of `interchain_security_module`
+fn interchain_security_module_account_metas(program_id: &Pubkey) -> Result<()> {
+    let (recipient_ism, _) = Pubkey::find_program_address(&[b"recipient_ism"], program_id);
+    set_simulation_return_data(vec![SerializableAccountMeta {
+        pubkey: recipient_ism,
+        is_signer: false,
+        is_writable: false,
+    }])
+}
+
+/// Returns the accounts of `handle` following the mailbox's process authority
+fn handle_account_metas(program_id: &Pubkey, instruction: &HandleInstruction) -> Result<()> {
+    let message = InboundMessage::parse(&instruction.message)?;
+    let boring_account = message.boring_account();
+    let (inbound, _) =
+        Pubkey::find_program_address(&[b"boring_inbound", boring_account.as_ref()], program_id);
+    let (recipient_ism, _) = Pubkey::find_program_address(&[b"recipient_ism"], program_id);
+
+    let mut account_metas = vec![
+        SerializableAccountMeta {
+            pubkey: boring_account,
+            is_signer: false,
+            // Governance messages update the holder's state.
+            is_writable: message.is_governance(),
+        },
+        SerializableAccountMeta {
+            pubkey: inbound,
+            is_signer: false,
+            is_writable: false,
+        },
+        SerializableAccountMeta {
+            pubkey: recipient_ism,
+            is_signer: false,
+            is_writable: false,
+        },
+    ];
+    match message {
+        InboundMessage::TokensSent { mint, .. } => account_metas.push(SerializableAccountMeta {
+            pubkey: holder_ata(&boring_account, &mint),
+            is_signer: false,
+            is_writable: false,
+        }),
+        InboundMessage::UpdateConfiguration { .. } => account_metas.push(SerializableAccountMeta {
+            pubkey: stored_config(&boring_account, program_id),
+            is_signer: false,
+            is_writable: true,
+        }),
+        _ => {}
+    }
+    set_simulation_return_data(account_metas)
+}
+
+/// Handles a message delivered by the holder's mailbox
+///
+/// Accounts:
+/// 0. `[signer]` The mailbox's process authority for this program.
+/// 1. `[writeable]` The holder the message is addressed to, read-only unless the message is a
+///    governance message.
+/// 2. `[]` The holder's inbound configuration.
+/// 3. `[]` The recipient ISM PDA, which may be uninitialized.
+/// 4. `[]` For tokens sent messages, the holder's token account of the sent mint.
+///    `[writeable]` For configuration updates, the holder's `BoringConfig` PDA, which may be
+///    uninitialized.
+fn handle<'info>(
+    program_id: &Pubkey,
+    accounts: &'info [AccountInfo<'info>],
+    instruction: &HandleInstruction,
+) -> Result<()> {
+    require!(accounts.len() >= 4, ErrorCode::AccountNotEnoughKeys);
+    let message = InboundMessage::parse(&instruction.message)?;
+
+    let boring_account = AccountLoader::<BoringState>::try_from(&accounts[1])?;
+    let boring_account_key = boring_account.key();
+    require_keys_eq!(
+        boring_account_key,
+        message.boring_account(),
+        CustomError::InvalidMessage
+    );
+
+    let inbound = Account::<BoringInbound>::try_from(&accounts[2])?;
+    let expected_inbound = Pubkey::create_program_address(
+        &[
+            b"boring_inbound",
+            boring_account_key.as_ref(),
+            &[inbound.bump],
+        ],
+        program_id,
+    )
+    .map_err(|_| error!(CustomError::InvalidMessage))?;
+    require_keys_eq!(inbound.key(), expected_inbound, CustomError::InvalidMessage);
+
+    // Only the holder's mailbox can deliver messages.
+    let process_authority = &accounts[0];
+    let (expected_process_authority, _) = Pubkey::find_program_address(
+        &[
+            b"hyperlane",
+            b"-",
+            b"process_authority",
+            b"-",
+            program_id.as_ref(),
+        ],
+        &inbound.mailbox,
+    );
+    require!(
+        process_authority.is_signer && process_authority.key() == expected_process_authority,
+        CustomError::Unauthorized
+    );
+
+    // The mailbox verified the message with the program's ISM, which the holder's owner must
+    // have chosen for the holder.
+    let recipient_ism = read_recipient_ism(&accounts[3], program_id)?;
+    require!(recipient_ism == inbound.ism, CustomError::IsmNotApproved);
+
+    // Governance messages must come from the holder's governor, all others from an allowed
+    // sender.
+    let is_governance = message.is_governance();
+    if is_governance {
+        require!(
+            Governor::from(boring_account.load_state()?.governor)
+                .is_governor(instruction.origin, &instruction.sender),
+            CustomError::UnauthorizedSender
+        );
+    } else {
+        require!(
+            inbound.is_allowed_sender(instruction.origin, &instruction.sender),
+            CustomError::UnauthorizedSender
+        );
+    }
+
+    match message {
+        InboundMessage::TokensSent {
+            boring_account,
+            amount,
+            mint,
+        } => {
+            // The holder's token account must cover the amount. Tokens it already held count
+            // towards the balance, so this only rejects reports the account can't back; the
+            // event carries the balance for indexers to reconcile with the warp route transfer.
+            let holder_ata_info = accounts.get(4).ok_or(ErrorCode::AccountNotEnoughKeys)?;
+            require_keys_eq!(
+                holder_ata_info.key(),
+                holder_ata(&boring_account, &mint),
+                CustomError::InvalidMessage
+            );
+            let balance = InterfaceAccount::<TokenAccount>::try_from(holder_ata_info)?.amount;
+            require!(balance >= amount, CustomError::TokensNotReceived);
+
+            emit!(TokensReceived {
+                boring_account,
+                origin: instruction.origin,
+                sender: instruction.sender,
+                mint,
+                amount,
+                balance,
+            });
+        }
+        InboundMessage::UpdateConfiguration { config, .. } => {
+            bridge_adapter::validate_config(&config)?;
+            let config_hash = config.compute_hash(&boring_account_key);
+            boring_account.load_state_mut()?.config_hash = config_hash;
+
+            // Keep the stored configuration in sync with the hash, as `update_configuration` does.
+            let config_account = accounts.get(4).ok_or(ErrorCode::AccountNotEnoughKeys)?;
+            require_keys_eq!(
+                config_account.key(),
+                stored_config(&boring_account_key, program_id),
+                CustomError::InvalidMessage
+            );
+            BoringConfig::update_if_stored(config_account, &config)?;
+            emit_configuration_updated(config_hash, &config);
+        }
+        InboundMessage::UpdateStrategist { new_strategist, .. } => {
+            let mut state = boring_account.load_state_mut()?;
+            let old_strategist = state.strategist;
+            state.strategist = new_strategist;
+            emit!(StrategistUpdated {
+                old_strategist,
+                new_strategist,
+            });
+        }
+        InboundMessage::TransferOwnership { new_owner, .. } => {
+            let mut state = boring_account.load_state_mut()?;
+            let old_owner = state.owner;
+            state.owner = new_owner;
+            emit!(OwnershipTransferred {
+                old_owner,
+                new_owner,
+            });
+        }
+    }
+
+    Ok(())
+}
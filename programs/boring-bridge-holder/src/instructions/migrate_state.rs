@@ -0,0 +1,207 @@
+use std::cell::{Ref, RefMut};
+
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_lang::Discriminator;
+use bytemuck::Zeroable;
+
+use crate::{
+    requires_auth, AutoBridgePolicy, BoringState, CustomError, Governor, MigrateState,
+    MAX_DOMAIN_TOTALS, STATE_VERSION,
+};
+
+/// Size of a version 1 `BoringState` account, which predates the version byte and reserved bytes
+pub const V1_ACCOUNT_SIZE: usize = 8 + 32 + 32 + 32 + 32 + 1;
+
+/// Size of a `BoringState` account in the current layout
+pub const ACCOUNT_SIZE: usize = 8 + BoringState::INIT_SPACE;
+
+/// Offset of the version byte in the account data, the same in every versioned layout
+const STATE_VERSION_OFFSET: usize = 8 + 32 + 32 + 32 + 32 + 1;
+
+/// The version 1 layout of `BoringState`
+#[derive(AnchorDeserialize)]
+struct BoringStateV1 {
+    creator: Pubkey,
+    owner: Pubkey,
+    strategist: Pubkey,
+    config_hash: [u8; 32],
+    bump: u8,
+}
+
+/// The Borsh layout of `BoringState` used by versions 2 to 7, followed by reserved bytes
+///
+/// Each of these versions carved its new fields out of the reserved bytes, so accounts that
+/// predate a field read it as zero.
+#[derive(AnchorDeserialize)]
+struct BoringStateBorsh {
+    creator: Pubkey,
+    owner: Pubkey,
+    strategist: Pubkey,
+    config_hash: [u8; 32],
+    bump: u8,
+    _state_version: u8,
+    total_bridged: u64,
+    transfer_count: u64,
+    last_transfer_slot: u64,
+    last_transfer_timestamp: i64,
+    /// Domain totals, each a `u32` domain followed by the `u64` amount and transfer count
+    _domain_totals: [[u8; 4 + 8 + 8]; MAX_DOMAIN_TOTALS],
+    auto_bridge_policy: AutoBridgePolicy,
+    sweep_reserve: u64,
+    message_nonce: u64,
+    governor: Governor,
+}
+
+/// The last Borsh encoded version of `BoringState`
+const LAST_BORSH_VERSION: u8 = 7;
+
+/// The first version of `BoringState` whose domain totals record the bridge kind
+const BRIDGE_KIND_VERSION: u8 = 10;
+
+/// Loads `BoringState` accounts, rejecting accounts stored with an older layout
+///
+/// `AccountLoader` reinterprets the account data as the current layout, so accounts of older
+/// versions must be upgraded with `migrate_state` before they can be loaded.
+pub trait LoadState {
+    /// Loads the state for reading
+    fn load_state(&self) -> Result<Ref<'_, BoringState>>;
+    /// Loads the state for writing
+    fn load_state_mut(&self) -> Result<RefMut<'_, BoringState>>;
+    /// The creator and bump the holder PDA signs with, copied out so no borrow of the account
+    /// data is held across a CPI
+    fn creator_and_bump(&self) -> Result<(Pubkey, u8)>;
+}
+
+impl<'info> LoadState for AccountLoader<'info, BoringState> {
+    fn load_state(&self) -> Result<Ref<'_, BoringState>> {
+        require_current_layout(self.as_ref())?;
+        self.load()
+    }
+
+    fn load_state_mut(&self) -> Result<RefMut<'_, BoringState>> {
+        require_current_layout(self.as_ref())?;
+        self.load_mut()
+    }
+
+    fn creator_and_bump(&self) -> Result<(Pubkey, u8)> {
+        let state = self.load_state()?;
+        Ok((state.creator, state.bump))
+    }
+}
+
+/// Checks that the account is large enough for, and versioned as, the current layout
+fn require_current_layout(account: &AccountInfo) -> Result<()> {
+    let data = account.try_borrow_data()?;
+    require!(
+        data.len() >= ACCOUNT_SIZE && data[STATE_VERSION_OFFSET] == STATE_VERSION,
+        CustomError::StateNotMigrated
+    );
+    Ok(())
+}
+
+/// Reads the `BoringState` stored in the account, upgrading older layouts to the current one
+///
+/// Returns the upgraded state and the version it was stored with.
+pub fn read_state(account: &AccountInfo) -> Result<(BoringState, u8)> {
+    require_keys_eq!(*account.owner, crate::ID, CustomError::InvalidState);
+
+    let data = account.try_borrow_data()?;
+    require!(
+        data.len() >= 8 && data[..8] == BoringState::DISCRIMINATOR,
+        CustomError::InvalidState
+    );
+
+    let mut state = BoringState::zeroed();
+    let old_version = if data.len() == V1_ACCOUNT_SIZE {
+        let v1 = BoringStateV1::deserialize(&mut &data[8..])?;
+        state.creator = v1.creator;
+        state.owner = v1.owner;
+        state.strategist = v1.strategist;
+        state.config_hash = v1.config_hash;
+        state.bump = v1.bump;
+        1
+    } else {
+        require!(data.len() > STATE_VERSION_OFFSET, CustomError::InvalidState);
+        match data[STATE_VERSION_OFFSET] {
+            old_version @ 2..=LAST_BORSH_VERSION => {
+                let borsh = BoringStateBorsh::deserialize(&mut &data[8..])?;
+                state.creator = borsh.creator;
+                state.owner = borsh.owner;
+                state.strategist = borsh.strategist;
+                state.config_hash = borsh.config_hash;
+                state.bump = borsh.bump;
+                state.total_bridged = borsh.total_bridged;
+                state.transfer_count = borsh.transfer_count;
+                state.last_transfer_slot = borsh.last_transfer_slot;
+                state.last_transfer_timestamp = borsh.last_transfer_timestamp;
+                state.auto_bridge_policy = borsh.auto_bridge_policy.into();
+                state.sweep_reserve = borsh.sweep_reserve;
+                state.message_nonce = borsh.message_nonce;
+                state.governor = borsh.governor.into();
+                old_version
+            }
+            // Later zero-copy versions only carve new fields out of the reserved bytes, which
+            // older accounts hold as zero.
+            old_version @ 8..=STATE_VERSION => {
+                require!(data.len() >= ACCOUNT_SIZE, CustomError::InvalidState);
+                state = bytemuck::pod_read_unaligned(&data[8..ACCOUNT_SIZE]);
+                old_version
+            }
+            _ => return err!(CustomError::InvalidState),
+        }
+    };
+    // Earlier domain totals did not record the bridge, and bridges number their chains
+    // differently, so they can't be attributed to one. The overall totals are kept.
+    if old_version < BRIDGE_KIND_VERSION {
+        state.domain_totals = Zeroable::zeroed();
+    }
+    state.state_version = STATE_VERSION;
+    Ok((state, old_version))
+}
+
+/// Migrates the `BoringState` account to the current layout
+pub fn migrate_state(accounts: &MigrateState) -> Result<u8> {
+    let account = accounts.boring_account.to_account_info();
+    let (state, old_version) = read_state(&account)?;
+
+    require!(
+        old_version < STATE_VERSION,
+        CustomError::StateAlreadyMigrated
+    );
+
+    // Make sure this is the holder PDA for the stored creator.
+    let expected = Pubkey::create_program_address(
+        &[b"boring_state", state.creator.as_ref(), &[state.bump]],
+        &crate::ID,
+    )
+    .map_err(|_| error!(CustomError::InvalidState))?;
+    require_keys_eq!(expected, account.key(), CustomError::InvalidState);
+
+    requires_auth(accounts.signer.key(), state.owner)?;
+
+    // Grow the account and top up rent from the signer.
+    if account.data_len() < ACCOUNT_SIZE {
+        let required = Rent::get()?.minimum_balance(ACCOUNT_SIZE);
+        let shortfall = required.saturating_sub(account.lamports());
+        if shortfall > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: accounts.signer.to_account_info(),
+                        to: account.clone(),
+                    },
+                ),
+                shortfall,
+            )?;
+        }
+        account.realloc(ACCOUNT_SIZE, true)?;
+    }
+
+    // The discriminator is unchanged, so only the state is rewritten.
+    let mut data = account.try_borrow_mut_data()?;
+    data[8..ACCOUNT_SIZE].copy_from_slice(bytemuck::bytes_of(&state));
+
+    Ok(old_version)
+}
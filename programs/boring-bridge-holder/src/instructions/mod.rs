@@ -1 +1,14 @@
+pub mod auto_bridge;
+pub mod bridge_adapter;
+pub mod circle_cctp;
+pub mod dispatch_message;
+pub mod hyperlane_warp_route;
+pub mod igp;
+pub mod layerzero_oft;
+pub mod message_recipient;
+pub mod migrate_state;
+#[cfg(test)]
+pub mod test_accounts;
 pub mod transfer_remote;
+pub mod transfer_remote_batch;
+pub mod wormhole_ntt;
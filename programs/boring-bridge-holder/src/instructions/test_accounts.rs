@@ -0,0 +1,103 @@
+//! Account fixtures for unit tests of the bridge adapters
+//!
+//! Adapters read their accounts from the Anchor contexts of their instructions, so tests build
+//! those contexts from fixture accounts with unique keys. Fixtures are leaked to live for
+//! `'static`, like the accounts the runtime hands to an instruction.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_lang::system_program;
+use anchor_lang::Discriminator;
+use anchor_spl::token::spl_token;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::BoringState;
+
+/// Leaks an account with the given key, owner and data
+fn leak(
+    key: Pubkey,
+    owner: Pubkey,
+    data: Vec<u8>,
+    executable: bool,
+) -> &'static AccountInfo<'static> {
+    Box::leak(Box::new(AccountInfo::new(
+        Box::leak(Box::new(key)),
+        false,
+        false,
+        Box::leak(Box::new(0)),
+        Box::leak(data.into_boxed_slice()),
+        Box::leak(Box::new(owner)),
+        executable,
+        0,
+    )))
+}
+
+/// An unchecked account with a unique key
+pub fn unchecked() -> AccountInfo<'static> {
+    leak(Pubkey::new_unique(), Pubkey::default(), Vec::new(), false).clone()
+}
+
+/// A signer with a unique key
+pub fn signer() -> Signer<'static> {
+    let info = leak(Pubkey::new_unique(), Pubkey::default(), Vec::new(), false);
+    let info = Box::leak(Box::new(AccountInfo {
+        is_signer: true,
+        ..info.clone()
+    }));
+    Signer::try_from(&*info).unwrap()
+}
+
+/// A holder state account with zeroed state
+pub fn boring_state() -> AccountLoader<'static, BoringState> {
+    let mut data = BoringState::DISCRIMINATOR.to_vec();
+    data.resize(8 + BoringState::INIT_SPACE, 0);
+    AccountLoader::try_from(leak(Pubkey::new_unique(), crate::ID, data, false)).unwrap()
+}
+
+/// An account of this program holding `value`
+pub fn program_account<T>(value: &T) -> Account<'static, T>
+where
+    T: AccountSerialize + AccountDeserialize + Owner + Clone,
+{
+    let mut data = Vec::new();
+    value.try_serialize(&mut data).unwrap();
+    Account::try_from(leak(Pubkey::new_unique(), crate::ID, data, false)).unwrap()
+}
+
+/// An initialized SPL Token mint
+pub fn mint() -> InterfaceAccount<'static, Mint> {
+    let mint = spl_token::state::Mint {
+        is_initialized: true,
+        ..Default::default()
+    };
+    let mut data = vec![0; spl_token::state::Mint::LEN];
+    mint.pack_into_slice(&mut data);
+    InterfaceAccount::try_from(leak(Pubkey::new_unique(), spl_token::ID, data, false)).unwrap()
+}
+
+/// An initialized SPL Token account
+pub fn token_account() -> InterfaceAccount<'static, TokenAccount> {
+    let account = spl_token::state::Account {
+        state: spl_token::state::AccountState::Initialized,
+        ..Default::default()
+    };
+    let mut data = vec![0; spl_token::state::Account::LEN];
+    account.pack_into_slice(&mut data);
+    InterfaceAccount::try_from(leak(Pubkey::new_unique(), spl_token::ID, data, false)).unwrap()
+}
+
+/// The SPL Token program
+pub fn token_program() -> Interface<'static, TokenInterface> {
+    Interface::try_from(leak(spl_token::ID, Pubkey::default(), Vec::new(), true)).unwrap()
+}
+
+/// The system program
+pub fn system_program() -> Program<'static, System> {
+    Program::try_from(leak(
+        system_program::ID,
+        Pubkey::default(),
+        Vec::new(),
+        true,
+    ))
+    .unwrap()
+}
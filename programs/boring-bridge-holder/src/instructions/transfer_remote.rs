@@ -1,18 +1,110 @@
 use anchor_lang::prelude::*;
-use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
-use anchor_lang::solana_program::program::invoke;
-use anchor_spl::token_2022;
-use borsh::BorshSerialize;
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_spl::token_2022::spl_token_2022;
+use anchor_spl::token_2022::spl_token_2022::extension::transfer_fee::TransferFeeConfig;
+use anchor_spl::token_2022::spl_token_2022::extension::{
+    transfer_fee, transfer_hook, BaseStateWithExtensions, StateWithExtensions,
+};
+use anchor_spl::token_2022::spl_token_2022::state::Mint as MintState;
+use anchor_spl::token_2022::Token2022;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+use spl_transfer_hook_interface::onchain::add_extra_accounts_for_execute_cpi;
 
-use crate::{ConfigurationData, CustomError, TransferRemote, TransferRemoteContext};
+use crate::instructions::bridge_adapter::{BridgeAdapter, BridgeTransfer};
+use crate::instructions::hyperlane_warp_route::{self, HyperlaneWarpRoute};
+use crate::instructions::migrate_state::LoadState;
+use crate::{
+    BoringState, ConfigurationBridgeKind, ConfigurationData, CustomError, Recipient,
+    TransferRemoteStoredContext, WarpRouteTransferAccounts, SWEEP_ALL,
+};
+
+/// Accounts used to move tokens to the strategist and execute the warp route CPI
+///
+/// Borrowed from each transfer remote context, so all transfer paths share the same token
+/// transfer and CPI logic. `strategist_ata` is the signer's token account, which is the
+/// keeper's for `crank_transfer_remote`. `unique_message` is the PDA derived from the
+/// holder's current message nonce, and `unique_message_bump` its bump. `hook_accounts` holds
+/// the extra accounts of the mint's transfer hook, if it has one.
+pub struct TransferRemoteAccounts<'a, 'info> {
+    pub boring_account: &'a AccountLoader<'info, BoringState>,
+    pub signer: &'a Signer<'info>,
+    pub target_program: &'a AccountInfo<'info>,
+    pub system_program: &'a Program<'info, System>,
+    pub noop: &'a AccountInfo<'info>,
+    pub token_pda: &'a AccountInfo<'info>,
+    pub mailbox_program: &'a AccountInfo<'info>,
+    pub mailbox_outbox: &'a AccountInfo<'info>,
+    pub message_dispatch_authority: &'a AccountInfo<'info>,
+    pub unique_message: &'a AccountInfo<'info>,
+    pub unique_message_bump: u8,
+    pub message_storage_pda: &'a AccountInfo<'info>,
+    pub igp_program: &'a AccountInfo<'info>,
+    pub igp_program_data: &'a AccountInfo<'info>,
+    pub gas_payment_pda: &'a AccountInfo<'info>,
+    pub igp_account: &'a AccountInfo<'info>,
+    pub token_sender: &'a AccountInfo<'info>,
+    pub token_2022: &'a Program<'info, Token2022>,
+    pub mint_auth: &'a AccountInfo<'info>,
+    pub mint: &'a InterfaceAccount<'info, Mint>,
+    pub boring_account_ata: &'a InterfaceAccount<'info, TokenAccount>,
+    pub strategist_ata: &'a InterfaceAccount<'info, TokenAccount>,
+    pub hook_accounts: &'a [AccountInfo<'info>],
+}
+
+/// Implements `transfer_accounts` for the warp route accounts of a transfer remote context
+///
+/// The shared warp route accounts and the stored configuration's context name their accounts
+/// like `TransferRemoteAccounts`, so they share a single mapping.
+macro_rules! impl_transfer_accounts {
+    ($context:ident) => {
+        impl<'info> $context<'info> {
+            pub fn transfer_accounts<'a>(
+                &'a self,
+                unique_message_bump: u8,
+                hook_accounts: &'a [AccountInfo<'info>],
+            ) -> TransferRemoteAccounts<'a, 'info> {
+                TransferRemoteAccounts {
+                    boring_account: &self.boring_account,
+                    signer: &self.signer,
+                    target_program: &self.target_program,
+                    system_program: &self.system_program,
+                    noop: &self.noop,
+                    token_pda: &self.token_pda,
+                    mailbox_program: &self.mailbox_program,
+                    mailbox_outbox: &self.mailbox_outbox,
+                    message_dispatch_authority: &self.message_dispatch_authority,
+                    unique_message: &self.unique_message,
+                    unique_message_bump,
+                    message_storage_pda: &self.message_storage_pda,
+                    igp_program: &self.igp_program,
+                    igp_program_data: &self.igp_program_data,
+                    gas_payment_pda: &self.gas_payment_pda,
+                    igp_account: &self.igp_account,
+                    token_sender: &self.token_sender,
+                    token_2022: &self.token_2022,
+                    mint_auth: &self.mint_auth,
+                    mint: &self.mint,
+                    boring_account_ata: &self.boring_account_ata,
+                    strategist_ata: &self.strategist_ata,
+                    hook_accounts,
+                }
+            }
+        }
+    };
+}
+
+impl_transfer_accounts!(WarpRouteTransferAccounts);
+impl_transfer_accounts!(TransferRemoteStoredContext);
 
 /// Verifies the configuration hash by hashing all inputs and comparing
 /// the result to the stored configuration hash
-pub fn verify_configuration<'info>(
-    accounts: &TransferRemoteContext<'info>,
+///
+/// The decimals are read from the mint, so the configured decimals must match the mint.
+pub fn verify_configuration(
+    accounts: &TransferRemoteAccounts,
     destination_domain: u32,
-    evm_recipient: [u8; 32],
-    decimals: u8,
+    recipient: Recipient,
+    remote_decimals: u8,
 ) -> Result<()> {
     let config = ConfigurationData {
         target_program: accounts.target_program.key(),
@@ -28,168 +120,225 @@ pub fn verify_configuration<'info>(
         token_2022_program: accounts.token_2022.key(),
         mint_auth: accounts.mint_auth.key(),
         destination_domain,
-        evm_recipient,
-        decimals,
+        recipient,
+        decimals: accounts.mint.decimals,
+        remote_decimals,
+        bridge_kind: ConfigurationBridgeKind::HyperlaneWarpRoute,
+        mint: accounts.mint.key(),
     };
 
+    verify_configuration_hash(accounts.boring_account, &config)
+}
+
+/// Verifies that the configuration data hashes to the stored configuration hash
+pub fn verify_configuration_hash(
+    boring_account: &AccountLoader<BoringState>,
+    config: &ConfigurationData,
+) -> Result<()> {
     require!(
-        config.compute_hash() == accounts.boring_account.config_hash,
+        config.compute_hash(&boring_account.key()) == boring_account.load_state()?.config_hash,
         CustomError::InvalidConfiguration
     );
     Ok(())
 }
 
-/// Transfers tokens to the strategist
-pub fn transfer_tokens_to_strategist<'info>(
-    accounts: &TransferRemoteContext<'info>,
-    amount: u64,
-    decimals: u8,
-) -> Result<()> {
-    let bump_bytes = &[accounts.boring_account.bump];
-    let seeds = &[
-        b"boring_state" as &[u8],
-        accounts.boring_account.creator.as_ref(),
-        bump_bytes,
-    ];
-    let signer_seeds = &[&seeds[..]];
-
-    let transfer_cpi_context = CpiContext::new_with_signer(
-        accounts.token_2022.to_account_info(),
-        token_2022::TransferChecked {
-            from: accounts.boring_account_ata.to_account_info(),
-            to: accounts.strategist_ata.to_account_info(),
-            authority: accounts.boring_account.to_account_info(),
-            mint: accounts.mint_auth.to_account_info(),
-        },
-        signer_seeds,
+/// Verifies configured decimals match the mint
+pub fn verify_decimals(accounts: &TransferRemoteAccounts, decimals: u8) -> Result<()> {
+    require!(
+        decimals == accounts.mint.decimals,
+        CustomError::InvalidDecimals
     );
-    token_2022::transfer_checked(transfer_cpi_context, amount, decimals)
+    Ok(())
 }
 
-/// Executes the transfer remote instruction
-pub fn execute_transfer_remote<'info>(
-    accounts: &TransferRemoteContext<'info>,
-    destination_domain: u32,
-    evm_recipient: [u8; 32],
+/// Resolves the amount to bridge, expanding `SWEEP_ALL` to the holder's balance at execution time
+///
+/// A sweep leaves the owner-configured sweep reserve in the holder.
+pub fn resolve_amount(accounts: &TransferRemoteAccounts, amount: u64) -> Result<u64> {
+    resolve_amount_from_balance(
+        &*accounts.boring_account.load_state()?,
+        accounts.boring_account_ata.amount,
+        amount,
+    )
+}
+
+/// Resolves the amount to bridge from the holder's token balance
+pub fn resolve_amount_from_balance(
+    boring_account: &BoringState,
+    balance: u64,
     amount: u64,
-) -> Result<()> {
-    let mut data;
-    {
-        let mut amount_or_id = [0u8; 32];
-        amount_or_id[..8].copy_from_slice(&amount.to_le_bytes());
-
-        let transfer_data = TransferRemote {
-            destination_domain,
-            recipient: evm_recipient,
-            amount_or_id,
-        };
-
-        // First 8 bytes represent the `PROGRAM_INSTRUCTION_DISCRIMINATOR`, final byte indicates
-        // the instruction type of `transfer_remote`.
-        data = vec![1, 1, 1, 1, 1, 1, 1, 1, 1];
-        data.extend(transfer_data.try_to_vec()?);
+) -> Result<u64> {
+    if amount != SWEEP_ALL {
+        return Ok(amount);
     }
 
-    let account_metas = create_account_metas(accounts);
-    let instruction = Instruction {
-        program_id: accounts.target_program.key(),
-        accounts: account_metas,
-        data,
-    };
-
-    Ok(invoke(&instruction, &create_account_infos(accounts))?)
+    let amount = balance.saturating_sub(boring_account.sweep_reserve);
+    require!(amount > 0, CustomError::InsufficientBalance);
+    Ok(amount)
 }
 
-/// Creates the account metas for the transfer remote instruction
-fn create_account_metas<'info>(accounts: &TransferRemoteContext) -> Vec<AccountMeta> {
-    let mut metas = Vec::with_capacity(17);
-    metas.push(AccountMeta::new_readonly(
-        accounts.system_program.key(),
-        false,
-    ));
-    metas.push(AccountMeta::new_readonly(accounts.noop.key(), false));
-    metas.push(AccountMeta::new_readonly(accounts.token_pda.key(), false));
-    metas.push(AccountMeta::new_readonly(
-        accounts.mailbox_program.key(),
-        false,
-    ));
-    metas.push(AccountMeta::new(accounts.mailbox_outbox.key(), false));
-    metas.push(AccountMeta::new_readonly(
-        accounts.message_dispatch_authority.key(),
-        false,
-    ));
-    metas.push(AccountMeta::new(accounts.signer.key(), true));
-    metas.push(AccountMeta::new_readonly(
-        accounts.unique_message.key(),
-        true,
-    ));
-    metas.push(AccountMeta::new(accounts.message_storage_pda.key(), false));
-    metas.push(AccountMeta::new_readonly(accounts.igp_program.key(), false));
-    metas.push(AccountMeta::new(accounts.igp_program_data.key(), false));
-    metas.push(AccountMeta::new(accounts.gas_payment_pda.key(), false));
-    metas.push(AccountMeta::new_readonly(accounts.igp_account.key(), false));
-    metas.push(AccountMeta::new(accounts.token_sender.key(), false));
-    metas.push(AccountMeta::new_readonly(accounts.token_2022.key(), false));
-    metas.push(AccountMeta::new(accounts.mint_auth.key(), false));
-    metas.push(AccountMeta::new(accounts.strategist_ata.key(), false));
-    metas
+/// Token-2022 extensions of the mint that change how a transfer is made
+struct MintTransferExtensions {
+    /// Transfer fee withheld on the transfer, if the mint enables the transfer fee extension
+    fee: Option<u64>,
+    /// Program of the mint's transfer hook, if it has one
+    hook_program_id: Option<Pubkey>,
 }
 
-/// Creates the account infos for the transfer remote instruction
-fn create_account_infos<'info>(accounts: &TransferRemoteContext<'info>) -> Vec<AccountInfo<'info>> {
-    let mut infos = Vec::with_capacity(17);
-    // 0.  `[executable]` The system program.
-    infos.push(accounts.system_program.to_account_info());
-
-    // 1.  `[executable]` The spl_noop program.
-    infos.push(accounts.noop.to_account_info());
-
-    // 2.  `[]` The token PDA account.
-    infos.push(accounts.token_pda.to_account_info());
-
-    // 3.  `[executable]` The mailbox program.
-    infos.push(accounts.mailbox_program.to_account_info());
-
-    // 4.  `[writeable]` The mailbox outbox account.
-    infos.push(accounts.mailbox_outbox.to_account_info());
+/// Reads the Token-2022 extensions that apply to a transfer of `amount`
+fn mint_transfer_extensions(
+    accounts: &TransferRemoteAccounts,
+    amount: u64,
+) -> Result<MintTransferExtensions> {
+    let mint_info = accounts.mint.to_account_info();
+    let data = mint_info.try_borrow_data()?;
+    let mint = StateWithExtensions::<MintState>::unpack(&data)?;
 
-    // 5.  `[]` Message dispatch authority.
-    infos.push(accounts.message_dispatch_authority.to_account_info());
+    let fee = match mint.get_extension::<TransferFeeConfig>() {
+        Ok(config) => Some(
+            config
+                .calculate_epoch_fee(Clock::get()?.epoch, amount)
+                .ok_or(ProgramError::ArithmeticOverflow)?,
+        ),
+        Err(_) => None,
+    };
 
-    // 6.  `[signer]` The token sender and mailbox payer.
-    infos.push(accounts.signer.to_account_info());
+    Ok(MintTransferExtensions {
+        fee,
+        hook_program_id: transfer_hook::get_program_id(&mint),
+    })
+}
 
-    // 7.  `[signer]` Unique message / gas payment account.
-    infos.push(accounts.unique_message.to_account_info());
+/// Transfers tokens to the strategist
+///
+/// If the mint enables the Token-2022 transfer fee extension, the transfer states the fee
+/// explicitly and the strategist receives `amount` less the fee. If the mint has a transfer
+/// hook, the hook's extra accounts are resolved from `hook_accounts` and validated against
+/// the mint's `ExtraAccountMetaList`.
+///
+/// # Returns
+/// * `Result<u64>` - The transfer fee withheld from the strategist's account
+pub fn transfer_tokens_to_strategist(
+    accounts: &TransferRemoteAccounts,
+    amount: u64,
+) -> Result<u64> {
+    let (creator, bump) = accounts.boring_account.creator_and_bump()?;
+    let bump_bytes = &[bump];
+    let seeds = &[b"boring_state" as &[u8], creator.as_ref(), bump_bytes];
+    let signer_seeds = &[&seeds[..]];
+    let decimals = accounts.mint.decimals;
 
-    // 8.  `[writeable]` Message storage PDA.
-    infos.push(accounts.message_storage_pda.to_account_info());
+    let extensions = mint_transfer_extensions(accounts, amount)?;
+    if let Some(fee) = extensions.fee {
+        // Nothing would be left to bridge once the fee is withheld.
+        require!(fee < amount, CustomError::InsufficientBalance);
+    }
 
-    //     ---- If using an IGP ----
-    // 9.  `[executable]` The IGP program.
-    infos.push(accounts.igp_program.to_account_info());
+    let source = accounts.boring_account_ata.to_account_info();
+    let mint = accounts.mint.to_account_info();
+    let destination = accounts.strategist_ata.to_account_info();
+    let authority = accounts.boring_account.to_account_info();
 
-    // 10. `[writeable]` The IGP program data.
-    infos.push(accounts.igp_program_data.to_account_info());
+    let mut instruction = match extensions.fee {
+        Some(fee) => transfer_fee::instruction::transfer_checked_with_fee(
+            accounts.token_2022.key,
+            source.key,
+            mint.key,
+            destination.key,
+            authority.key,
+            &[],
+            amount,
+            decimals,
+            fee,
+        )?,
+        None => spl_token_2022::instruction::transfer_checked(
+            accounts.token_2022.key,
+            source.key,
+            mint.key,
+            destination.key,
+            authority.key,
+            &[],
+            amount,
+            decimals,
+        )?,
+    };
+    let mut account_infos = vec![
+        source.clone(),
+        mint.clone(),
+        destination.clone(),
+        authority.clone(),
+        accounts.token_2022.to_account_info(),
+    ];
 
-    // 11. `[writeable]` Gas payment PDA.
-    infos.push(accounts.gas_payment_pda.to_account_info());
+    if let Some(hook_program_id) = extensions.hook_program_id {
+        add_extra_accounts_for_execute_cpi(
+            &mut instruction,
+            &mut account_infos,
+            &hook_program_id,
+            source,
+            mint,
+            destination,
+            authority,
+            amount,
+            accounts.hook_accounts,
+        )?;
+    }
 
-    // 12. `[]` OPTIONAL - The Overhead IGP program, if the configured IGP is an Overhead IGP.
-    infos.push(accounts.igp_account.to_account_info());
+    invoke_signed(&instruction, &account_infos, signer_seeds)?;
+    Ok(extensions.fee.unwrap_or_default())
+}
 
-    // 13. `[writeable]` The IGP account.
-    infos.push(accounts.token_sender.to_account_info());
+/// Executes the transfer remote instruction through the Hyperlane warp route adapter
+///
+/// The warp route scales the amount to `remote_decimals` itself, so the amount is only
+/// checked to convert without losing precision.
+pub fn execute_transfer_remote(
+    accounts: &TransferRemoteAccounts,
+    destination_domain: u32,
+    evm_recipient: [u8; 32],
+    remote_decimals: u8,
+    amount: u64,
+) -> Result<()> {
+    hyperlane_warp_route::verify_remote_amount(amount, accounts.mint.decimals, remote_decimals)?;
 
-    //      ---- End if ----
-    // 14. `[executable]` The spl_token_2022 program.
-    infos.push(accounts.token_2022.to_account_info());
+    let transfer = BridgeTransfer {
+        destination_domain,
+        recipient: evm_recipient,
+        amount,
+    };
 
-    // 15. `[writeable]` The mint / mint authority PDA account.
-    infos.push(accounts.mint_auth.to_account_info());
+    // The holder signs for the unique message account derived from its message nonce.
+    let boring_account_key = accounts.boring_account.key();
+    let nonce_bytes = accounts
+        .boring_account
+        .load_state()?
+        .message_nonce
+        .to_le_bytes();
+    let bump_bytes = &[accounts.unique_message_bump];
+    let seeds = &[
+        b"unique_message" as &[u8],
+        boring_account_key.as_ref(),
+        &nonce_bytes,
+        bump_bytes,
+    ];
 
-    // 16. `[writeable]` The token sender's associated token account, from which tokens will be burned.
-    infos.push(accounts.strategist_ata.to_account_info());
+    HyperlaneWarpRoute::new(accounts).invoke(&transfer, &[&seeds[..]])
+}
 
-    infos
+/// Verifies the signer spent at most `max_gas_payment_lamports` on the dispatch
+///
+/// The spend covers the interchain gas payment and the rent of the message storage and gas
+/// payment accounts, so it guards against an IGP whose gas oracle spikes.
+pub fn verify_gas_payment(
+    signer: &Signer,
+    lamports_before: u64,
+    max_gas_payment_lamports: u64,
+) -> Result<()> {
+    let spent = lamports_before.saturating_sub(signer.lamports());
+    verbose_msg!("Gas payment: {} lamports", spent);
+    require!(
+        spent <= max_gas_payment_lamports,
+        CustomError::GasPaymentTooHigh
+    );
+    Ok(())
 }
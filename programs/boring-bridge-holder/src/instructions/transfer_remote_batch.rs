@@ -0,0 +1,248 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use anchor_spl::token_2022::Token2022;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+
+use crate::instructions::bridge_adapter::BridgeAdapter;
+use crate::instructions::hyperlane_warp_route::HyperlaneWarpRoute;
+use crate::instructions::migrate_state::LoadState;
+use crate::instructions::transfer_remote::{self, TransferRemoteAccounts};
+use crate::{BatchLeg, BoringRoute, BoringState, CustomError};
+
+/// Number of remaining accounts consumed by each leg of a batch transfer
+///
+/// 0.  `[]` The route account.
+/// 1.  `[executable]` The target warp route program.
+/// 2.  `[executable]` The spl_noop program.
+/// 3.  `[]` The token PDA account.
+/// 4.  `[executable]` The mailbox program.
+/// 5.  `[writeable]` The mailbox outbox account.
+/// 6.  `[]` Message dispatch authority.
+/// 7.  `[]` Unique message / gas payment PDA, derived from the holder's message nonce.
+/// 8.  `[writeable]` Message storage PDA.
+/// 9.  `[executable]` The IGP program.
+/// 10. `[writeable]` The IGP program data.
+/// 11. `[writeable]` Gas payment PDA.
+/// 12. `[]` The overhead IGP account.
+/// 13. `[writeable]` The IGP account.
+/// 14. `[executable]` The spl_token_2022 program.
+/// 15. `[writeable]` The mint / mint authority PDA account.
+/// 16. `[]` The mint of the bridged token.
+/// 17. `[writeable]` The boring account's associated token account.
+/// 18. `[writeable]` The strategist's associated token account.
+pub const BATCH_LEG_ACCOUNTS: usize = 19;
+
+/// Typed accounts of a single batch leg, validated against its route
+struct BatchLegAccounts<'info> {
+    infos: &'info [AccountInfo<'info>],
+    route: Account<'info, BoringRoute>,
+    unique_message_bump: u8,
+    token_2022: Program<'info, Token2022>,
+    mint: InterfaceAccount<'info, Mint>,
+    boring_account_ata: InterfaceAccount<'info, TokenAccount>,
+    strategist_ata: InterfaceAccount<'info, TokenAccount>,
+}
+
+impl<'info> BatchLegAccounts<'info> {
+    /// Loads and validates the accounts of a leg
+    fn load(
+        boring_account: &AccountLoader<'info, BoringState>,
+        signer: &Signer<'info>,
+        leg: &BatchLeg,
+        infos: &'info [AccountInfo<'info>],
+    ) -> Result<Self> {
+        let route = Account::<BoringRoute>::try_from(&infos[0])?;
+        let expected_route = Pubkey::create_program_address(
+            &[
+                b"boring_route",
+                boring_account.key().as_ref(),
+                &[leg.route_id],
+                &[route.bump],
+            ],
+            &crate::ID,
+        )
+        .map_err(|_| error!(CustomError::InvalidRoute))?;
+        require_keys_eq!(expected_route, route.key(), CustomError::InvalidRoute);
+
+        // Every account must match the route's configuration.
+        let config = &route.config;
+        let expected = [
+            (1, config.target_program),
+            (2, config.noop),
+            (3, config.token_pda),
+            (4, config.mailbox_program),
+            (5, config.mailbox_outbox),
+            (6, config.message_dispatch_authority),
+            (9, config.igp_program),
+            (10, config.igp_program_data),
+            (12, config.igp_account),
+            (13, config.token_sender),
+            (14, config.token_2022_program),
+            (15, config.mint_auth),
+            (16, config.mint),
+        ];
+        for (index, key) in expected {
+            require_keys_eq!(infos[index].key(), key, CustomError::InvalidConfiguration);
+        }
+
+        // The unique message is derived from the nonce of this leg, and the message storage
+        // and gas payment PDAs from the unique message.
+        let (unique_message, unique_message_bump) = Pubkey::find_program_address(
+            &[
+                b"unique_message",
+                boring_account.key().as_ref(),
+                &boring_account.load_state()?.message_nonce.to_le_bytes(),
+            ],
+            &crate::ID,
+        );
+        require_keys_eq!(
+            infos[7].key(),
+            unique_message,
+            CustomError::InvalidBatchAccounts
+        );
+        let (message_storage_pda, _) = Pubkey::find_program_address(
+            &[
+                b"hyperlane",
+                b"-",
+                b"dispatched_message",
+                b"-",
+                unique_message.as_ref(),
+            ],
+            &config.mailbox_program,
+        );
+        require_keys_eq!(
+            infos[8].key(),
+            message_storage_pda,
+            CustomError::InvalidBatchAccounts
+        );
+        let (gas_payment_pda, _) = Pubkey::find_program_address(
+            &[
+                b"hyperlane_igp",
+                b"-",
+                b"gas_payment",
+                b"-",
+                unique_message.as_ref(),
+            ],
+            &config.igp_program,
+        );
+        require_keys_eq!(
+            infos[11].key(),
+            gas_payment_pda,
+            CustomError::InvalidBatchAccounts
+        );
+
+        let token_2022 = Program::<Token2022>::try_from(&infos[14])?;
+        let mint = InterfaceAccount::<Mint>::try_from(&infos[16])?;
+        let boring_account_ata = InterfaceAccount::<TokenAccount>::try_from(&infos[17])?;
+        require_keys_eq!(
+            boring_account_ata.key(),
+            get_associated_token_address_with_program_id(
+                &boring_account.key(),
+                &config.mint,
+                &token_2022.key(),
+            ),
+            CustomError::InvalidBatchAccounts
+        );
+        let strategist_ata = InterfaceAccount::<TokenAccount>::try_from(&infos[18])?;
+        require_keys_eq!(
+            strategist_ata.key(),
+            get_associated_token_address_with_program_id(
+                &signer.key(),
+                &config.mint,
+                &token_2022.key(),
+            ),
+            CustomError::InvalidBatchAccounts
+        );
+
+        Ok(Self {
+            infos,
+            route,
+            unique_message_bump,
+            token_2022,
+            mint,
+            boring_account_ata,
+            strategist_ata,
+        })
+    }
+
+    /// Borrows the leg's accounts in the shape used by the transfer remote helpers
+    fn transfer_accounts<'a>(
+        &'a self,
+        boring_account: &'a AccountLoader<'info, BoringState>,
+        signer: &'a Signer<'info>,
+        system_program: &'a Program<'info, System>,
+        hook_accounts: &'a [AccountInfo<'info>],
+    ) -> TransferRemoteAccounts<'a, 'info> {
+        TransferRemoteAccounts {
+            boring_account,
+            signer,
+            target_program: &self.infos[1],
+            system_program,
+            noop: &self.infos[2],
+            token_pda: &self.infos[3],
+            mailbox_program: &self.infos[4],
+            mailbox_outbox: &self.infos[5],
+            message_dispatch_authority: &self.infos[6],
+            unique_message: &self.infos[7],
+            unique_message_bump: self.unique_message_bump,
+            message_storage_pda: &self.infos[8],
+            igp_program: &self.infos[9],
+            igp_program_data: &self.infos[10],
+            gas_payment_pda: &self.infos[11],
+            igp_account: &self.infos[12],
+            token_sender: &self.infos[13],
+            token_2022: &self.token_2022,
+            mint_auth: &self.infos[15],
+            mint: &self.mint,
+            boring_account_ata: &self.boring_account_ata,
+            strategist_ata: &self.strategist_ata,
+            hook_accounts,
+        }
+    }
+}
+
+/// A completed leg of a batch transfer
+pub struct CompletedLeg {
+    pub destination_domain: u32,
+    pub evm_recipient: [u8; 32],
+    pub amount: u64,
+    pub transfer_fee: u64,
+}
+
+/// Executes a single leg of a batch transfer
+///
+/// Accounts for the leg are loaded from `infos`, which must hold `BATCH_LEG_ACCOUNTS` accounts.
+/// `hook_accounts` holds the extra accounts of the mint's transfer hook, shared by all legs.
+pub fn execute_leg<'info>(
+    boring_account: &AccountLoader<'info, BoringState>,
+    signer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    leg: &BatchLeg,
+    infos: &'info [AccountInfo<'info>],
+    hook_accounts: &'info [AccountInfo<'info>],
+) -> Result<CompletedLeg> {
+    let leg_accounts = BatchLegAccounts::load(boring_account, signer, leg, infos)?;
+    let accounts =
+        leg_accounts.transfer_accounts(boring_account, signer, system_program, hook_accounts);
+    let config = &leg_accounts.route.config;
+
+    HyperlaneWarpRoute::validate_config(config)?;
+    transfer_remote::verify_decimals(&accounts, config.decimals)?;
+    let amount = transfer_remote::resolve_amount(&accounts, leg.amount)?;
+    let transfer_fee = transfer_remote::transfer_tokens_to_strategist(&accounts, amount)?;
+    let amount = amount - transfer_fee;
+    transfer_remote::execute_transfer_remote(
+        &accounts,
+        config.destination_domain,
+        config.recipient.to_bytes32(),
+        config.remote_decimals,
+        amount,
+    )?;
+
+    Ok(CompletedLeg {
+        destination_domain: config.destination_domain,
+        evm_recipient: config.recipient.to_bytes32(),
+        amount,
+        transfer_fee,
+    })
+}
@@ -0,0 +1,394 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::AccountMeta;
+use anchor_lang::solana_program::keccak;
+use anchor_lang::system_program;
+use anchor_spl::token_interface::{self, Approve};
+use borsh::BorshSerialize;
+
+use crate::instructions::bridge_adapter::{BridgeAdapter, BridgeTransfer};
+use crate::instructions::migrate_state::LoadState;
+use crate::{BridgeKind, CustomError, NttConfigurationData, NttMode, TransferRemoteNttContext};
+
+/// `sha256("global:transfer_burn")[..8]`, the Anchor discriminator of `transfer_burn`
+const TRANSFER_BURN_DISCRIMINATOR: [u8; 8] = [75, 144, 26, 232, 39, 12, 75, 222];
+/// `sha256("global:transfer_lock")[..8]`, the Anchor discriminator of `transfer_lock`
+const TRANSFER_LOCK_DISCRIMINATOR: [u8; 8] = [179, 158, 146, 148, 151, 46, 176, 200];
+
+/// Arguments of the NTT manager's `transfer_burn` and `transfer_lock`
+///
+/// The session authority the holder delegates to is derived from their keccak256 hash.
+#[derive(BorshSerialize)]
+struct TransferArgs {
+    amount: u64,
+    /// The Wormhole chain ID of the destination chain
+    recipient_chain: u16,
+    recipient_address: [u8; 32],
+    should_queue: bool,
+}
+
+impl TransferArgs {
+    fn new(transfer: &BridgeTransfer) -> Result<Self> {
+        Ok(Self {
+            amount: transfer.amount,
+            recipient_chain: u16::try_from(transfer.destination_domain)
+                .map_err(|_| error!(CustomError::InvalidConfiguration))?,
+            recipient_address: transfer.recipient,
+            // A transfer beyond the outbound rate limit fails rather than waiting in the queue.
+            should_queue: false,
+        })
+    }
+}
+
+/// Adapter sending tokens through a Wormhole NTT manager's `transfer_burn` or `transfer_lock`
+///
+/// The manager moves the tokens from the holder's token account through a session authority
+/// the holder delegates to, and creates the outbox item the Wormhole transceiver releases.
+pub struct WormholeNtt<'a, 'info> {
+    accounts: &'a TransferRemoteNttContext<'info>,
+    mode: NttMode,
+}
+
+impl<'a, 'info> WormholeNtt<'a, 'info> {
+    pub fn new(accounts: &'a TransferRemoteNttContext<'info>, mode: NttMode) -> Self {
+        Self { accounts, mode }
+    }
+}
+
+impl<'info> BridgeAdapter<'info> for WormholeNtt<'_, 'info> {
+    const KIND: BridgeKind = BridgeKind::WormholeNtt;
+
+    type Config = NttConfigurationData;
+
+    /// Tokens sent to the zero address can't be recovered, so a recipient must be set.
+    fn validate_config(config: &NttConfigurationData) -> Result<()> {
+        require!(
+            config.recipient_address != [0; 32],
+            CustomError::InvalidConfiguration
+        );
+        Ok(())
+    }
+
+    fn program_id(&self) -> Pubkey {
+        self.accounts.ntt_manager_program.key()
+    }
+
+    fn account_metas(&self) -> Vec<AccountMeta> {
+        let accounts = self.accounts;
+        let mut metas = vec![
+            AccountMeta::new(accounts.signer.key(), true),
+            AccountMeta::new_readonly(accounts.manager_config.key(), false),
+            AccountMeta::new(accounts.mint.key(), false),
+            AccountMeta::new(accounts.boring_account_ata.key(), false),
+            AccountMeta::new_readonly(accounts.token_program.key(), false),
+            AccountMeta::new(accounts.outbox_item.key(), true),
+            AccountMeta::new(accounts.outbox_rate_limit.key(), false),
+            AccountMeta::new(accounts.custody.key(), false),
+            AccountMeta::new_readonly(accounts.system_program.key(), false),
+            AccountMeta::new(accounts.inbox_rate_limit.key(), false),
+            AccountMeta::new_readonly(accounts.peer.key(), false),
+            AccountMeta::new_readonly(accounts.session_authority.key(), false),
+        ];
+        if self.mode == NttMode::Burning {
+            metas.push(AccountMeta::new_readonly(
+                accounts.token_authority.key(),
+                false,
+            ));
+        }
+        metas
+    }
+
+    fn account_infos(&self) -> Vec<AccountInfo<'info>> {
+        let accounts = self.accounts;
+        let mut infos = vec![
+            // 0.  `[signer, writeable]` The payer of the outbox item's rent.
+            accounts.signer.to_account_info(),
+            // 1.  `[]` The NTT manager's config.
+            accounts.manager_config.to_account_info(),
+            // 2.  `[writeable]` The mint of the sent token.
+            accounts.mint.to_account_info(),
+            // 3.  `[writeable]` The token account tokens are sent from.
+            accounts.boring_account_ata.to_account_info(),
+            // 4.  `[executable]` The token program of the mint.
+            accounts.token_program.to_account_info(),
+            // 5.  `[signer, writeable]` The outbox item, derived from the holder's message nonce.
+            accounts.outbox_item.to_account_info(),
+            // 6.  `[writeable]` The outbound rate limit.
+            accounts.outbox_rate_limit.to_account_info(),
+            // 7.  `[writeable]` The NTT manager's custody token account.
+            accounts.custody.to_account_info(),
+            // 8.  `[executable]` The system program.
+            accounts.system_program.to_account_info(),
+            // 9.  `[writeable]` The inbound rate limit of the destination chain.
+            accounts.inbox_rate_limit.to_account_info(),
+            // 10. `[]` The NTT manager peer of the destination chain.
+            accounts.peer.to_account_info(),
+            // 11. `[]` The session authority the holder delegated to.
+            accounts.session_authority.to_account_info(),
+        ];
+        if self.mode == NttMode::Burning {
+            // 12. `[]` The NTT manager's token authority, only when burning.
+            infos.push(accounts.token_authority.to_account_info());
+        }
+        infos
+    }
+
+    fn instruction_data(&self, transfer: &BridgeTransfer) -> Result<Vec<u8>> {
+        let mut data = match self.mode {
+            NttMode::Burning => TRANSFER_BURN_DISCRIMINATOR.to_vec(),
+            NttMode::Locking => TRANSFER_LOCK_DISCRIMINATOR.to_vec(),
+        };
+        data.extend(TransferArgs::new(transfer)?.try_to_vec()?);
+        Ok(data)
+    }
+}
+
+/// Verifies the NTT configuration hash by hashing all inputs and comparing the result to the
+/// stored NTT configuration hash
+pub fn verify_configuration(
+    accounts: &TransferRemoteNttContext,
+    mode: NttMode,
+    recipient_chain: u16,
+    recipient_address: [u8; 32],
+) -> Result<()> {
+    let config = NttConfigurationData {
+        ntt_manager_program: accounts.ntt_manager_program.key(),
+        manager_config: accounts.manager_config.key(),
+        mint: accounts.mint.key(),
+        custody: accounts.custody.key(),
+        outbox_rate_limit: accounts.outbox_rate_limit.key(),
+        relayer_fee_recipient: accounts.relayer_fee_recipient.key(),
+        mode,
+        recipient_chain,
+        recipient_address,
+    };
+
+    require!(
+        config.compute_hash(&accounts.boring_account.key()) == accounts.ntt_config.config_hash,
+        CustomError::InvalidConfiguration
+    );
+    Ok(())
+}
+
+/// Sends tokens from the holder through the NTT manager
+///
+/// The holder delegates `amount` to the session authority the manager derives from the transfer
+/// arguments, then signs for the outbox item derived from its message nonce.
+pub fn execute_transfer(
+    accounts: &TransferRemoteNttContext,
+    mode: NttMode,
+    outbox_item_bump: u8,
+    recipient_chain: u16,
+    recipient_address: [u8; 32],
+    amount: u64,
+) -> Result<()> {
+    let transfer = BridgeTransfer {
+        destination_domain: u32::from(recipient_chain),
+        recipient: recipient_address,
+        amount,
+    };
+
+    let boring_account = &accounts.boring_account;
+    let (creator, bump) = boring_account.creator_and_bump()?;
+    let holder_bump = &[bump];
+    let holder_seeds = &[b"boring_state" as &[u8], creator.as_ref(), holder_bump];
+
+    // The session authority is bound to the holder and the exact transfer arguments.
+    let args_hash = keccak::hash(&TransferArgs::new(&transfer)?.try_to_vec()?);
+    let (session_authority, _) = Pubkey::find_program_address(
+        &[
+            b"session_authority",
+            boring_account.key().as_ref(),
+            args_hash.as_ref(),
+        ],
+        &accounts.ntt_manager_program.key(),
+    );
+    require_keys_eq!(
+        accounts.session_authority.key(),
+        session_authority,
+        CustomError::InvalidConfiguration
+    );
+    token_interface::approve(
+        CpiContext::new_with_signer(
+            accounts.token_program.to_account_info(),
+            Approve {
+                to: accounts.boring_account_ata.to_account_info(),
+                delegate: accounts.session_authority.to_account_info(),
+                authority: boring_account.to_account_info(),
+            },
+            &[&holder_seeds[..]],
+        ),
+        amount,
+    )?;
+
+    let boring_account_key = boring_account.key();
+    let nonce_bytes = boring_account.load_state()?.message_nonce.to_le_bytes();
+    let outbox_item_bump = &[outbox_item_bump];
+    let outbox_item_seeds = &[
+        b"unique_message" as &[u8],
+        boring_account_key.as_ref(),
+        &nonce_bytes,
+        outbox_item_bump,
+    ];
+
+    WormholeNtt::new(accounts, mode).invoke(&transfer, &[&outbox_item_seeds[..]])
+}
+
+/// Pays the relayer that delivers the transfer on the destination chain
+pub fn pay_relayer_fee(
+    accounts: &TransferRemoteNttContext,
+    relayer_fee_lamports: u64,
+) -> Result<()> {
+    if relayer_fee_lamports == 0 {
+        return Ok(());
+    }
+
+    system_program::transfer(
+        CpiContext::new(
+            accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: accounts.signer.to_account_info(),
+                to: accounts.relayer_fee_recipient.to_account_info(),
+            },
+        ),
+        relayer_fee_lamports,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::solana_program::hash::hash;
+
+    use super::*;
+    use crate::instructions::test_accounts;
+    use crate::BoringNttConfig;
+
+    fn accounts() -> TransferRemoteNttContext<'static> {
+        TransferRemoteNttContext {
+            boring_account: test_accounts::boring_state(),
+            ntt_config: test_accounts::program_account(&BoringNttConfig {
+                bump: 0,
+                config_hash: [0; 32],
+            }),
+            signer: test_accounts::signer(),
+            ntt_manager_program: test_accounts::unchecked(),
+            manager_config: test_accounts::unchecked(),
+            mint: test_accounts::mint(),
+            boring_account_ata: test_accounts::token_account(),
+            token_program: test_accounts::token_program(),
+            outbox_item: test_accounts::unchecked(),
+            outbox_rate_limit: test_accounts::unchecked(),
+            custody: test_accounts::unchecked(),
+            system_program: test_accounts::system_program(),
+            inbox_rate_limit: test_accounts::unchecked(),
+            peer: test_accounts::unchecked(),
+            session_authority: test_accounts::unchecked(),
+            token_authority: test_accounts::unchecked(),
+            relayer_fee_recipient: test_accounts::unchecked(),
+        }
+    }
+
+    #[test]
+    fn builds_transfer_account_metas_for_each_mode() {
+        let accounts = accounts();
+
+        // The accounts of the NTT manager's `transfer_lock`, as `(key, is_signer, is_writable)`.
+        let locking = [
+            // payer
+            (accounts.signer.key(), true, true),
+            // config
+            (accounts.manager_config.key(), false, false),
+            // mint
+            (accounts.mint.key(), false, true),
+            // from
+            (accounts.boring_account_ata.key(), false, true),
+            // token_program
+            (accounts.token_program.key(), false, false),
+            // outbox_item
+            (accounts.outbox_item.key(), true, true),
+            // outbox_rate_limit
+            (accounts.outbox_rate_limit.key(), false, true),
+            // custody
+            (accounts.custody.key(), false, true),
+            // system_program
+            (accounts.system_program.key(), false, false),
+            // inbox_rate_limit
+            (accounts.inbox_rate_limit.key(), false, true),
+            // peer
+            (accounts.peer.key(), false, false),
+            // session_authority
+            (accounts.session_authority.key(), false, false),
+        ];
+        // `transfer_burn` takes the manager's token authority as well.
+        let mut burning = locking.to_vec();
+        burning.push((accounts.token_authority.key(), false, false));
+
+        for (mode, expected) in [
+            (NttMode::Locking, locking.to_vec()),
+            (NttMode::Burning, burning),
+        ] {
+            let adapter = WormholeNtt::new(&accounts, mode);
+            let metas = adapter.account_metas();
+            assert_eq!(metas.len(), expected.len(), "{mode:?}");
+            for (meta, (key, is_signer, is_writable)) in metas.iter().zip(expected) {
+                assert_eq!(meta.pubkey, key);
+                assert_eq!(meta.is_signer, is_signer, "{mode:?} {key}");
+                assert_eq!(meta.is_writable, is_writable, "{mode:?} {key}");
+            }
+
+            let infos = adapter.account_infos();
+            assert_eq!(infos.len(), metas.len(), "{mode:?}");
+            for (info, meta) in infos.iter().zip(&metas) {
+                assert_eq!(*info.key, meta.pubkey);
+            }
+            assert_eq!(adapter.program_id(), accounts.ntt_manager_program.key());
+        }
+    }
+
+    #[test]
+    fn encodes_transfer_data_for_each_mode() {
+        assert_eq!(
+            TRANSFER_BURN_DISCRIMINATOR,
+            hash(b"global:transfer_burn").to_bytes()[..8]
+        );
+        assert_eq!(
+            TRANSFER_LOCK_DISCRIMINATOR,
+            hash(b"global:transfer_lock").to_bytes()[..8]
+        );
+
+        let accounts = accounts();
+        let transfer = BridgeTransfer {
+            destination_domain: 2,
+            recipient: [7; 32],
+            amount: 1_000_000,
+        };
+        for (mode, discriminator) in [
+            (NttMode::Burning, TRANSFER_BURN_DISCRIMINATOR),
+            (NttMode::Locking, TRANSFER_LOCK_DISCRIMINATOR),
+        ] {
+            let data = WormholeNtt::new(&accounts, mode)
+                .instruction_data(&transfer)
+                .unwrap();
+
+            assert_eq!(data.len(), 8 + 8 + 2 + 32 + 1, "{mode:?}");
+            assert_eq!(data[..8], discriminator);
+            assert_eq!(data[8..16], 1_000_000u64.to_le_bytes());
+            assert_eq!(data[16..18], 2u16.to_le_bytes());
+            assert_eq!(data[18..50], [7; 32]);
+            // `should_queue` is false
+            assert_eq!(data[50], 0);
+        }
+    }
+
+    #[test]
+    fn rejects_recipient_chains_beyond_u16() {
+        let accounts = accounts();
+        let transfer = BridgeTransfer {
+            destination_domain: u32::from(u16::MAX) + 1,
+            recipient: [7; 32],
+            amount: 1_000_000,
+        };
+        assert!(WormholeNtt::new(&accounts, NttMode::Burning)
+            .instruction_data(&transfer)
+            .is_err());
+    }
+}
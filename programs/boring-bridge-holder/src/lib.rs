@@ -1,19 +1,92 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token_2022::Token2022;
-use anchor_spl::token_interface::TokenAccount;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 use borsh::{BorshDeserialize, BorshSerialize};
-use solana_program::hash::hash;
+use solana_program::hash::{hash, hashv};
 use solana_program::pubkey::Pubkey;
-use std::mem::size_of;
+
+/// Logs a diagnostic message in builds with the `verbose-logs` feature
+///
+/// Formatting logs, base58 addresses above all, costs compute units on every call, so default
+/// builds compile these out. Declared before the modules so they can use it.
+macro_rules! verbose_msg {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "verbose-logs")]
+        msg!($($arg)*);
+    };
+}
 
 pub mod instructions;
-use crate::instructions::transfer_remote;
+use crate::instructions::bridge_adapter::BridgeAdapter;
+use crate::instructions::circle_cctp::CircleCctp;
+use crate::instructions::hyperlane_warp_route::HyperlaneWarpRoute;
+use crate::instructions::layerzero_oft::{LayerZeroOft, OftSendOptions};
+use crate::instructions::migrate_state::LoadState;
+use crate::instructions::wormhole_ntt::WormholeNtt;
+use crate::instructions::{
+    auto_bridge, bridge_adapter, circle_cctp, dispatch_message, igp, layerzero_oft,
+    message_recipient, migrate_state, transfer_remote, transfer_remote_batch, wormhole_ntt,
+};
 
 pub mod events;
 use crate::events::*;
+#[cfg(not(target_os = "solana"))]
+pub mod client;
+pub mod u256;
 
 declare_id!("AWzzXzsLQvddsYdphCV6CTcr5ALXtg8AAtZXTqbUcVBF");
 
+/// Domain separator prepended to every configuration hash preimage
+pub const CONFIG_HASH_DOMAIN: &[u8] = b"boring_bridge_holder:configuration";
+
+/// Domain separator prepended to every CCTP configuration hash preimage
+pub const CCTP_CONFIG_HASH_DOMAIN: &[u8] = b"boring_bridge_holder:cctp_configuration";
+
+/// Domain separator prepended to every NTT configuration hash preimage
+pub const NTT_CONFIG_HASH_DOMAIN: &[u8] = b"boring_bridge_holder:ntt_configuration";
+
+/// Domain separator prepended to every OFT configuration hash preimage
+pub const OFT_CONFIG_HASH_DOMAIN: &[u8] = b"boring_bridge_holder:oft_configuration";
+
+/// Version of the `ConfigurationData` hash preimage layout
+///
+/// Version 1 predates `ConfigurationData::bridge_kind`, version 2
+/// `ConfigurationData::remote_decimals`, version 3 the `Recipient` format of
+/// `ConfigurationData::recipient`, and version 4 `ConfigurationData::mint`. Hashes of earlier versions are re-hashed with
+/// `migrate_configuration_hash`.
+pub const CONFIG_HASH_VERSION: u8 = 5;
+
+/// Version of the CCTP, NTT and OFT configuration hash preimage layouts
+///
+/// Versioned apart from `CONFIG_HASH_VERSION`, so changes to the Hyperlane configuration don't
+/// invalidate the hashes approved for other bridges.
+pub const BRIDGE_CONFIG_HASH_VERSION: u8 = 2;
+
+/// Amount sentinel that bridges the holder's full balance at execution time, less the sweep reserve
+pub const SWEEP_ALL: u64 = u64::MAX;
+
+/// Largest keeper bounty an auto-bridge policy may pay per transfer, 0.1 SOL
+///
+/// Keepers are paid from the fee vault each time the policy allows a transfer, so the bounty
+/// is bounded to cap how fast they can drain it.
+pub const MAX_KEEPER_BOUNTY_LAMPORTS: u64 = 100_000_000;
+
+/// Current layout version of `BoringState`
+///
+/// Version 1 predates the version byte and reserved bytes. Versions 2 to 7 are Borsh encoded,
+/// each carving new fields out of the reserved bytes:
+/// * 3 - bridging statistics
+/// * 4 - auto-bridge policy
+/// * 5 - sweep reserve
+/// * 6 - message nonce
+/// * 7 - governor
+///
+/// Version 8 loads the state zero-copy, and later versions carve new fields out of its
+/// reserved bytes:
+/// * 9 - lookup table
+/// * 10 - bridge kind of domain totals
+pub const STATE_VERSION: u8 = 10;
+
 /// Checks that the signer is the same as the authorized key
 ///
 /// # Arguments
@@ -22,11 +95,93 @@ declare_id!("AWzzXzsLQvddsYdphCV6CTcr5ALXtg8AAtZXTqbUcVBF");
 ///
 /// # Returns
 /// * `Result<()>` - Result indicating success or containing an error
-fn requires_auth(signer: Pubkey, allowed: Pubkey) -> Result<()> {
+pub(crate) fn requires_auth(signer: Pubkey, allowed: Pubkey) -> Result<()> {
     require_keys_eq!(signer, allowed, CustomError::Unauthorized);
     Ok(())
 }
 
+/// Emits a `ConfigurationUpdated` event for the given configuration
+///
+/// # Arguments
+/// * `config_hash` - The hash stored for the configuration
+/// * `config` - The configuration data that was hashed
+fn emit_configuration_updated(config_hash: [u8; 32], config: &ConfigurationData) {
+    emit!(ConfigurationUpdated {
+        config_hash,
+        target_program: config.target_program,
+        noop: config.noop,
+        token_pda: config.token_pda,
+        mailbox_program: config.mailbox_program,
+        mailbox_outbox: config.mailbox_outbox,
+        message_dispatch_authority: config.message_dispatch_authority,
+        igp_program: config.igp_program,
+        igp_program_data: config.igp_program_data,
+        igp_account: config.igp_account,
+        token_sender: config.token_sender,
+        token_2022_program: config.token_2022_program,
+        mint_auth: config.mint_auth,
+        destination_domain: config.destination_domain,
+        recipient: config.recipient,
+        decimals: config.decimals,
+        remote_decimals: config.remote_decimals,
+        bridge_kind: config.bridge_kind,
+        mint: config.mint,
+    });
+}
+
+/// Records a completed transfer in the bridging statistics, advances the message nonce and
+/// emits a `TransferRemote` event
+///
+/// # Arguments
+/// * `boring_account` - The state account of the holder
+/// * `bridge_kind` - The bridge the transfer went through
+/// * `destination_domain` - The ID of the destination chain in the bridge's own numbering
+/// * `evm_recipient` - The 32-byte recipient address on the destination chain
+/// * `amount` - The amount of tokens bridged
+/// * `transfer_fee` - The Token-2022 transfer fee withheld when moving the tokens to the signer
+///
+/// # Returns
+/// * `Result<()>` - Result indicating success or containing an error
+fn record_transfer(
+    boring_account: &mut BoringState,
+    bridge_kind: BridgeKind,
+    destination_domain: u32,
+    evm_recipient: [u8; 32],
+    amount: u64,
+    transfer_fee: u64,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    let domain_total_bridged = boring_account.record_transfer(
+        bridge_kind,
+        destination_domain,
+        amount,
+        clock.slot,
+        clock.unix_timestamp,
+    );
+
+    // The next transfer derives a fresh unique message account.
+    let message_nonce = boring_account.message_nonce;
+    boring_account.message_nonce = message_nonce
+        .checked_add(1)
+        .ok_or(CustomError::InvalidState)?;
+
+    emit!(events::TransferRemote {
+        bridge_kind,
+        destination_domain,
+        evm_recipient,
+        amount,
+        transfer_fee,
+        total_bridged: boring_account.total_bridged,
+        transfer_count: boring_account.transfer_count,
+        domain_total_bridged,
+        slot: clock.slot,
+        timestamp: clock.unix_timestamp,
+        message_nonce,
+    });
+
+    Ok(())
+}
+
 #[program]
 mod boring_bridge_holder {
     use super::*;
@@ -47,14 +202,16 @@ mod boring_bridge_holder {
         strategist: Pubkey,
         config: ConfigurationData,
     ) -> Result<()> {
-        let boring_account = &mut ctx.accounts.boring_account;
+        bridge_adapter::validate_config(&config)?;
+        let config_hash = config.compute_hash(&ctx.accounts.boring_account.key());
+        let boring_account = &mut ctx.accounts.boring_account.load_init()?;
 
         boring_account.creator = ctx.accounts.signer.key();
         boring_account.owner = owner;
         boring_account.strategist = strategist;
-        let config_hash = config.compute_hash();
         boring_account.config_hash = config_hash;
         boring_account.bump = ctx.bumps.boring_account;
+        boring_account.state_version = STATE_VERSION;
 
         emit!(Initialized {
             boring_account: ctx.accounts.boring_account.key(),
@@ -64,24 +221,7 @@ mod boring_bridge_holder {
             strategist,
         });
 
-        emit!(ConfigurationUpdated {
-            config_hash,
-            target_program: config.target_program,
-            noop: config.noop,
-            token_pda: config.token_pda,
-            mailbox_program: config.mailbox_program,
-            mailbox_outbox: config.mailbox_outbox,
-            message_dispatch_authority: config.message_dispatch_authority,
-            igp_program: config.igp_program,
-            igp_program_data: config.igp_program_data,
-            igp_account: config.igp_account,
-            token_sender: config.token_sender,
-            token_2022_program: config.token_2022_program,
-            mint_auth: config.mint_auth,
-            destination_domain: config.destination_domain,
-            evm_recipient: config.evm_recipient,
-            decimals: config.decimals,
-        });
+        emit_configuration_updated(config_hash, &config);
 
         Ok(())
     }
@@ -99,7 +239,7 @@ mod boring_bridge_holder {
     /// * `Result<()>` - Result indicating success or containing an error
     pub fn transfer_ownership(ctx: Context<UpdateOwner>, new_owner: Pubkey) -> Result<()> {
         // Check that signer is the current owner
-        let boring_account = &mut ctx.accounts.boring_account;
+        let boring_account = &mut *ctx.accounts.boring_account.load_state_mut()?;
         let old_owner = boring_account.owner;
         requires_auth(ctx.accounts.signer.key(), old_owner)?;
 
@@ -127,7 +267,7 @@ mod boring_bridge_holder {
     /// * `Result<()>` - Result indicating success or containing an error
     pub fn update_strategist(ctx: Context<UpdateOwner>, new_strategist: Pubkey) -> Result<()> {
         // Check that signer is the current owner
-        let boring_account = &mut ctx.accounts.boring_account;
+        let boring_account = &mut *ctx.accounts.boring_account.load_state_mut()?;
         let old_strategist = boring_account.strategist;
         requires_auth(ctx.accounts.signer.key(), boring_account.owner)?;
 
@@ -148,39 +288,106 @@ mod boring_bridge_holder {
     /// * `ctx` - The context of accounts
     /// * `config` - The new configuration data
     ///
+    /// If the holder stores its configuration, the stored configuration is updated as well, so
+    /// `transfer_remote_stored` keeps matching the config hash.
+    ///
     /// # Errors
     /// * `CustomError::Unauthorized` - If the signer is not the current owner
+    /// * `CustomError::InvalidConfiguration` - If the bridge adapter rejects the configuration
     ///
     /// # Returns
     /// * `Result<()>` - Result indicating success or containing an error
     pub fn update_configuration(
-        ctx: Context<UpdateOwner>,
+        ctx: Context<UpdateConfiguration>,
         config: ConfigurationData,
     ) -> Result<()> {
         // Check that signer is the current owner
-        let boring_account = &mut ctx.accounts.boring_account;
+        let boring_account_key = ctx.accounts.boring_account.key();
+        let boring_account = &mut *ctx.accounts.boring_account.load_state_mut()?;
         requires_auth(ctx.accounts.signer.key(), boring_account.owner)?;
+        bridge_adapter::validate_config(&config)?;
 
-        // Update the configuration hash
-        boring_account.config_hash = config.compute_hash();
+        // Update the configuration hash and the stored configuration, if any
+        boring_account.config_hash = config.compute_hash(&boring_account_key);
+        BoringConfig::update_if_stored(&ctx.accounts.config_account, &config)?;
 
-        emit!(ConfigurationUpdated {
-            config_hash: boring_account.config_hash,
-            target_program: config.target_program,
-            noop: config.noop,
-            token_pda: config.token_pda,
-            mailbox_program: config.mailbox_program,
-            mailbox_outbox: config.mailbox_outbox,
-            message_dispatch_authority: config.message_dispatch_authority,
-            igp_program: config.igp_program,
-            igp_program_data: config.igp_program_data,
-            igp_account: config.igp_account,
-            token_sender: config.token_sender,
-            token_2022_program: config.token_2022_program,
-            mint_auth: config.mint_auth,
-            destination_domain: config.destination_domain,
-            evm_recipient: config.evm_recipient,
-            decimals: config.decimals,
+        emit_configuration_updated(boring_account.config_hash, &config);
+
+        Ok(())
+    }
+
+    /// Re-hashes a configuration that was stored with the legacy hash or an earlier hash version
+    ///
+    /// The legacy hash was `sha256(borsh(config))`, which is not bound to a program or holder.
+    /// The provided configuration must match the stored hash under the legacy scheme or an
+    /// earlier version before it is re-hashed with the current scheme. Earlier versions didn't
+    /// record the recipient format, so the configuration must also pass the validation of
+    /// `update_configuration`; a legacy configuration whose recipient is not an EVM address
+    /// must be approved again by the owner instead.
+    ///
+    /// # Arguments
+    /// * `ctx` - The context of accounts
+    /// * `config` - The configuration data currently approved for this holder
+    ///
+    /// # Errors
+    /// * `CustomError::Unauthorized` - If the signer is neither the owner nor the strategist
+    /// * `CustomError::InvalidConfiguration` - If the configuration doesn't match the legacy hash
+    /// * `CustomError::InvalidRecipient` - If the recipient is invalid for its format
+    ///
+    /// # Returns
+    /// * `Result<()>` - Result indicating success or containing an error
+    pub fn migrate_configuration_hash(
+        ctx: Context<MigrateConfigurationHash>,
+        config: ConfigurationData,
+    ) -> Result<()> {
+        let boring_account_key = ctx.accounts.boring_account.key();
+        let boring_account = &mut *ctx.accounts.boring_account.load_state_mut()?;
+
+        // Verify owner or strategist
+        let signer = ctx.accounts.signer.key();
+        require!(
+            signer == boring_account.owner || signer == boring_account.strategist,
+            CustomError::Unauthorized
+        );
+
+        let config_hash = Some(boring_account.config_hash);
+        require!(
+            config.compute_legacy_hash() == config_hash
+                || (1..CONFIG_HASH_VERSION).any(|version| {
+                    config.compute_previous_hash(&boring_account_key, version) == config_hash
+                }),
+            CustomError::InvalidConfiguration
+        );
+        bridge_adapter::validate_config(&config)?;
+
+        boring_account.config_hash = config.compute_hash(&boring_account_key);
+
+        emit_configuration_updated(boring_account.config_hash, &config);
+
+        Ok(())
+    }
+
+    /// Migrates the Boring Bridge Holder state account to the current layout
+    ///
+    /// Reallocates accounts created with an older layout, paying any extra rent from the signer.
+    ///
+    /// # Arguments
+    /// * `ctx` - The context of accounts
+    ///
+    /// # Errors
+    /// * `CustomError::Unauthorized` - If the signer is not the current owner
+    /// * `CustomError::InvalidState` - If the account is not a Boring Bridge Holder state account
+    /// * `CustomError::StateAlreadyMigrated` - If the account already uses the current layout
+    ///
+    /// # Returns
+    /// * `Result<()>` - Result indicating success or containing an error
+    pub fn migrate_state(ctx: Context<MigrateState>) -> Result<()> {
+        let old_version = migrate_state::migrate_state(ctx.accounts)?;
+
+        emit!(StateMigrated {
+            boring_account: ctx.accounts.boring_account.key(),
+            old_version,
+            new_version: STATE_VERSION,
         });
 
         Ok(())
@@ -191,170 +398,2095 @@ mod boring_bridge_holder {
     /// # Arguments
     /// * `ctx` - The context of accounts
     /// * `destination_domain` - The domain ID of the destination chain
-    /// * `evm_recipient` - The 32-byte recipient address on the destination chain
-    /// * `decimals` - The number of decimals for the token
-    /// * `amount` - The amount of tokens to transfer
+    /// * `recipient` - The recipient on the destination chain, as configured
+    /// * `remote_decimals` - The decimals of the token on the destination chain
+    /// * `amount` - The amount of tokens to transfer, or `SWEEP_ALL` to transfer the full balance
+    /// * `max_gas_payment_lamports` - The most lamports the signer may spend on the dispatch
     ///
     /// # Errors
     /// * `CustomError::Unauthorized` - If the signer is not the strategist
     /// * `CustomError::InvalidConfiguration` - If the provided configuration doesn't match stored hash
+    /// * `CustomError::InsufficientBalance` - If a sweep would leave nothing to transfer
+    /// * `CustomError::AmountLosesPrecision` - If the amount can't be expressed in the remote decimals
+    /// * `CustomError::GasPaymentTooHigh` - If the signer spent more than `max_gas_payment_lamports`
     ///
     /// # Returns
     /// * `Result<()>` - Result indicating success or containing an error
-    pub fn transfer_remote(
-        ctx: Context<TransferRemoteContext>,
+    pub fn transfer_remote<'info>(
+        ctx: Context<'_, '_, '_, 'info, TransferRemoteContext<'info>>,
         destination_domain: u32,
-        evm_recipient: [u8; 32],
-        decimals: u8,
+        recipient: Recipient,
+        remote_decimals: u8,
         amount: u64,
+        max_gas_payment_lamports: u64,
     ) -> Result<()> {
+        let accounts = ctx
+            .accounts
+            .transfer
+            .transfer_accounts(ctx.bumps.transfer.unique_message, ctx.remaining_accounts);
+
         // Verify strategist
-        msg!(
+        verbose_msg!(
             "Verifying signer: {} against stored strategist: {}",
-            ctx.accounts.signer.key(),
-            ctx.accounts.boring_account.strategist
+            accounts.signer.key(),
+            accounts.boring_account.load_state()?.strategist
         );
         requires_auth(
-            ctx.accounts.signer.key(),
-            ctx.accounts.boring_account.strategist,
+            accounts.signer.key(),
+            accounts.boring_account.load_state()?.strategist,
         )?;
 
-        // Verify configuration matches stored hash
-        msg!("Verifying configuration matches stored hash");
+        // Verify configuration matches stored hash, using the mint's decimals
+        verbose_msg!("Verifying configuration matches stored hash");
         transfer_remote::verify_configuration(
-            &ctx.accounts,
+            &accounts,
             destination_domain,
-            evm_recipient,
-            decimals,
+            recipient,
+            remote_decimals,
         )?;
+        let evm_recipient = recipient.to_bytes32();
 
         // Transfer tokens to strategist
-        msg!("Transferring tokens to strategist");
-        transfer_remote::transfer_tokens_to_strategist(&ctx.accounts, amount, decimals)?;
+        verbose_msg!("Transferring tokens to strategist");
+        let amount = transfer_remote::resolve_amount(&accounts, amount)?;
+        let transfer_fee = transfer_remote::transfer_tokens_to_strategist(&accounts, amount)?;
+        let amount = amount - transfer_fee;
 
         // Create and execute the transfer remote instruction
-        msg!("Creating and executing transfer remote instruction");
+        verbose_msg!("Creating and executing transfer remote instruction");
+        let lamports_before = accounts.signer.lamports();
+        transfer_remote::execute_transfer_remote(
+            &accounts,
+            destination_domain,
+            evm_recipient,
+            remote_decimals,
+            amount,
+        )?;
+        transfer_remote::verify_gas_payment(
+            accounts.signer,
+            lamports_before,
+            max_gas_payment_lamports,
+        )?;
+
+        // Record the transfer in the bridging statistics
+        record_transfer(
+            &mut *ctx.accounts.transfer.boring_account.load_state_mut()?,
+            BridgeKind::HyperlaneWarpRoute,
+            destination_domain,
+            evm_recipient,
+            amount,
+            transfer_fee,
+        )
+    }
+
+    /// Transfers tokens remotely with a companion message carrying a payload
+    ///
+    /// The warp route's message holds only the recipient and amount, so the payload is sent in
+    /// a second message dispatched by the holder, whose body is the transfer's message ID
+    /// followed by the payload. The companion message uses the message nonce after the
+    /// transfer's, and the `TransferPayloadDispatched` event links both message IDs.
+    ///
+    /// # Arguments
+    /// * `ctx` - The context of accounts
+    /// * `destination_domain` - The domain ID of the destination chain
+    /// * `recipient` - The recipient on the destination chain, as configured
+    /// * `remote_decimals` - The decimals of the token on the destination chain
+    /// * `amount` - The amount of tokens to transfer, or `SWEEP_ALL` to transfer the full balance
+    /// * `max_gas_payment_lamports` - The most lamports the signer may spend on both dispatches
+    /// * `payload_recipient` - The 32-byte recipient of the companion message
+    /// * `payload` - The payload of the companion message
+    /// * `payload_gas_amount` - The destination gas to pay the IGP for the companion message, if any
+    ///
+    /// # Errors
+    /// * `CustomError::Unauthorized` - If the signer is not the strategist
+    /// * `CustomError::InvalidConfiguration` - If the provided configuration doesn't match stored hash
+    /// * `CustomError::RecipientNotAllowed` - If the payload recipient is not in the dispatch allowlist
+    /// * `CustomError::InsufficientBalance` - If a sweep would leave nothing to transfer
+    /// * `CustomError::AmountLosesPrecision` - If the amount can't be expressed in the remote decimals
+    /// * `CustomError::GasPaymentTooHigh` - If the signer spent more than `max_gas_payment_lamports`
+    ///
+    /// # Returns
+    /// * `Result<()>` - Result indicating success or containing an error
+    #[allow(clippy::too_many_arguments)]
+    pub fn transfer_remote_with_payload<'info>(
+        ctx: Context<'_, '_, '_, 'info, TransferRemoteWithPayloadContext<'info>>,
+        destination_domain: u32,
+        recipient: Recipient,
+        remote_decimals: u8,
+        amount: u64,
+        max_gas_payment_lamports: u64,
+        payload_recipient: [u8; 32],
+        payload: Vec<u8>,
+        payload_gas_amount: Option<u64>,
+    ) -> Result<()> {
+        let accounts = ctx
+            .accounts
+            .transfer
+            .transfer_accounts(ctx.bumps.transfer.unique_message, ctx.remaining_accounts);
+
+        // Verify strategist
+        requires_auth(
+            accounts.signer.key(),
+            accounts.boring_account.load_state()?.strategist,
+        )?;
+
+        // Verify configuration matches stored hash, using the mint's decimals
+        transfer_remote::verify_configuration(
+            &accounts,
+            destination_domain,
+            recipient,
+            remote_decimals,
+        )?;
+        let evm_recipient = recipient.to_bytes32();
+        require!(
+            ctx.accounts
+                .dispatch
+                .is_allowed_recipient(destination_domain, &payload_recipient),
+            CustomError::RecipientNotAllowed
+        );
+
+        // Transfer tokens to strategist
+        let amount = transfer_remote::resolve_amount(&accounts, amount)?;
+        let transfer_fee = transfer_remote::transfer_tokens_to_strategist(&accounts, amount)?;
+        let amount = amount - transfer_fee;
+
+        // Execute the transfer remote instruction
+        let lamports_before = accounts.signer.lamports();
         transfer_remote::execute_transfer_remote(
-            &ctx.accounts,
+            &accounts,
+            destination_domain,
+            evm_recipient,
+            remote_decimals,
+            amount,
+        )?;
+        let transfer_message_id =
+            dispatch_message::dispatched_message_id(accounts.message_storage_pda)?;
+
+        // Dispatch the companion message and pay for its gas
+        let payload_message_nonce = accounts.boring_account.load_state()?.next_message_nonce()?;
+        let payload_accounts = ctx
+            .accounts
+            .payload_dispatch_accounts(ctx.bumps.payload_message, payload_message_nonce);
+        let mut message_body = transfer_message_id.to_vec();
+        message_body.extend(payload);
+        let payload_message_id = dispatch_message::dispatch(
+            &payload_accounts,
+            destination_domain,
+            payload_recipient,
+            message_body,
+        )?;
+        if let Some(gas_amount) = payload_gas_amount {
+            dispatch_message::pay_for_gas(
+                &payload_accounts,
+                payload_message_id,
+                destination_domain,
+                gas_amount,
+            )?;
+        }
+        transfer_remote::verify_gas_payment(
+            accounts.signer,
+            lamports_before,
+            max_gas_payment_lamports,
+        )?;
+
+        // Record the transfer in the bridging statistics
+        record_transfer(
+            &mut *ctx.accounts.transfer.boring_account.load_state_mut()?,
+            BridgeKind::HyperlaneWarpRoute,
             destination_domain,
             evm_recipient,
             amount,
+            transfer_fee,
         )?;
 
+        // The companion message used the next nonce as well.
+        ctx.accounts
+            .transfer
+            .boring_account
+            .load_state_mut()?
+            .message_nonce = payload_message_nonce
+            .checked_add(1)
+            .ok_or(CustomError::InvalidState)?;
+
+        emit!(TransferPayloadDispatched {
+            transfer_message_id,
+            payload_message_id,
+            payload_recipient,
+            payload_message_nonce,
+        });
+
         Ok(())
     }
 
-    /// Returns the program version
-    /// Used to verify the program was upgraded
-    pub fn version(_ctx: Context<Version>) -> Result<()> {
-        msg!("Program version: 1.0.3");
+    /// Stores the full configuration on-chain and updates the configuration hash
+    ///
+    /// Creates the configuration account on first use, so strategists can bridge with
+    /// `transfer_remote_stored` without reconstructing the configuration off-chain.
+    ///
+    /// # Arguments
+    /// * `ctx` - The context of accounts
+    /// * `config` - The new configuration data
+    ///
+    /// # Errors
+    /// * `CustomError::Unauthorized` - If the signer is not the current owner
+    /// * `CustomError::InvalidConfiguration` - If the bridge adapter rejects the configuration
+    ///
+    /// # Returns
+    /// * `Result<()>` - Result indicating success or containing an error
+    pub fn store_configuration(
+        ctx: Context<StoreConfiguration>,
+        config: ConfigurationData,
+    ) -> Result<()> {
+        // Check that signer is the current owner
+        let boring_account_key = ctx.accounts.boring_account.key();
+        let boring_account = &mut *ctx.accounts.boring_account.load_state_mut()?;
+        requires_auth(ctx.accounts.signer.key(), boring_account.owner)?;
+        bridge_adapter::validate_config(&config)?;
+
+        // Update the configuration hash and the stored configuration
+        boring_account.config_hash = config.compute_hash(&boring_account_key);
+        emit_configuration_updated(boring_account.config_hash, &config);
+        ctx.accounts.config_account.config = config;
+
         Ok(())
     }
-}
 
-#[derive(Accounts)]
-pub struct Initialize<'info> {
-    #[account(
-        init,
-        payer = signer,
-        space = size_of::<BoringState>() + 8,
-        seeds = [b"boring_state", signer.key().as_ref()],
-        bump
-    )]
-    pub boring_account: Account<'info, BoringState>,
-    #[account(mut)]
-    pub signer: Signer<'info>,
-    pub system_program: Program<'info, System>, // Only needed when creating or initializing an account.
-}
+    /// Returns the stored configuration via return data
+    ///
+    /// # Arguments
+    /// * `ctx` - The context of accounts
+    ///
+    /// # Returns
+    /// * `Result<ConfigurationData>` - The stored configuration data
+    pub fn get_configuration(ctx: Context<GetConfiguration>) -> Result<ConfigurationData> {
+        Ok(ctx.accounts.config_account.config.clone())
+    }
 
-#[derive(Accounts)]
-pub struct UpdateOwner<'info> {
-    #[account(
-        mut,
-        seeds = [b"boring_state", boring_account.creator.as_ref()],
-        bump = boring_account.bump,
-    )]
-    pub boring_account: Account<'info, BoringState>,
-    pub signer: Signer<'info>,
-}
+    /// Transfers tokens remotely using the configuration stored on-chain
+    ///
+    /// # Arguments
+    /// * `ctx` - The context of accounts
+    /// * `amount` - The amount of tokens to transfer, or `SWEEP_ALL` to transfer the full balance
+    /// * `max_gas_payment_lamports` - The most lamports the signer may spend on gas payment and message rent
+    ///
+    /// # Errors
+    /// * `CustomError::Unauthorized` - If the signer is not the strategist
+    /// * `CustomError::InvalidConfiguration` - If the stored configuration doesn't match stored hash
+    /// * `CustomError::InvalidDecimals` - If the stored decimals don't match the mint
+    /// * `CustomError::InsufficientBalance` - If a sweep would leave nothing to transfer
+    /// * `CustomError::AmountLosesPrecision` - If the amount can't be expressed in the remote decimals
+    /// * `CustomError::GasPaymentTooHigh` - If the signer spent more than `max_gas_payment_lamports`
+    ///
+    /// # Returns
+    /// * `Result<()>` - Result indicating success or containing an error
+    pub fn transfer_remote_stored<'info>(
+        ctx: Context<'_, '_, '_, 'info, TransferRemoteStoredContext<'info>>,
+        amount: u64,
+        max_gas_payment_lamports: u64,
+    ) -> Result<()> {
+        let accounts = ctx
+            .accounts
+            .transfer_accounts(ctx.bumps.unique_message, ctx.remaining_accounts);
+        let config = &ctx.accounts.config_account.config;
 
-#[derive(Accounts)]
-pub struct UpdateStrategist<'info> {
-    #[account(
-        mut,
-        seeds = [b"boring_state", boring_account.creator.as_ref()],
-        bump = boring_account.bump,
-    )]
-    pub boring_account: Account<'info, BoringState>,
-    pub signer: Signer<'info>,
-}
+        // Verify strategist
+        requires_auth(
+            accounts.signer.key(),
+            accounts.boring_account.load_state()?.strategist,
+        )?;
 
-#[derive(Accounts)]
-pub struct UpdateConfiguration<'info> {
-    #[account(
-        mut,
-        seeds = [b"boring_state", boring_account.creator.as_ref()],
-        bump = boring_account.bump,
-    )]
-    pub boring_account: Account<'info, BoringState>,
-    pub signer: Signer<'info>,
-}
+        // Verify the stored configuration has not been superseded by `update_configuration`
+        transfer_remote::verify_configuration_hash(accounts.boring_account, config)?;
+        HyperlaneWarpRoute::validate_config(config)?;
+        transfer_remote::verify_decimals(&accounts, config.decimals)?;
 
-#[derive(Accounts)]
-pub struct TransferRemoteContext<'info> {
-    #[account(
-        seeds = [b"boring_state", boring_account.creator.as_ref()],
-        bump = boring_account.bump,
-    )]
-    pub boring_account: Account<'info, BoringState>,
-    #[account(mut)]
-    pub signer: Signer<'info>,
-    /// Taret program
-    /// CHECK: Checked in config hash
-    pub target_program: AccountInfo<'info>,
-    /// System Program account
-    pub system_program: Program<'info, System>,
-    /// NOOP
-    /// CHECK: Checked in config hash
-    pub noop: AccountInfo<'info>,
-    /// Token PDA owned by program_target
-    /// CHECK: Checked in config hash
-    pub token_pda: AccountInfo<'info>,
-    /// Mailbox Program
-    /// CHECK: Checked in config hash
-    pub mailbox_program: AccountInfo<'info>,
-    /// Mailbox Outbox
-    #[account(mut)]
-    /// CHECK: Checked in config hash
-    pub mailbox_outbox: AccountInfo<'info>,
-    /// Message Dispatch Authority
-    /// CHECK: Checked in config hash
-    pub message_dispatch_authority: AccountInfo<'info>,
-    /// Unique message / gas payment account
-    /// CHECK: Only needs to be a signer
-    pub unique_message: Signer<'info>,
-    /// Message storage PDA
-    #[account(
-        mut,
-        seeds = [
-            b"hyperlane",
-            b"-",
-            b"dispatched_message",
-            b"-",
-            unique_message.key().as_ref()
-        ],
-        bump,
-        seeds::program = mailbox_program.key()
-    )]
-    /// CHECK: Checked against PDA
-    pub message_storage_pda: AccountInfo<'info>,
-    /// IGP Program
-    /// CHECK: Checked in config hash
-    pub igp_program: AccountInfo<'info>,
-    /// IGP Program Data
-    #[account(mut)]
-    /// CHECK: Checked in config hash
-    pub igp_program_data: AccountInfo<'info>,
+        // Transfer tokens to strategist
+        let amount = transfer_remote::resolve_amount(&accounts, amount)?;
+        let transfer_fee = transfer_remote::transfer_tokens_to_strategist(&accounts, amount)?;
+        let amount = amount - transfer_fee;
+
+        // Create and execute the transfer remote instruction
+        let lamports_before = accounts.signer.lamports();
+        transfer_remote::execute_transfer_remote(
+            &accounts,
+            config.destination_domain,
+            config.recipient.to_bytes32(),
+            config.remote_decimals,
+            amount,
+        )?;
+        transfer_remote::verify_gas_payment(
+            accounts.signer,
+            lamports_before,
+            max_gas_payment_lamports,
+        )?;
+
+        // Record the transfer in the bridging statistics
+        let destination_domain = config.destination_domain;
+        let evm_recipient = config.recipient.to_bytes32();
+        record_transfer(
+            &mut *ctx.accounts.boring_account.load_state_mut()?,
+            config.bridge_kind.into(),
+            destination_domain,
+            evm_recipient,
+            amount,
+            transfer_fee,
+        )
+    }
+
+    /// Updates the auto-bridge policy that lets keepers trigger transfers
+    ///
+    /// # Arguments
+    /// * `ctx` - The context of accounts
+    /// * `policy` - The new auto-bridge policy
+    ///
+    /// # Errors
+    /// * `CustomError::Unauthorized` - If the signer is not the current owner
+    /// * `CustomError::InvalidAutoBridgePolicy` - If the policy is malformed, is enabled without a
+    ///   positive minimum interval, or its keeper bounty exceeds `MAX_KEEPER_BOUNTY_LAMPORTS`
+    ///
+    /// # Returns
+    /// * `Result<()>` - Result indicating success or containing an error
+    pub fn update_auto_bridge_policy(
+        ctx: Context<UpdateOwner>,
+        policy: AutoBridgePolicy,
+    ) -> Result<()> {
+        // Check that signer is the current owner
+        let boring_account = &mut *ctx.accounts.boring_account.load_state_mut()?;
+        requires_auth(ctx.accounts.signer.key(), boring_account.owner)?;
+        auto_bridge::validate_policy(&policy)?;
+
+        // Update the policy
+        boring_account.auto_bridge_policy = policy.into();
+
+        emit!(AutoBridgePolicyUpdated {
+            enabled: policy.enabled,
+            min_balance: policy.min_balance,
+            amount: policy.amount,
+            min_interval_seconds: policy.min_interval_seconds,
+            keeper_bounty_lamports: policy.keeper_bounty_lamports,
+        });
+
+        Ok(())
+    }
+
+    /// Updates the EVM-side governor that can execute owner actions through Hyperlane messages
+    ///
+    /// # Arguments
+    /// * `ctx` - The context of accounts
+    /// * `governor` - The new governor
+    ///
+    /// # Errors
+    /// * `CustomError::Unauthorized` - If the signer is not the current owner
+    ///
+    /// # Returns
+    /// * `Result<()>` - Result indicating success or containing an error
+    pub fn update_governor(ctx: Context<UpdateOwner>, governor: Governor) -> Result<()> {
+        // Check that signer is the current owner
+        let boring_account = &mut *ctx.accounts.boring_account.load_state_mut()?;
+        requires_auth(ctx.accounts.signer.key(), boring_account.owner)?;
+
+        // Update the governor
+        boring_account.governor = governor.into();
+
+        emit!(GovernorUpdated {
+            enabled: governor.enabled,
+            origin: governor.origin,
+            sender: governor.sender,
+        });
+
+        Ok(())
+    }
+
+    /// Records the canonical address lookup table of the holder, so clients can discover it
+    ///
+    /// The table is created and extended off-chain with the static addresses of the
+    /// configuration. The program never reads it; `Pubkey::default()` clears it.
+    ///
+    /// # Arguments
+    /// * `ctx` - The context of accounts
+    /// * `lookup_table` - The address of the lookup table
+    ///
+    /// # Errors
+    /// * `CustomError::Unauthorized` - If the signer is not the current owner
+    ///
+    /// # Returns
+    /// * `Result<()>` - Result indicating success or containing an error
+    pub fn set_lookup_table(ctx: Context<UpdateOwner>, lookup_table: Pubkey) -> Result<()> {
+        // Check that signer is the current owner
+        let boring_account = &mut *ctx.accounts.boring_account.load_state_mut()?;
+        requires_auth(ctx.accounts.signer.key(), boring_account.owner)?;
+
+        // Update the lookup table
+        boring_account.lookup_table = lookup_table;
+
+        emit!(LookupTableUpdated {
+            boring_account: ctx.accounts.boring_account.key(),
+            lookup_table,
+        });
+
+        Ok(())
+    }
+
+    /// Approves or updates a route that can be used in batch transfers
+    ///
+    /// # Arguments
+    /// * `ctx` - The context of accounts
+    /// * `route_id` - The identifier of the route
+    /// * `config` - The configuration data of the route
+    ///
+    /// # Errors
+    /// * `CustomError::Unauthorized` - If the signer is not the current owner
+    /// * `CustomError::InvalidConfiguration` - If the bridge adapter rejects the configuration
+    ///
+    /// # Returns
+    /// * `Result<()>` - Result indicating success or containing an error
+    pub fn set_route(
+        ctx: Context<SetRoute>,
+        route_id: u8,
+        config: ConfigurationData,
+    ) -> Result<()> {
+        // Check that signer is the current owner
+        requires_auth(
+            ctx.accounts.signer.key(),
+            ctx.accounts.boring_account.load_state()?.owner,
+        )?;
+        bridge_adapter::validate_config(&config)?;
+
+        emit!(RouteUpdated {
+            route: ctx.accounts.route.key(),
+            route_id,
+            target_program: config.target_program,
+            mint_auth: config.mint_auth,
+            mint: config.mint,
+            destination_domain: config.destination_domain,
+            recipient: config.recipient,
+        });
+
+        let route = &mut ctx.accounts.route;
+        route.route_id = route_id;
+        route.bump = ctx.bumps.route;
+        route.config = config;
+
+        Ok(())
+    }
+
+    /// Updates the approved Circle CCTP configuration
+    ///
+    /// # Arguments
+    /// * `ctx` - The context of accounts
+    /// * `config` - The new CCTP configuration data
+    ///
+    /// # Errors
+    /// * `CustomError::Unauthorized` - If the signer is not the current owner
+    /// * `CustomError::InvalidConfiguration` - If the mint recipient is not set
+    ///
+    /// # Returns
+    /// * `Result<()>` - Result indicating success or containing an error
+    pub fn update_cctp_configuration(
+        ctx: Context<UpdateCctpConfiguration>,
+        config: CctpConfigurationData,
+    ) -> Result<()> {
+        // Check that signer is the current owner
+        requires_auth(
+            ctx.accounts.signer.key(),
+            ctx.accounts.boring_account.load_state()?.owner,
+        )?;
+        CircleCctp::validate_config(&config)?;
+
+        let config_hash = config.compute_hash(&ctx.accounts.boring_account.key());
+        emit!(CctpConfigurationUpdated {
+            config_hash,
+            token_messenger_minter_program: config.token_messenger_minter_program,
+            message_transmitter_program: config.message_transmitter_program,
+            message_transmitter: config.message_transmitter,
+            token_messenger: config.token_messenger,
+            remote_token_messenger: config.remote_token_messenger,
+            token_minter: config.token_minter,
+            local_token: config.local_token,
+            burn_token_mint: config.burn_token_mint,
+            destination_domain: config.destination_domain,
+            mint_recipient: config.mint_recipient,
+        });
+
+        let cctp_config = &mut ctx.accounts.cctp_config;
+        cctp_config.bump = ctx.bumps.cctp_config;
+        cctp_config.config_hash = config_hash;
+
+        Ok(())
+    }
+
+    /// Burns tokens from the holder through Circle's CCTP to mint them on the destination chain
+    ///
+    /// # Arguments
+    /// * `ctx` - The context of accounts
+    /// * `destination_domain` - The CCTP domain of the destination chain
+    /// * `mint_recipient` - The 32-byte address minted to on the destination chain
+    /// * `amount` - The amount of tokens to burn, or `SWEEP_ALL` to burn the full balance
+    ///
+    /// # Errors
+    /// * `CustomError::Unauthorized` - If the signer is not the strategist
+    /// * `CustomError::InvalidConfiguration` - If the provided configuration doesn't match the stored CCTP hash
+    /// * `CustomError::InsufficientBalance` - If a sweep would leave nothing to transfer
+    ///
+    /// # Returns
+    /// * `Result<()>` - Result indicating success or containing an error
+    pub fn deposit_for_burn(
+        ctx: Context<DepositForBurnContext>,
+        destination_domain: u32,
+        mint_recipient: [u8; 32],
+        amount: u64,
+    ) -> Result<()> {
+        // Verify strategist
+        requires_auth(
+            ctx.accounts.signer.key(),
+            ctx.accounts.boring_account.load_state()?.strategist,
+        )?;
+
+        // Verify configuration matches the stored CCTP hash
+        circle_cctp::verify_configuration(ctx.accounts, destination_domain, mint_recipient)?;
+
+        // Burn the tokens from the holder
+        let amount = transfer_remote::resolve_amount_from_balance(
+            &*ctx.accounts.boring_account.load_state()?,
+            ctx.accounts.boring_account_ata.amount,
+            amount,
+        )?;
+        circle_cctp::execute_deposit_for_burn(
+            ctx.accounts,
+            ctx.bumps.message_sent_event_data,
+            destination_domain,
+            mint_recipient,
+            amount,
+        )?;
+
+        // Record the transfer in the bridging statistics
+        record_transfer(
+            &mut *ctx.accounts.boring_account.load_state_mut()?,
+            BridgeKind::CircleCctp,
+            destination_domain,
+            mint_recipient,
+            amount,
+            0,
+        )
+    }
+
+    /// Updates the approved Wormhole NTT configuration
+    ///
+    /// # Arguments
+    /// * `ctx` - The context of accounts
+    /// * `config` - The new NTT configuration data
+    ///
+    /// # Errors
+    /// * `CustomError::Unauthorized` - If the signer is not the current owner
+    /// * `CustomError::InvalidConfiguration` - If the recipient address is not set
+    ///
+    /// # Returns
+    /// * `Result<()>` - Result indicating success or containing an error
+    pub fn update_ntt_configuration(
+        ctx: Context<UpdateNttConfiguration>,
+        config: NttConfigurationData,
+    ) -> Result<()> {
+        // Check that signer is the current owner
+        requires_auth(
+            ctx.accounts.signer.key(),
+            ctx.accounts.boring_account.load_state()?.owner,
+        )?;
+        WormholeNtt::validate_config(&config)?;
+
+        let config_hash = config.compute_hash(&ctx.accounts.boring_account.key());
+        emit!(NttConfigurationUpdated {
+            config_hash,
+            ntt_manager_program: config.ntt_manager_program,
+            manager_config: config.manager_config,
+            mint: config.mint,
+            custody: config.custody,
+            outbox_rate_limit: config.outbox_rate_limit,
+            relayer_fee_recipient: config.relayer_fee_recipient,
+            mode: config.mode,
+            recipient_chain: config.recipient_chain,
+            recipient_address: config.recipient_address,
+        });
+
+        let ntt_config = &mut ctx.accounts.ntt_config;
+        ntt_config.bump = ctx.bumps.ntt_config;
+        ntt_config.config_hash = config_hash;
+
+        Ok(())
+    }
+
+    /// Sends tokens from the holder through a Wormhole NTT manager
+    ///
+    /// # Arguments
+    /// * `ctx` - The context of accounts
+    /// * `mode` - Whether the manager burns or locks the tokens
+    /// * `recipient_chain` - The Wormhole chain ID of the destination chain
+    /// * `recipient_address` - The 32-byte recipient address on the destination chain
+    /// * `amount` - The amount of tokens to send, or `SWEEP_ALL` to send the full balance
+    /// * `relayer_fee_lamports` - The lamports the signer pays the configured relayer
+    ///
+    /// # Errors
+    /// * `CustomError::Unauthorized` - If the signer is not the strategist
+    /// * `CustomError::InvalidConfiguration` - If the provided configuration doesn't match the stored NTT hash
+    /// * `CustomError::InsufficientBalance` - If a sweep would leave nothing to transfer
+    ///
+    /// # Returns
+    /// * `Result<()>` - Result indicating success or containing an error
+    pub fn transfer_remote_ntt(
+        ctx: Context<TransferRemoteNttContext>,
+        mode: NttMode,
+        recipient_chain: u16,
+        recipient_address: [u8; 32],
+        amount: u64,
+        relayer_fee_lamports: u64,
+    ) -> Result<()> {
+        // Verify strategist
+        requires_auth(
+            ctx.accounts.signer.key(),
+            ctx.accounts.boring_account.load_state()?.strategist,
+        )?;
+
+        // Verify configuration matches the stored NTT hash
+        wormhole_ntt::verify_configuration(ctx.accounts, mode, recipient_chain, recipient_address)?;
+
+        // Send the tokens and pay the relayer
+        let amount = transfer_remote::resolve_amount_from_balance(
+            &*ctx.accounts.boring_account.load_state()?,
+            ctx.accounts.boring_account_ata.amount,
+            amount,
+        )?;
+        wormhole_ntt::execute_transfer(
+            ctx.accounts,
+            mode,
+            ctx.bumps.outbox_item,
+            recipient_chain,
+            recipient_address,
+            amount,
+        )?;
+        wormhole_ntt::pay_relayer_fee(ctx.accounts, relayer_fee_lamports)?;
+
+        // Record the transfer in the bridging statistics
+        record_transfer(
+            &mut *ctx.accounts.boring_account.load_state_mut()?,
+            BridgeKind::WormholeNtt,
+            u32::from(recipient_chain),
+            recipient_address,
+            amount,
+            0,
+        )
+    }
+
+    /// Updates the approved LayerZero OFT configuration
+    ///
+    /// # Arguments
+    /// * `ctx` - The context of accounts
+    /// * `config` - The new OFT configuration data
+    ///
+    /// # Errors
+    /// * `CustomError::Unauthorized` - If the signer is not the current owner
+    /// * `CustomError::InvalidConfiguration` - If the recipient is not set
+    ///
+    /// # Returns
+    /// * `Result<()>` - Result indicating success or containing an error
+    pub fn update_oft_configuration(
+        ctx: Context<UpdateOftConfiguration>,
+        config: OftConfigurationData,
+    ) -> Result<()> {
+        // Check that signer is the current owner
+        requires_auth(
+            ctx.accounts.signer.key(),
+            ctx.accounts.boring_account.load_state()?.owner,
+        )?;
+        LayerZeroOft::validate_config(&config)?;
+
+        let config_hash = config.compute_hash(&ctx.accounts.boring_account.key());
+        emit!(OftConfigurationUpdated {
+            config_hash,
+            oft_program: config.oft_program,
+            endpoint_program: config.endpoint_program,
+            oft_store: config.oft_store,
+            peer: config.peer,
+            token_escrow: config.token_escrow,
+            token_mint: config.token_mint,
+            dst_eid: config.dst_eid,
+            to: config.to,
+        });
+
+        let oft_config = &mut ctx.accounts.oft_config;
+        oft_config.bump = ctx.bumps.oft_config;
+        oft_config.config_hash = config_hash;
+
+        Ok(())
+    }
+
+    /// Sends tokens from the holder through a LayerZero V2 OFT program
+    ///
+    /// The accounts of the endpoint's `send` are passed as remaining accounts, after the
+    /// endpoint program.
+    ///
+    /// # Arguments
+    /// * `ctx` - The context of accounts
+    /// * `dst_eid` - The LayerZero endpoint ID of the destination chain
+    /// * `to` - The 32-byte recipient address on the destination chain
+    /// * `amount` - The amount of tokens to send, or `SWEEP_ALL` to send the full balance
+    /// * `min_amount` - The least amount received on the destination chain
+    /// * `options` - Executor options passed to the endpoint
+    /// * `native_fee` - The native fee quoted with the OFT program's `quote_send`, paid by the signer
+    ///
+    /// # Errors
+    /// * `CustomError::Unauthorized` - If the signer is not the strategist
+    /// * `CustomError::InvalidConfiguration` - If the provided configuration doesn't match the stored OFT hash
+    /// * `CustomError::InsufficientBalance` - If a sweep would leave nothing to transfer
+    ///
+    /// # Returns
+    /// * `Result<()>` - Result indicating success or containing an error
+    pub fn transfer_remote_oft<'info>(
+        ctx: Context<'_, '_, '_, 'info, TransferRemoteOftContext<'info>>,
+        dst_eid: u32,
+        to: [u8; 32],
+        amount: u64,
+        min_amount: u64,
+        options: Vec<u8>,
+        native_fee: u64,
+    ) -> Result<()> {
+        // Verify strategist
+        requires_auth(
+            ctx.accounts.signer.key(),
+            ctx.accounts.boring_account.load_state()?.strategist,
+        )?;
+
+        // Verify configuration matches the stored OFT hash
+        layerzero_oft::verify_configuration(ctx.accounts, dst_eid, to)?;
+
+        // Send the tokens
+        let amount = transfer_remote::resolve_amount_from_balance(
+            &*ctx.accounts.boring_account.load_state()?,
+            ctx.accounts.boring_account_ata.amount,
+            amount,
+        )?;
+        layerzero_oft::execute_send(
+            ctx.accounts,
+            ctx.remaining_accounts,
+            dst_eid,
+            to,
+            amount,
+            OftSendOptions {
+                min_amount,
+                options,
+                native_fee,
+            },
+        )?;
+
+        // Record the transfer in the bridging statistics
+        record_transfer(
+            &mut *ctx.accounts.boring_account.load_state_mut()?,
+            BridgeKind::LayerZeroOft,
+            dst_eid,
+            to,
+            amount,
+            0,
+        )
+    }
+
+    /// Configures which Hyperlane messages the holder accepts
+    ///
+    /// # Arguments
+    /// * `ctx` - The context of accounts
+    /// * `mailbox` - The mailbox program that delivers messages to the holder
+    /// * `ism` - The ISM messages to the holder must be verified by, or `None` for the mailbox's default ISM
+    /// * `allowed_senders` - The origin domains and senders messages are accepted from
+    ///
+    /// # Errors
+    /// * `CustomError::Unauthorized` - If the signer is not the current owner
+    /// * `CustomError::TooManyAllowedSenders` - If more than `MAX_ALLOWED_SENDERS` senders are given
+    ///
+    /// # Returns
+    /// * `Result<()>` - Result indicating success or containing an error
+    pub fn set_inbound_config(
+        ctx: Context<SetInboundConfig>,
+        mailbox: Pubkey,
+        ism: Option<Pubkey>,
+        allowed_senders: Vec<AllowedSender>,
+    ) -> Result<()> {
+        // Check that signer is the current owner
+        requires_auth(
+            ctx.accounts.signer.key(),
+            ctx.accounts.boring_account.load_state()?.owner,
+        )?;
+        require!(
+            allowed_senders.len() <= MAX_ALLOWED_SENDERS,
+            CustomError::TooManyAllowedSenders
+        );
+
+        emit!(InboundConfigUpdated {
+            boring_account: ctx.accounts.boring_account.key(),
+            mailbox,
+            ism,
+            allowed_senders: allowed_senders.clone(),
+        });
+
+        let inbound = &mut ctx.accounts.inbound;
+        inbound.bump = ctx.bumps.inbound;
+        inbound.mailbox = mailbox;
+        inbound.ism = ism;
+        inbound.allowed_senders = allowed_senders;
+
+        Ok(())
+    }
+
+    /// Configures which recipients the holder may dispatch Hyperlane messages to
+    ///
+    /// # Arguments
+    /// * `ctx` - The context of accounts
+    /// * `allowed_recipients` - The destination domains and recipients messages may be sent to
+    ///
+    /// # Errors
+    /// * `CustomError::Unauthorized` - If the signer is not the current owner
+    /// * `CustomError::TooManyAllowedRecipients` - If more than `MAX_ALLOWED_RECIPIENTS` recipients are given
+    ///
+    /// # Returns
+    /// * `Result<()>` - Result indicating success or containing an error
+    pub fn set_dispatch_allowlist(
+        ctx: Context<SetDispatchAllowlist>,
+        allowed_recipients: Vec<AllowedRecipient>,
+    ) -> Result<()> {
+        // Check that signer is the current owner
+        requires_auth(
+            ctx.accounts.signer.key(),
+            ctx.accounts.boring_account.load_state()?.owner,
+        )?;
+        require!(
+            allowed_recipients.len() <= MAX_ALLOWED_RECIPIENTS,
+            CustomError::TooManyAllowedRecipients
+        );
+
+        emit!(DispatchAllowlistUpdated {
+            boring_account: ctx.accounts.boring_account.key(),
+            allowed_recipients: allowed_recipients.clone(),
+        });
+
+        let dispatch = &mut ctx.accounts.dispatch;
+        dispatch.bump = ctx.bumps.dispatch;
+        dispatch.allowed_recipients = allowed_recipients;
+
+        Ok(())
+    }
+
+    /// Dispatches an arbitrary Hyperlane message with the holder as its sender
+    ///
+    /// The mailbox and IGP accounts are the ones of the holder's stored configuration, which
+    /// must still match the configuration hash.
+    ///
+    /// # Arguments
+    /// * `ctx` - The context of accounts
+    /// * `destination_domain` - The domain ID of the destination chain
+    /// * `recipient` - The 32-byte recipient address on the destination chain
+    /// * `message_body` - The body of the message
+    /// * `gas_amount` - The destination gas to pay the IGP for, if any
+    /// * `max_gas_payment_lamports` - The most lamports the signer may spend on the dispatch
+    ///
+    /// # Errors
+    /// * `CustomError::Unauthorized` - If the signer is neither the owner nor the strategist
+    /// * `CustomError::InvalidConfiguration` - If the stored configuration doesn't match stored hash
+    /// * `CustomError::RecipientNotAllowed` - If the recipient is not in the dispatch allowlist
+    /// * `CustomError::GasPaymentTooHigh` - If the signer spent more than `max_gas_payment_lamports`
+    ///
+    /// # Returns
+    /// * `Result<()>` - Result indicating success or containing an error
+    pub fn dispatch_message(
+        ctx: Context<DispatchMessageContext>,
+        destination_domain: u32,
+        recipient: [u8; 32],
+        message_body: Vec<u8>,
+        gas_amount: Option<u64>,
+        max_gas_payment_lamports: u64,
+    ) -> Result<()> {
+        // Verify owner or strategist
+        let signer = ctx.accounts.signer.key();
+        let message_nonce = {
+            let boring_account = ctx.accounts.boring_account.load_state()?;
+            require!(
+                signer == boring_account.owner || signer == boring_account.strategist,
+                CustomError::Unauthorized
+            );
+            boring_account.message_nonce
+        };
+
+        // Verify the stored configuration has not been superseded by `update_configuration`
+        transfer_remote::verify_configuration_hash(
+            &ctx.accounts.boring_account,
+            &ctx.accounts.config_account.config,
+        )?;
+        require!(
+            ctx.accounts
+                .dispatch
+                .is_allowed_recipient(destination_domain, &recipient),
+            CustomError::RecipientNotAllowed
+        );
+
+        // Dispatch the message and pay for its gas
+        let accounts = ctx
+            .accounts
+            .dispatch_accounts(ctx.bumps.unique_message, message_nonce);
+        let lamports_before = accounts.signer.lamports();
+        let message_id =
+            dispatch_message::dispatch(&accounts, destination_domain, recipient, message_body)?;
+        if let Some(gas_amount) = gas_amount {
+            dispatch_message::pay_for_gas(&accounts, message_id, destination_domain, gas_amount)?;
+        }
+        transfer_remote::verify_gas_payment(
+            &ctx.accounts.signer,
+            lamports_before,
+            max_gas_payment_lamports,
+        )?;
+
+        // The next message derives a fresh unique message account.
+        ctx.accounts.boring_account.load_state_mut()?.message_nonce = message_nonce
+            .checked_add(1)
+            .ok_or(CustomError::InvalidState)?;
+
+        emit!(MessageDispatched {
+            destination_domain,
+            recipient,
+            message_id,
+            gas_amount,
+            message_nonce,
+        });
+
+        Ok(())
+    }
+
+    /// Sets the ISM that verifies Hyperlane messages delivered to this program
+    ///
+    /// The mailbox queries the ISM per recipient program rather than per holder, so it is set by
+    /// the program's upgrade authority. Holders only accept messages while it matches the ISM
+    /// their owner chose with `set_inbound_config`.
+    ///
+    /// # Arguments
+    /// * `ctx` - The context of accounts
+    /// * `ism` - The ISM program, or `None` to use the mailbox's default ISM
+    ///
+    /// # Errors
+    /// * `CustomError::Unauthorized` - If the signer is not the program's upgrade authority
+    ///
+    /// # Returns
+    /// * `Result<()>` - Result indicating success or containing an error
+    pub fn set_recipient_ism(ctx: Context<SetRecipientIsm>, ism: Option<Pubkey>) -> Result<()> {
+        emit!(RecipientIsmUpdated { ism });
+
+        let recipient_ism = &mut ctx.accounts.recipient_ism;
+        recipient_ism.bump = ctx.bumps.recipient_ism;
+        recipient_ism.ism = ism;
+
+        Ok(())
+    }
+
+    /// Transfers tokens remotely across several approved routes in one instruction
+    ///
+    /// Each leg consumes `BATCH_LEG_ACCOUNTS` remaining accounts, in leg order, including its own
+    /// unique message PDA. Any remaining accounts after the legs are the extra accounts of the
+    /// mints' transfer hooks. Legs execute in sequence and the whole batch fails if any leg fails.
+    ///
+    /// # Arguments
+    /// * `ctx` - The context of accounts
+    /// * `legs` - The route and amount of each leg; an amount of `SWEEP_ALL` transfers the full balance
+    /// * `max_gas_payment_lamports` - The most lamports the signer may spend on gas payment and
+    ///   message rent across all legs
+    ///
+    /// # Errors
+    /// * `CustomError::Unauthorized` - If the signer is not the strategist
+    /// * `CustomError::InvalidBatchAccounts` - If the legs don't match the remaining accounts
+    /// * `CustomError::InvalidRoute` - If a route account is not the approved route for its leg
+    /// * `CustomError::InvalidConfiguration` - If a leg's accounts don't match its route
+    /// * `CustomError::InvalidDecimals` - If a route's decimals don't match its mint
+    /// * `CustomError::GasPaymentTooHigh` - If the signer spent more than `max_gas_payment_lamports`
+    ///
+    /// # Returns
+    /// * `Result<()>` - Result indicating success or containing an error
+    pub fn transfer_remote_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, TransferRemoteBatchContext<'info>>,
+        legs: Vec<BatchLeg>,
+        max_gas_payment_lamports: u64,
+    ) -> Result<()> {
+        // Verify strategist
+        requires_auth(
+            ctx.accounts.signer.key(),
+            ctx.accounts.boring_account.load_state()?.strategist,
+        )?;
+
+        let leg_accounts_len = legs.len() * transfer_remote_batch::BATCH_LEG_ACCOUNTS;
+        require!(
+            !legs.is_empty() && ctx.remaining_accounts.len() >= leg_accounts_len,
+            CustomError::InvalidBatchAccounts
+        );
+
+        let (leg_accounts, hook_accounts) = ctx.remaining_accounts.split_at(leg_accounts_len);
+        let lamports_before = ctx.accounts.signer.lamports();
+        for (leg, infos) in legs
+            .iter()
+            .zip(leg_accounts.chunks(transfer_remote_batch::BATCH_LEG_ACCOUNTS))
+        {
+            let completed = transfer_remote_batch::execute_leg(
+                &ctx.accounts.boring_account,
+                &ctx.accounts.signer,
+                &ctx.accounts.system_program,
+                leg,
+                infos,
+                hook_accounts,
+            )?;
+
+            // Record the transfer in the bridging statistics
+            record_transfer(
+                &mut *ctx.accounts.boring_account.load_state_mut()?,
+                BridgeKind::HyperlaneWarpRoute,
+                completed.destination_domain,
+                completed.evm_recipient,
+                completed.amount,
+                completed.transfer_fee,
+            )?;
+        }
+
+        transfer_remote::verify_gas_payment(
+            &ctx.accounts.signer,
+            lamports_before,
+            max_gas_payment_lamports,
+        )
+    }
+
+    /// Updates the amount of tokens a sweep leaves in the holder
+    ///
+    /// # Arguments
+    /// * `ctx` - The context of accounts
+    /// * `sweep_reserve` - The amount of tokens to keep when sweeping the full balance
+    ///
+    /// # Errors
+    /// * `CustomError::Unauthorized` - If the signer is not the current owner
+    ///
+    /// # Returns
+    /// * `Result<()>` - Result indicating success or containing an error
+    pub fn update_sweep_reserve(ctx: Context<UpdateOwner>, sweep_reserve: u64) -> Result<()> {
+        // Check that signer is the current owner
+        let boring_account = &mut *ctx.accounts.boring_account.load_state_mut()?;
+        let old_sweep_reserve = boring_account.sweep_reserve;
+        requires_auth(ctx.accounts.signer.key(), boring_account.owner)?;
+
+        // Update the sweep reserve
+        boring_account.sweep_reserve = sweep_reserve;
+
+        emit!(SweepReserveUpdated {
+            old_sweep_reserve,
+            new_sweep_reserve: sweep_reserve,
+        });
+
+        Ok(())
+    }
+
+    /// Transfers tokens remotely on behalf of the holder when the auto-bridge policy allows it
+    ///
+    /// Anyone may call this. The keeper pays bridge fees like the strategist does, and is paid the
+    /// policy's keeper bounty from the holder's fee vault.
+    ///
+    /// # Arguments
+    /// * `ctx` - The context of accounts
+    /// * `destination_domain` - The domain ID of the destination chain
+    /// * `recipient` - The recipient on the destination chain, as configured
+    /// * `remote_decimals` - The decimals of the token on the destination chain
+    /// * `max_gas_payment_lamports` - The most lamports the keeper may spend on gas payment and message rent
+    ///
+    /// # Errors
+    /// * `CustomError::AutoBridgeDisabled` - If the auto-bridge policy is disabled
+    /// * `CustomError::InvalidAutoBridgePolicy` - If the policy has no positive minimum interval
+    /// * `CustomError::AutoBridgeTooSoon` - If the minimum interval since the last transfer has not passed
+    /// * `CustomError::AutoBridgeBelowThreshold` - If the holder's balance is below the policy threshold
+    /// * `CustomError::InvalidConfiguration` - If the provided configuration doesn't match stored hash
+    /// * `CustomError::FeeVaultInsufficient` - If the fee vault cannot pay the keeper bounty
+    /// * `CustomError::GasPaymentTooHigh` - If the keeper spent more than `max_gas_payment_lamports`
+    ///
+    /// # Returns
+    /// * `Result<()>` - Result indicating success or containing an error
+    pub fn crank_transfer_remote<'info>(
+        ctx: Context<'_, '_, '_, 'info, CrankTransferRemoteContext<'info>>,
+        destination_domain: u32,
+        recipient: Recipient,
+        remote_decimals: u8,
+        max_gas_payment_lamports: u64,
+    ) -> Result<()> {
+        let accounts = ctx
+            .accounts
+            .transfer
+            .transfer_accounts(ctx.bumps.transfer.unique_message, ctx.remaining_accounts);
+
+        // Check the policy allows a transfer now
+        let amount = auto_bridge::bridge_amount(
+            &*accounts.boring_account.load_state()?,
+            accounts.boring_account_ata.amount,
+            Clock::get()?.unix_timestamp,
+        )?;
+
+        // Verify configuration matches stored hash, using the mint's decimals
+        transfer_remote::verify_configuration(
+            &accounts,
+            destination_domain,
+            recipient,
+            remote_decimals,
+        )?;
+        let evm_recipient = recipient.to_bytes32();
+
+        // Transfer tokens to keeper
+        let transfer_fee = transfer_remote::transfer_tokens_to_strategist(&accounts, amount)?;
+        let amount = amount - transfer_fee;
+
+        // Create and execute the transfer remote instruction
+        let lamports_before = accounts.signer.lamports();
+        transfer_remote::execute_transfer_remote(
+            &accounts,
+            destination_domain,
+            evm_recipient,
+            remote_decimals,
+            amount,
+        )?;
+        transfer_remote::verify_gas_payment(
+            accounts.signer,
+            lamports_before,
+            max_gas_payment_lamports,
+        )?;
+
+        // Pay the keeper
+        let bounty = auto_bridge::pay_keeper_bounty(ctx.accounts, ctx.bumps.fee_vault)?;
+        emit!(KeeperRewarded {
+            keeper: ctx.accounts.transfer.signer.key(),
+            bounty_lamports: bounty,
+        });
+
+        // Record the transfer in the bridging statistics
+        record_transfer(
+            &mut *ctx.accounts.transfer.boring_account.load_state_mut()?,
+            BridgeKind::HyperlaneWarpRoute,
+            destination_domain,
+            evm_recipient,
+            amount,
+            transfer_fee,
+        )
+    }
+
+    /// Returns the program version via return data
+    /// Used to verify the program was upgraded
+    ///
+    /// # Returns
+    /// * `Result<VersionInfo>` - The semver components, git commit and enabled features of the build
+    pub fn version(_ctx: Context<Version>) -> Result<VersionInfo> {
+        verbose_msg!("Program version: {}", env!("CARGO_PKG_VERSION"));
+        Ok(VersionInfo::current())
+    }
+
+    /// Returns the state of the Boring Bridge Holder via return data
+    ///
+    /// # Arguments
+    /// * `ctx` - The context of accounts
+    ///
+    /// # Errors
+    /// * `CustomError::InvalidState` - If a domain total records an unknown bridge kind
+    ///
+    /// # Returns
+    /// * `Result<StateView>` - The owner, strategist and config hash of the holder
+    pub fn get_state(ctx: Context<GetState>) -> Result<StateView> {
+        StateView::try_from(&*ctx.accounts.boring_account.load_state()?)
+    }
+
+    /// Handles the Hyperlane message recipient interface
+    ///
+    /// The mailbox and relayers call `handle`, `handle_account_metas`, `interchain_security_module`
+    /// and `interchain_security_module_account_metas` with Hyperlane's own discriminators, which
+    /// Anchor routes here.
+    pub fn fallback<'info>(
+        program_id: &Pubkey,
+        accounts: &'info [AccountInfo<'info>],
+        data: &[u8],
+    ) -> Result<()> {
+        message_recipient::process(program_id, accounts, data)
+    }
+
+    /// Estimates the interchain gas payment for a message via return data
+    ///
+    /// # Arguments
+    /// * `ctx` - The context of accounts
+    /// * `destination_domain` - The domain ID of the destination chain
+    /// * `gas_amount` - The destination gas amount of the message, before any IGP overhead
+    ///
+    /// # Errors
+    /// * `CustomError::InvalidIgpAccount` - If the IGP accounts cannot be decoded or have no
+    ///   gas oracle for the destination domain
+    /// * `CustomError::GasQuoteOverflow` - If the payment doesn't fit in a `u64`
+    ///
+    /// # Returns
+    /// * `Result<u64>` - The estimated gas payment in lamports
+    pub fn quote_gas_payment(
+        ctx: Context<QuoteGasPayment>,
+        destination_domain: u32,
+        gas_amount: u64,
+    ) -> Result<u64> {
+        igp::quote_gas_payment(
+            &ctx.accounts.igp,
+            ctx.accounts.overhead_igp.as_ref(),
+            destination_domain,
+            gas_amount,
+        )
+    }
+}
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(
+        init,
+        payer = signer,
+        space = 8 + BoringState::INIT_SPACE,
+        seeds = [b"boring_state", signer.key().as_ref()],
+        bump
+    )]
+    pub boring_account: AccountLoader<'info, BoringState>,
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>, // Only needed when creating or initializing an account.
+}
+
+#[derive(Accounts)]
+pub struct UpdateOwner<'info> {
+    #[account(
+        mut,
+        seeds = [b"boring_state", boring_account.load_state()?.creator.as_ref()],
+        bump = boring_account.load_state()?.bump,
+    )]
+    pub boring_account: AccountLoader<'info, BoringState>,
+    pub signer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateStrategist<'info> {
+    #[account(
+        mut,
+        seeds = [b"boring_state", boring_account.load_state()?.creator.as_ref()],
+        bump = boring_account.load_state()?.bump,
+    )]
+    pub boring_account: AccountLoader<'info, BoringState>,
+    pub signer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateConfiguration<'info> {
+    #[account(
+        mut,
+        seeds = [b"boring_state", boring_account.load_state()?.creator.as_ref()],
+        bump = boring_account.load_state()?.bump,
+    )]
+    pub boring_account: AccountLoader<'info, BoringState>,
+    /// The holder's stored configuration
+    /// CHECK: Updated only if the holder stores its configuration
+    #[account(
+        mut,
+        seeds = [b"boring_config", boring_account.key().as_ref()],
+        bump
+    )]
+    pub config_account: UncheckedAccount<'info>,
+    pub signer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateConfigurationHash<'info> {
+    #[account(
+        mut,
+        seeds = [b"boring_state", boring_account.load_state()?.creator.as_ref()],
+        bump = boring_account.load_state()?.bump,
+    )]
+    pub boring_account: AccountLoader<'info, BoringState>,
+    /// Owner or strategist
+    pub signer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateState<'info> {
+    /// The state account to migrate, which may not deserialize as the current layout
+    /// CHECK: Checked in migrate_state
+    #[account(mut)]
+    pub boring_account: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Warp route accounts shared by the transfer remote contexts checked against the config hash
+///
+/// The configured accounts are checked by hashing them in `transfer_remote::verify_configuration`,
+/// and the message accounts against the PDAs of the holder's message nonce.
+#[derive(Accounts)]
+pub struct WarpRouteTransferAccounts<'info> {
+    #[account(
+        mut,
+        seeds = [b"boring_state", boring_account.load_state()?.creator.as_ref()],
+        bump = boring_account.load_state()?.bump,
+    )]
+    pub boring_account: AccountLoader<'info, BoringState>,
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    /// Target program
+    /// CHECK: Checked in config hash
+    pub target_program: AccountInfo<'info>,
+    /// System Program account
+    pub system_program: Program<'info, System>,
+    /// NOOP
+    /// CHECK: Checked in config hash
+    pub noop: AccountInfo<'info>,
+    /// Token PDA owned by program_target
+    /// CHECK: Checked in config hash
+    pub token_pda: AccountInfo<'info>,
+    /// Mailbox Program
+    /// CHECK: Checked in config hash
+    pub mailbox_program: AccountInfo<'info>,
+    /// Mailbox Outbox
+    #[account(mut)]
+    /// CHECK: Checked in config hash
+    pub mailbox_outbox: AccountInfo<'info>,
+    /// Message Dispatch Authority
+    /// CHECK: Checked in config hash
+    pub message_dispatch_authority: AccountInfo<'info>,
+    /// Unique message / gas payment account, derived from the holder's message nonce
+    #[account(
+        seeds = [
+            b"unique_message",
+            boring_account.key().as_ref(),
+            &boring_account.load_state()?.message_nonce.to_le_bytes()
+        ],
+        bump
+    )]
+    /// CHECK: Checked against PDA
+    pub unique_message: AccountInfo<'info>,
+    /// Message storage PDA
+    #[account(
+        mut,
+        seeds = [
+            b"hyperlane",
+            b"-",
+            b"dispatched_message",
+            b"-",
+            unique_message.key().as_ref()
+        ],
+        bump,
+        seeds::program = mailbox_program.key()
+    )]
+    /// CHECK: Checked against PDA
+    pub message_storage_pda: AccountInfo<'info>,
+    /// IGP Program
+    /// CHECK: Checked in config hash
+    pub igp_program: AccountInfo<'info>,
+    /// IGP Program Data
+    #[account(mut)]
+    /// CHECK: Checked in config hash
+    pub igp_program_data: AccountInfo<'info>,
+    /// Gas payment PDA
+    #[account(
+        mut,
+        seeds = [
+            b"hyperlane_igp",
+            b"-",
+            b"gas_payment",
+            b"-",
+            unique_message.key().as_ref()
+        ],
+        bump,
+        seeds::program = igp_program.key()
+    )]
+    /// CHECK: Checked against PDA
+    pub gas_payment_pda: AccountInfo<'info>,
+    /// IGP Account
+    /// CHECK: Checked in config hash
+    pub igp_account: AccountInfo<'info>,
+    /// Token Sender
+    #[account(mut)]
+    /// CHECK: Checked in config hash
+    pub token_sender: AccountInfo<'info>,
+    /// Token 2022
+    #[account(
+        address = anchor_spl::token_2022::ID
+    )]
+    /// CHECK: Checked in config hash
+    pub token_2022: Program<'info, Token2022>,
+    /// Mint / mint authority PDA of the warp route
+    #[account(mut)]
+    /// CHECK: Checked in config hash
+    pub mint_auth: AccountInfo<'info>,
+    /// Mint of the bridged token, whose decimals are used for the transfer
+    /// CHECK: Checked in config hash
+    pub mint: InterfaceAccount<'info, Mint>,
+    /// Boring Account Associated Token Account
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = boring_account,
+        associated_token::token_program = token_2022
+    )]
+    /// CHECK: Checked against PDA
+    pub boring_account_ata: InterfaceAccount<'info, TokenAccount>,
+    /// Strategist Associated Token Account, which is the keeper's for `crank_transfer_remote`
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = signer.key(),
+        associated_token::token_program = token_2022
+    )]
+    /// CHECK: Checked against PDA
+    pub strategist_ata: InterfaceAccount<'info, TokenAccount>,
+}
+
+#[derive(Accounts)]
+pub struct TransferRemoteContext<'info> {
+    pub transfer: WarpRouteTransferAccounts<'info>,
+}
+
+#[derive(Accounts)]
+pub struct TransferRemoteWithPayloadContext<'info> {
+    pub transfer: WarpRouteTransferAccounts<'info>,
+    /// Dispatch allowlist the payload recipient must be in
+    #[account(
+        seeds = [b"boring_dispatch", transfer.boring_account.key().as_ref()],
+        bump = dispatch.bump,
+    )]
+    pub dispatch: Account<'info, BoringDispatch>,
+    /// Unique message / gas payment account of the companion message, derived from the
+    /// message nonce after the transfer's
+    #[account(
+        seeds = [
+            b"unique_message",
+            transfer.boring_account.key().as_ref(),
+            &transfer.boring_account.load_state()?.next_message_nonce()?.to_le_bytes()
+        ],
+        bump
+    )]
+    /// CHECK: Checked against PDA
+    pub payload_message: AccountInfo<'info>,
+    /// Message storage PDA of the companion message
+    #[account(
+        mut,
+        seeds = [
+            b"hyperlane",
+            b"-",
+            b"dispatched_message",
+            b"-",
+            payload_message.key().as_ref()
+        ],
+        bump,
+        seeds::program = transfer.mailbox_program.key()
+    )]
+    /// CHECK: Checked against PDA
+    pub payload_message_storage_pda: AccountInfo<'info>,
+    /// Gas payment PDA of the companion message
+    #[account(
+        mut,
+        seeds = [
+            b"hyperlane_igp",
+            b"-",
+            b"gas_payment",
+            b"-",
+            payload_message.key().as_ref()
+        ],
+        bump,
+        seeds::program = transfer.igp_program.key()
+    )]
+    /// CHECK: Checked against PDA
+    pub payload_gas_payment_pda: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct StoreConfiguration<'info> {
+    #[account(
+        mut,
+        seeds = [b"boring_state", boring_account.load_state()?.creator.as_ref()],
+        bump = boring_account.load_state()?.bump,
+    )]
+    pub boring_account: AccountLoader<'info, BoringState>,
+    #[account(
+        init_if_needed,
+        payer = signer,
+        space = 8 + BoringConfig::INIT_SPACE,
+        seeds = [b"boring_config", boring_account.key().as_ref()],
+        bump
+    )]
+    pub config_account: Account<'info, BoringConfig>,
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct GetConfiguration<'info> {
+    #[account(
+        seeds = [b"boring_state", boring_account.load_state()?.creator.as_ref()],
+        bump = boring_account.load_state()?.bump,
+    )]
+    pub boring_account: AccountLoader<'info, BoringState>,
+    #[account(
+        seeds = [b"boring_config", boring_account.key().as_ref()],
+        bump
+    )]
+    pub config_account: Account<'info, BoringConfig>,
+}
+
+#[derive(Accounts)]
+pub struct TransferRemoteStoredContext<'info> {
+    #[account(
+        mut,
+        seeds = [b"boring_state", boring_account.load_state()?.creator.as_ref()],
+        bump = boring_account.load_state()?.bump,
+    )]
+    pub boring_account: AccountLoader<'info, BoringState>,
+    #[account(
+        seeds = [b"boring_config", boring_account.key().as_ref()],
+        bump
+    )]
+    pub config_account: Account<'info, BoringConfig>,
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    /// Target program
+    /// CHECK: Checked against stored config
+    #[account(address = config_account.config.target_program)]
+    pub target_program: AccountInfo<'info>,
+    /// System Program account
+    pub system_program: Program<'info, System>,
+    /// NOOP
+    /// CHECK: Checked against stored config
+    #[account(address = config_account.config.noop)]
+    pub noop: AccountInfo<'info>,
+    /// Token PDA owned by program_target
+    /// CHECK: Checked against stored config
+    #[account(address = config_account.config.token_pda)]
+    pub token_pda: AccountInfo<'info>,
+    /// Mailbox Program
+    /// CHECK: Checked against stored config
+    #[account(address = config_account.config.mailbox_program)]
+    pub mailbox_program: AccountInfo<'info>,
+    /// Mailbox Outbox
+    #[account(mut, address = config_account.config.mailbox_outbox)]
+    /// CHECK: Checked against stored config
+    pub mailbox_outbox: AccountInfo<'info>,
+    /// Message Dispatch Authority
+    /// CHECK: Checked against stored config
+    #[account(address = config_account.config.message_dispatch_authority)]
+    pub message_dispatch_authority: AccountInfo<'info>,
+    /// Unique message / gas payment account, derived from the holder's message nonce
+    #[account(
+        seeds = [
+            b"unique_message",
+            boring_account.key().as_ref(),
+            &boring_account.load_state()?.message_nonce.to_le_bytes()
+        ],
+        bump
+    )]
+    /// CHECK: Checked against PDA
+    pub unique_message: AccountInfo<'info>,
+    /// Message storage PDA
+    #[account(
+        mut,
+        seeds = [
+            b"hyperlane",
+            b"-",
+            b"dispatched_message",
+            b"-",
+            unique_message.key().as_ref()
+        ],
+        bump,
+        seeds::program = mailbox_program.key()
+    )]
+    /// CHECK: Checked against PDA
+    pub message_storage_pda: AccountInfo<'info>,
+    /// IGP Program
+    /// CHECK: Checked against stored config
+    #[account(address = config_account.config.igp_program)]
+    pub igp_program: AccountInfo<'info>,
+    /// IGP Program Data
+    #[account(mut, address = config_account.config.igp_program_data)]
+    /// CHECK: Checked against stored config
+    pub igp_program_data: AccountInfo<'info>,
+    /// Gas payment PDA
+    #[account(
+        mut,
+        seeds = [
+            b"hyperlane_igp",
+            b"-",
+            b"gas_payment",
+            b"-",
+            unique_message.key().as_ref()
+        ],
+        bump,
+        seeds::program = igp_program.key()
+    )]
+    /// CHECK: Checked against PDA
+    pub gas_payment_pda: AccountInfo<'info>,
+    /// IGP Account
+    /// CHECK: Checked against stored config
+    #[account(address = config_account.config.igp_account)]
+    pub igp_account: AccountInfo<'info>,
+    /// Token Sender
+    #[account(mut, address = config_account.config.token_sender)]
+    /// CHECK: Checked against stored config
+    pub token_sender: AccountInfo<'info>,
+    /// Token 2022
+    #[account(
+        address = config_account.config.token_2022_program
+    )]
+    pub token_2022: Program<'info, Token2022>,
+    /// Mint / mint authority PDA of the warp route
+    #[account(mut, address = config_account.config.mint_auth)]
+    /// CHECK: Checked against stored config
+    pub mint_auth: AccountInfo<'info>,
+    /// Mint of the bridged token, whose decimals are used for the transfer
+    #[account(address = config_account.config.mint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    /// Boring Account Associated Token Account
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = boring_account,
+        associated_token::token_program = token_2022
+    )]
+    pub boring_account_ata: InterfaceAccount<'info, TokenAccount>,
+    /// Strategist Associated Token Account
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = signer.key(),
+        associated_token::token_program = token_2022
+    )]
+    pub strategist_ata: InterfaceAccount<'info, TokenAccount>,
+}
+
+#[derive(Accounts)]
+pub struct CrankTransferRemoteContext<'info> {
+    /// Warp route accounts, whose signer is the keeper triggering the transfer
+    pub transfer: WarpRouteTransferAccounts<'info>,
+    /// Fee vault paying the keeper bounty
+    #[account(
+        mut,
+        seeds = [b"fee_vault", transfer.boring_account.key().as_ref()],
+        bump,
+    )]
+    pub fee_vault: SystemAccount<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(route_id: u8)]
+pub struct SetRoute<'info> {
+    #[account(
+        seeds = [b"boring_state", boring_account.load_state()?.creator.as_ref()],
+        bump = boring_account.load_state()?.bump,
+    )]
+    pub boring_account: AccountLoader<'info, BoringState>,
+    #[account(
+        init_if_needed,
+        payer = signer,
+        space = 8 + BoringRoute::INIT_SPACE,
+        seeds = [b"boring_route", boring_account.key().as_ref(), &[route_id]],
+        bump
+    )]
+    pub route: Account<'info, BoringRoute>,
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateCctpConfiguration<'info> {
+    #[account(
+        seeds = [b"boring_state", boring_account.load_state()?.creator.as_ref()],
+        bump = boring_account.load_state()?.bump,
+    )]
+    pub boring_account: AccountLoader<'info, BoringState>,
+    #[account(
+        init_if_needed,
+        payer = signer,
+        space = 8 + BoringCctpConfig::INIT_SPACE,
+        seeds = [b"boring_cctp_config", boring_account.key().as_ref()],
+        bump
+    )]
+    pub cctp_config: Account<'info, BoringCctpConfig>,
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DepositForBurnContext<'info> {
+    #[account(
+        mut,
+        seeds = [b"boring_state", boring_account.load_state()?.creator.as_ref()],
+        bump = boring_account.load_state()?.bump,
+    )]
+    pub boring_account: AccountLoader<'info, BoringState>,
+    #[account(
+        seeds = [b"boring_cctp_config", boring_account.key().as_ref()],
+        bump = cctp_config.bump,
+    )]
+    pub cctp_config: Account<'info, BoringCctpConfig>,
+    /// Strategist, which pays the rent of the message account
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    /// TokenMessengerMinter's sender authority
+    #[account(
+        seeds = [b"sender_authority"],
+        bump,
+        seeds::program = token_messenger_minter_program.key()
+    )]
+    /// CHECK: Checked against PDA
+    pub sender_authority_pda: AccountInfo<'info>,
+    /// Boring Account Associated Token Account, from which tokens are burned
+    #[account(
+        mut,
+        associated_token::mint = burn_token_mint,
+        associated_token::authority = boring_account,
+        associated_token::token_program = token_program
+    )]
+    pub boring_account_ata: InterfaceAccount<'info, TokenAccount>,
+    /// MessageTransmitter state
+    #[account(mut)]
+    /// CHECK: Checked in CCTP config hash
+    pub message_transmitter: AccountInfo<'info>,
+    /// TokenMessenger state
+    /// CHECK: Checked in CCTP config hash
+    pub token_messenger: AccountInfo<'info>,
+    /// TokenMessenger of the destination domain
+    /// CHECK: Checked in CCTP config hash
+    pub remote_token_messenger: AccountInfo<'info>,
+    /// TokenMinter state
+    /// CHECK: Checked in CCTP config hash
+    pub token_minter: AccountInfo<'info>,
+    /// TokenMinter's state of the burned mint
+    #[account(mut)]
+    /// CHECK: Checked in CCTP config hash
+    pub local_token: AccountInfo<'info>,
+    /// Burned mint
+    #[account(mut)]
+    pub burn_token_mint: InterfaceAccount<'info, Mint>,
+    /// Message account, derived from the holder's message nonce
+    #[account(
+        mut,
+        seeds = [
+            b"unique_message",
+            boring_account.key().as_ref(),
+            &boring_account.load_state()?.message_nonce.to_le_bytes()
+        ],
+        bump
+    )]
+    /// CHECK: Checked against PDA
+    pub message_sent_event_data: AccountInfo<'info>,
+    /// MessageTransmitter Program
+    /// CHECK: Checked in CCTP config hash
+    pub message_transmitter_program: AccountInfo<'info>,
+    /// TokenMessengerMinter Program
+    /// CHECK: Checked in CCTP config hash
+    pub token_messenger_minter_program: AccountInfo<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    /// TokenMessengerMinter's event authority
+    #[account(
+        seeds = [b"__event_authority"],
+        bump,
+        seeds::program = token_messenger_minter_program.key()
+    )]
+    /// CHECK: Checked against PDA
+    pub event_authority: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateNttConfiguration<'info> {
+    #[account(
+        seeds = [b"boring_state", boring_account.load_state()?.creator.as_ref()],
+        bump = boring_account.load_state()?.bump,
+    )]
+    pub boring_account: AccountLoader<'info, BoringState>,
+    #[account(
+        init_if_needed,
+        payer = signer,
+        space = 8 + BoringNttConfig::INIT_SPACE,
+        seeds = [b"boring_ntt_config", boring_account.key().as_ref()],
+        bump
+    )]
+    pub ntt_config: Account<'info, BoringNttConfig>,
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(mode: NttMode, recipient_chain: u16)]
+pub struct TransferRemoteNttContext<'info> {
+    #[account(
+        mut,
+        seeds = [b"boring_state", boring_account.load_state()?.creator.as_ref()],
+        bump = boring_account.load_state()?.bump,
+    )]
+    pub boring_account: AccountLoader<'info, BoringState>,
+    #[account(
+        seeds = [b"boring_ntt_config", boring_account.key().as_ref()],
+        bump = ntt_config.bump,
+    )]
+    pub ntt_config: Account<'info, BoringNttConfig>,
+    /// Strategist, which pays the rent of the outbox item and the relayer fee
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    /// NTT Manager Program
+    /// CHECK: Checked in NTT config hash
+    pub ntt_manager_program: AccountInfo<'info>,
+    /// NTT manager config
+    /// CHECK: Checked in NTT config hash
+    pub manager_config: AccountInfo<'info>,
+    /// Sent mint
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    /// Boring Account Associated Token Account, from which tokens are sent
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = boring_account,
+        associated_token::token_program = token_program
+    )]
+    pub boring_account_ata: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+    /// Outbox item, derived from the holder's message nonce
+    #[account(
+        mut,
+        seeds = [
+            b"unique_message",
+            boring_account.key().as_ref(),
+            &boring_account.load_state()?.message_nonce.to_le_bytes()
+        ],
+        bump
+    )]
+    /// CHECK: Checked against PDA
+    pub outbox_item: AccountInfo<'info>,
+    /// Outbound rate limit
+    #[account(mut)]
+    /// CHECK: Checked in NTT config hash
+    pub outbox_rate_limit: AccountInfo<'info>,
+    /// NTT manager custody token account
+    #[account(mut)]
+    /// CHECK: Checked in NTT config hash
+    pub custody: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
+    /// Inbound rate limit of the destination chain
+    #[account(
+        mut,
+        seeds = [b"inbox_rate_limit", recipient_chain.to_be_bytes().as_ref()],
+        bump,
+        seeds::program = ntt_manager_program.key()
+    )]
+    /// CHECK: Checked against PDA
+    pub inbox_rate_limit: AccountInfo<'info>,
+    /// NTT manager peer of the destination chain
+    #[account(
+        seeds = [b"peer", recipient_chain.to_be_bytes().as_ref()],
+        bump,
+        seeds::program = ntt_manager_program.key()
+    )]
+    /// CHECK: Checked against PDA
+    pub peer: AccountInfo<'info>,
+    /// Session authority, derived from the transfer arguments
+    /// CHECK: Checked against PDA when transferring
+    pub session_authority: AccountInfo<'info>,
+    /// NTT manager token authority, used when burning
+    #[account(
+        seeds = [b"token_authority"],
+        bump,
+        seeds::program = ntt_manager_program.key()
+    )]
+    /// CHECK: Checked against PDA
+    pub token_authority: AccountInfo<'info>,
+    /// Relayer fee recipient
+    #[account(mut)]
+    /// CHECK: Checked in NTT config hash
+    pub relayer_fee_recipient: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateOftConfiguration<'info> {
+    #[account(
+        seeds = [b"boring_state", boring_account.load_state()?.creator.as_ref()],
+        bump = boring_account.load_state()?.bump,
+    )]
+    pub boring_account: AccountLoader<'info, BoringState>,
+    #[account(
+        init_if_needed,
+        payer = signer,
+        space = 8 + BoringOftConfig::INIT_SPACE,
+        seeds = [b"boring_oft_config", boring_account.key().as_ref()],
+        bump
+    )]
+    pub oft_config: Account<'info, BoringOftConfig>,
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct TransferRemoteOftContext<'info> {
+    #[account(
+        mut,
+        seeds = [b"boring_state", boring_account.load_state()?.creator.as_ref()],
+        bump = boring_account.load_state()?.bump,
+    )]
+    pub boring_account: AccountLoader<'info, BoringState>,
+    #[account(
+        seeds = [b"boring_oft_config", boring_account.key().as_ref()],
+        bump = oft_config.bump,
+    )]
+    pub oft_config: Account<'info, BoringOftConfig>,
+    /// Strategist, which pays the native fee
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    /// OFT Program
+    /// CHECK: Checked in OFT config hash
+    pub oft_program: AccountInfo<'info>,
+    /// LayerZero Endpoint Program
+    /// CHECK: Checked in OFT config hash
+    pub endpoint_program: AccountInfo<'info>,
+    /// OFT peer of the destination endpoint
+    #[account(mut)]
+    /// CHECK: Checked in OFT config hash
+    pub peer: AccountInfo<'info>,
+    /// OFT store
+    #[account(mut)]
+    /// CHECK: Checked in OFT config hash
+    pub oft_store: AccountInfo<'info>,
+    /// Boring Account Associated Token Account, from which tokens are sent
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = boring_account,
+        associated_token::token_program = token_program
+    )]
+    pub boring_account_ata: InterfaceAccount<'info, TokenAccount>,
+    /// OFT token escrow
+    #[account(mut)]
+    /// CHECK: Checked in OFT config hash
+    pub token_escrow: AccountInfo<'info>,
+    /// Sent mint
+    #[account(mut)]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+    /// OFT program event authority
+    #[account(
+        seeds = [b"__event_authority"],
+        bump,
+        seeds::program = oft_program.key()
+    )]
+    /// CHECK: Checked against PDA
+    pub event_authority: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetInboundConfig<'info> {
+    #[account(
+        seeds = [b"boring_state", boring_account.load_state()?.creator.as_ref()],
+        bump = boring_account.load_state()?.bump,
+    )]
+    pub boring_account: AccountLoader<'info, BoringState>,
+    #[account(
+        init_if_needed,
+        payer = signer,
+        space = 8 + BoringInbound::INIT_SPACE,
+        seeds = [b"boring_inbound", boring_account.key().as_ref()],
+        bump
+    )]
+    pub inbound: Account<'info, BoringInbound>,
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetDispatchAllowlist<'info> {
+    #[account(
+        seeds = [b"boring_state", boring_account.load_state()?.creator.as_ref()],
+        bump = boring_account.load_state()?.bump,
+    )]
+    pub boring_account: AccountLoader<'info, BoringState>,
+    #[account(
+        init_if_needed,
+        payer = signer,
+        space = 8 + BoringDispatch::INIT_SPACE,
+        seeds = [b"boring_dispatch", boring_account.key().as_ref()],
+        bump
+    )]
+    pub dispatch: Account<'info, BoringDispatch>,
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DispatchMessageContext<'info> {
+    #[account(
+        mut,
+        seeds = [b"boring_state", boring_account.load_state()?.creator.as_ref()],
+        bump = boring_account.load_state()?.bump,
+    )]
+    pub boring_account: AccountLoader<'info, BoringState>,
+    #[account(
+        seeds = [b"boring_config", boring_account.key().as_ref()],
+        bump
+    )]
+    pub config_account: Account<'info, BoringConfig>,
+    #[account(
+        seeds = [b"boring_dispatch", boring_account.key().as_ref()],
+        bump = dispatch.bump,
+    )]
+    pub dispatch: Account<'info, BoringDispatch>,
+    /// Owner or strategist, which pays the message storage rent and the gas payment
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    /// System Program account
+    pub system_program: Program<'info, System>,
+    /// NOOP
+    /// CHECK: Checked against stored config
+    #[account(address = config_account.config.noop)]
+    pub noop: AccountInfo<'info>,
+    /// Mailbox Program
+    /// CHECK: Checked against stored config
+    #[account(address = config_account.config.mailbox_program)]
+    pub mailbox_program: AccountInfo<'info>,
+    /// Mailbox Outbox
+    #[account(mut, address = config_account.config.mailbox_outbox)]
+    /// CHECK: Checked against stored config
+    pub mailbox_outbox: AccountInfo<'info>,
+    /// Unique message / gas payment account, derived from the holder's message nonce
+    #[account(
+        seeds = [
+            b"unique_message",
+            boring_account.key().as_ref(),
+            &boring_account.load_state()?.message_nonce.to_le_bytes()
+        ],
+        bump
+    )]
+    /// CHECK: Checked against PDA
+    pub unique_message: AccountInfo<'info>,
+    /// Message storage PDA
+    #[account(
+        mut,
+        seeds = [
+            b"hyperlane",
+            b"-",
+            b"dispatched_message",
+            b"-",
+            unique_message.key().as_ref()
+        ],
+        bump,
+        seeds::program = mailbox_program.key()
+    )]
+    /// CHECK: Checked against PDA
+    pub message_storage_pda: AccountInfo<'info>,
+    /// IGP Program
+    /// CHECK: Checked against stored config
+    #[account(address = config_account.config.igp_program)]
+    pub igp_program: AccountInfo<'info>,
+    /// IGP Program Data
+    #[account(mut, address = config_account.config.igp_program_data)]
+    /// CHECK: Checked against stored config
+    pub igp_program_data: AccountInfo<'info>,
     /// Gas payment PDA
     #[account(
         mut,
@@ -370,46 +2502,80 @@ pub struct TransferRemoteContext<'info> {
     )]
     /// CHECK: Checked against PDA
     pub gas_payment_pda: AccountInfo<'info>,
-    /// IGP Account
-    /// CHECK: Checked in config hash
+    /// IGP Account, the overhead IGP wrapping the token sender
+    #[account(address = config_account.config.igp_account)]
+    /// CHECK: Checked against stored config
     pub igp_account: AccountInfo<'info>,
-    /// Token Sender
-    #[account(mut)]
-    /// CHECK: Checked in config hash
+    /// Token Sender, the inner IGP that is paid
+    #[account(mut, address = config_account.config.token_sender)]
+    /// CHECK: Checked against stored config
     pub token_sender: AccountInfo<'info>,
-    /// Token 2022
+}
+
+#[derive(Accounts)]
+pub struct SetRecipientIsm<'info> {
+    #[account(
+        init_if_needed,
+        payer = signer,
+        space = 8 + RecipientIsm::INIT_SPACE,
+        seeds = [b"recipient_ism"],
+        bump
+    )]
+    pub recipient_ism: Account<'info, RecipientIsm>,
     #[account(
-        address = anchor_spl::token_2022::ID
+        constraint = program.programdata_address()? == Some(program_data.key()) @ CustomError::Unauthorized
     )]
-    /// CHECK: Checked in config hash
-    pub token_2022: Program<'info, Token2022>,
-    /// Mint Authority
-    #[account(mut)]
-    /// CHECK: Checked in config hash
-    pub mint_auth: AccountInfo<'info>,
-    /// Boring Account Associated Token Account
+    pub program: Program<'info, crate::program::BoringBridgeHolder>,
     #[account(
-        mut,
-        associated_token::mint = mint_auth,
-        associated_token::authority = boring_account,
-        associated_token::token_program = token_2022
+        constraint = program_data.upgrade_authority_address == Some(signer.key()) @ CustomError::Unauthorized
     )]
-    /// CHECK: Checked against PDA
-    pub boring_account_ata: InterfaceAccount<'info, TokenAccount>,
-    /// Strategist Associated Token Account
+    pub program_data: Account<'info, ProgramData>,
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct TransferRemoteBatchContext<'info> {
     #[account(
         mut,
-        associated_token::mint = mint_auth,
-        associated_token::authority = signer.key(),
-        associated_token::token_program = token_2022
+        seeds = [b"boring_state", boring_account.load_state()?.creator.as_ref()],
+        bump = boring_account.load_state()?.bump,
     )]
-    /// CHECK: Checked against PDA
-    pub strategist_ata: InterfaceAccount<'info, TokenAccount>,
+    pub boring_account: AccountLoader<'info, BoringState>,
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    /// System Program account
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
 pub struct Version {}
 
+#[derive(Accounts)]
+pub struct GetState<'info> {
+    #[account(
+        seeds = [b"boring_state", boring_account.load_state()?.creator.as_ref()],
+        bump = boring_account.load_state()?.bump,
+    )]
+    pub boring_account: AccountLoader<'info, BoringState>,
+}
+
+#[derive(Accounts)]
+pub struct QuoteGasPayment<'info> {
+    /// IGP Program
+    /// CHECK: Owner of the IGP accounts
+    pub igp_program: AccountInfo<'info>,
+    /// IGP account whose gas oracles price the payment
+    #[account(owner = igp_program.key())]
+    /// CHECK: Decoded as an IGP account
+    pub igp: AccountInfo<'info>,
+    /// Overhead IGP wrapping the IGP account, if the route uses one
+    #[account(owner = igp_program.key())]
+    /// CHECK: Decoded as an overhead IGP account
+    pub overhead_igp: Option<AccountInfo<'info>>,
+}
+
 #[derive(BorshSerialize, Debug, Clone)]
 pub struct TransferRemote {
     pub destination_domain: u32,
@@ -421,7 +2587,7 @@ pub struct TransferRemote {
 ///
 /// This struct contains all the necessary addresses and parameters
 /// for interacting with Hyperlane's infrastructure
-#[derive(AnchorSerialize, AnchorDeserialize)] // Add this derive to enable serialization
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone)] // Add this derive to enable serialization
 pub struct ConfigurationData {
     target_program: Pubkey,
     noop: Pubkey,
@@ -436,27 +2602,925 @@ pub struct ConfigurationData {
     token_2022_program: Pubkey,
     mint_auth: Pubkey,
     destination_domain: u32,
-    evm_recipient: [u8; 32],
+    /// The recipient on the destination chain, in the destination's address format
+    recipient: Recipient,
     decimals: u8,
+    /// Decimals of the token on the destination chain
+    remote_decimals: u8,
+    /// The bridge the configuration transfers through
+    bridge_kind: ConfigurationBridgeKind,
+    /// The mint of the bridged token. Synthetic routes burn from the mint itself, so it equals
+    /// `mint_auth`; routes whose `mint_auth` is a separate PDA, such as collateral routes, name
+    /// the mint here.
+    mint: Pubkey,
 }
 
-/// Digests the configuration data into a 32-byte hash
 impl ConfigurationData {
-    fn compute_hash(&self) -> [u8; 32] {
+    /// Digests the configuration data into a 32-byte hash
+    ///
+    /// The preimage is domain separated by a version tag, the program ID and the holder
+    /// address, so a hash approved for one holder is never valid for another holder,
+    /// another deployment, or a future preimage layout.
+    pub(crate) fn compute_hash(&self, boring_account: &Pubkey) -> [u8; 32] {
+        let mut data = Vec::new();
+        self.serialize(&mut data).unwrap();
+        hashv(&[
+            CONFIG_HASH_DOMAIN,
+            &[CONFIG_HASH_VERSION],
+            crate::ID.as_ref(),
+            boring_account.as_ref(),
+            &data,
+        ])
+        .to_bytes()
+    }
+
+    /// Digests the configuration data using the legacy, unversioned scheme, which used the
+    /// version 1 preimage layout
+    fn compute_legacy_hash(&self) -> Option<[u8; 32]> {
+        let data = self.serialize_previous(1)?;
+        Some(hash(&data).to_bytes())
+    }
+
+    /// Digests the configuration data using an earlier version of the preimage layout
+    fn compute_previous_hash(&self, boring_account: &Pubkey, version: u8) -> Option<[u8; 32]> {
+        let data = self.serialize_previous(version)?;
+        Some(
+            hashv(&[
+                CONFIG_HASH_DOMAIN,
+                &[version],
+                crate::ID.as_ref(),
+                boring_account.as_ref(),
+                &data,
+            ])
+            .to_bytes(),
+        )
+    }
+
+    /// Serializes the configuration data in the preimage layout of an earlier hash version
+    ///
+    /// Fields added since `version` are dropped, so only configurations that hold the values
+    /// implied before those fields existed can be expressed; others return `None`:
+    /// * Before version 5, the mint was the mint authority.
+    /// * Before version 4, the recipient was a bare 32-byte EVM word.
+    /// * Before version 3, the remote token had the mint's decimals.
+    /// * Before version 2, every configuration was a Hyperlane warp route configuration.
+    fn serialize_previous(&self, version: u8) -> Option<Vec<u8>> {
+        let mut data = Vec::new();
+        self.serialize(&mut data).unwrap();
+        if version < 5 {
+            if self.mint != self.mint_auth {
+                return None;
+            }
+            // Drop the mint, the last 32 bytes.
+            data.truncate(data.len() - 32);
+        }
+        if version < 4 {
+            if !matches!(self.recipient, Recipient::Evm20(_)) {
+                return None;
+            }
+            // Drop the recipient's variant byte, which follows the 12 addresses and the
+            // destination domain.
+            let recipient_offset = 12 * 32 + 4;
+            data.remove(recipient_offset);
+        }
+        if version < 3 {
+            if self.remote_decimals != self.decimals {
+                return None;
+            }
+            // Drop the remote decimals, the byte before the bridge kind.
+            data.remove(data.len() - 2);
+        }
+        if version < 2 {
+            // Drop the bridge kind, the last byte. Only warp routes can be configured, so every
+            // configuration was valid before the bridge kind was added.
+            data.pop();
+        }
+        Some(data)
+    }
+}
+
+#[cfg(test)]
+mod configuration_hash_tests {
+    use super::*;
+
+    fn config(mint_auth: Pubkey, mint: Pubkey) -> ConfigurationData {
+        ConfigurationData {
+            target_program: Pubkey::new_unique(),
+            noop: Pubkey::new_unique(),
+            token_pda: Pubkey::new_unique(),
+            mailbox_program: Pubkey::new_unique(),
+            mailbox_outbox: Pubkey::new_unique(),
+            message_dispatch_authority: Pubkey::new_unique(),
+            igp_program: Pubkey::new_unique(),
+            igp_program_data: Pubkey::new_unique(),
+            igp_account: Pubkey::new_unique(),
+            token_sender: Pubkey::new_unique(),
+            token_2022_program: anchor_spl::token_2022::ID,
+            mint_auth,
+            destination_domain: 1,
+            recipient: Recipient::Evm20([0; 32]),
+            decimals: 6,
+            remote_decimals: 6,
+            bridge_kind: ConfigurationBridgeKind::HyperlaneWarpRoute,
+            mint,
+        }
+    }
+
+    #[test]
+    fn version_4_preimage_drops_a_mint_equal_to_the_mint_authority() {
+        let mint = Pubkey::new_unique();
+        let synthetic = config(mint, mint);
+        let mut data = Vec::new();
+        synthetic.serialize(&mut data).unwrap();
+
+        assert_eq!(
+            synthetic.serialize_previous(4),
+            Some(data[..data.len() - 32].to_vec())
+        );
+        assert_eq!(
+            config(Pubkey::new_unique(), mint).serialize_previous(4),
+            None
+        );
+    }
+
+    #[test]
+    fn hash_commits_to_the_mint() {
+        let holder = Pubkey::new_unique();
+        let synthetic = config(Pubkey::new_unique(), Pubkey::new_unique());
+        let mut collateral = synthetic.clone();
+        collateral.mint = Pubkey::new_unique();
+
+        assert_ne!(
+            synthetic.compute_hash(&holder),
+            collateral.compute_hash(&holder)
+        );
+    }
+}
+
+/// A recipient address, in the format of its destination chain
+///
+/// Bridges send recipients as a 32-byte word, so each format is stored as that word.
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Recipient {
+    /// A 20-byte EVM address, left-padded with 12 zero bytes
+    Evm20([u8; 32]),
+    /// A full 32-byte address, such as a Cosmos, SVM or Starknet address
+    Bytes32([u8; 32]),
+}
+
+impl Recipient {
+    /// The 32-byte word the bridge sends as the recipient
+    pub fn to_bytes32(&self) -> [u8; 32] {
+        match self {
+            Recipient::Evm20(bytes) | Recipient::Bytes32(bytes) => *bytes,
+        }
+    }
+
+    /// Validates the recipient against the encoding rules of its format
+    ///
+    /// # Errors
+    /// * `CustomError::InvalidRecipient` - If the recipient is the zero address, or an EVM
+    ///   address whose upper 12 bytes are not zero
+    pub fn validate(&self) -> Result<()> {
+        let bytes = self.to_bytes32();
+        require!(bytes != [0; 32], CustomError::InvalidRecipient);
+        if let Recipient::Evm20(bytes) = self {
+            require!(bytes[..12] == [0; 12], CustomError::InvalidRecipient);
+        }
+        Ok(())
+    }
+}
+
+/// The bridge a `ConfigurationData` transfers through
+///
+/// Bridges with their own configuration type, such as Circle CCTP, Wormhole NTT and LayerZero
+/// OFT, are approved through it and have no variant here.
+#[derive(
+    AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Default, PartialEq, Eq, Debug,
+)]
+pub enum ConfigurationBridgeKind {
+    /// A Hyperlane warp route
+    #[default]
+    HyperlaneWarpRoute,
+}
+
+impl From<ConfigurationBridgeKind> for BridgeKind {
+    fn from(kind: ConfigurationBridgeKind) -> Self {
+        match kind {
+            ConfigurationBridgeKind::HyperlaneWarpRoute => Self::HyperlaneWarpRoute,
+        }
+    }
+}
+
+/// The bridge a transfer goes through, each implemented by a `BridgeAdapter`
+///
+/// Keys the bridging statistics and is reported in `TransferRemote` events.
+#[derive(
+    AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Default, PartialEq, Eq, Debug,
+)]
+pub enum BridgeKind {
+    /// A Hyperlane warp route
+    #[default]
+    HyperlaneWarpRoute,
+    /// Circle's CCTP, configured with `CctpConfigurationData`
+    CircleCctp,
+    /// A Wormhole NTT manager, configured with `NttConfigurationData`
+    WormholeNtt,
+    /// A LayerZero V2 OFT program, configured with `OftConfigurationData`
+    LayerZeroOft,
+}
+
+impl BridgeKind {
+    /// The bridge kind whose discriminant is stored in a zero-copy account
+    ///
+    /// # Errors
+    /// * `CustomError::InvalidState` - If the discriminant is not a bridge kind
+    fn from_stored(discriminant: u8) -> Result<Self> {
+        match discriminant {
+            0 => Ok(Self::HyperlaneWarpRoute),
+            1 => Ok(Self::CircleCctp),
+            2 => Ok(Self::WormholeNtt),
+            3 => Ok(Self::LayerZeroOft),
+            _ => err!(CustomError::InvalidState),
+        }
+    }
+}
+
+/// Configuration of Circle CCTP transfers
+///
+/// Committed to its own hash, stored in the holder's `BoringCctpConfig`.
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone)]
+pub struct CctpConfigurationData {
+    token_messenger_minter_program: Pubkey,
+    message_transmitter_program: Pubkey,
+    message_transmitter: Pubkey,
+    token_messenger: Pubkey,
+    /// The TokenMessenger of the destination domain
+    remote_token_messenger: Pubkey,
+    token_minter: Pubkey,
+    /// The TokenMinter's state of the burned mint
+    local_token: Pubkey,
+    burn_token_mint: Pubkey,
+    /// The CCTP domain of the destination chain
+    destination_domain: u32,
+    /// The 32-byte address minted to on the destination chain
+    mint_recipient: [u8; 32],
+}
+
+impl CctpConfigurationData {
+    /// Digests the CCTP configuration data into a 32-byte hash
+    ///
+    /// Domain separated like `ConfigurationData::compute_hash`, with its own domain so a CCTP
+    /// hash never matches a configuration of another bridge.
+    pub(crate) fn compute_hash(&self, boring_account: &Pubkey) -> [u8; 32] {
         let mut data = Vec::new();
         self.serialize(&mut data).unwrap();
-        hash(&data).to_bytes()
+        hashv(&[
+            CCTP_CONFIG_HASH_DOMAIN,
+            &[BRIDGE_CONFIG_HASH_VERSION],
+            crate::ID.as_ref(),
+            boring_account.as_ref(),
+            &data,
+        ])
+        .to_bytes()
+    }
+}
+
+/// Version information of the deployed program
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct VersionInfo {
+    pub major: u16,
+    pub minor: u16,
+    pub patch: u16,
+    /// Git commit the program was built from, or "unknown"
+    pub git_commit: String,
+    /// Cargo features enabled in the build, as listed by the build script
+    pub features: Vec<String>,
+}
+
+impl VersionInfo {
+    /// Builds the version information of this build
+    fn current() -> Self {
+        Self {
+            major: env!("CARGO_PKG_VERSION_MAJOR").parse().unwrap_or_default(),
+            minor: env!("CARGO_PKG_VERSION_MINOR").parse().unwrap_or_default(),
+            patch: env!("CARGO_PKG_VERSION_PATCH").parse().unwrap_or_default(),
+            git_commit: env!("GIT_COMMIT").to_string(),
+            features: env!("ENABLED_FEATURES")
+                .split(',')
+                .filter(|feature| !feature.is_empty())
+                .map(str::to_string)
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod version_info_tests {
+    use super::*;
+
+    #[test]
+    fn reports_the_enabled_features() {
+        let features = VersionInfo::current().features;
+        assert_eq!(
+            features.contains(&"verbose-logs".to_string()),
+            cfg!(feature = "verbose-logs")
+        );
+        assert!(!features.contains(&"default".to_string()));
+        assert!(features.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+}
+
+/// A read-only view of the Boring Bridge Holder state
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct StateView {
+    pub creator: Pubkey,
+    pub owner: Pubkey,
+    pub strategist: Pubkey,
+    pub config_hash: [u8; 32],
+    pub state_version: u8,
+    pub total_bridged: u64,
+    pub transfer_count: u64,
+    pub last_transfer_slot: u64,
+    pub last_transfer_timestamp: i64,
+    /// Totals for each destination domain bridged to, skipping unused slots
+    pub domain_totals: Vec<DomainTotal>,
+    pub auto_bridge_policy: AutoBridgePolicy,
+    pub sweep_reserve: u64,
+    pub message_nonce: u64,
+    pub governor: Governor,
+    pub lookup_table: Pubkey,
+}
+
+impl TryFrom<&BoringState> for StateView {
+    type Error = Error;
+
+    fn try_from(state: &BoringState) -> Result<Self> {
+        Ok(Self {
+            creator: state.creator,
+            owner: state.owner,
+            strategist: state.strategist,
+            config_hash: state.config_hash,
+            state_version: state.state_version,
+            total_bridged: state.total_bridged,
+            transfer_count: state.transfer_count,
+            last_transfer_slot: state.last_transfer_slot,
+            last_transfer_timestamp: state.last_transfer_timestamp,
+            domain_totals: state
+                .domain_totals
+                .iter()
+                .filter(|total| total.transfer_count > 0)
+                .map(|total| DomainTotal::try_from(*total))
+                .collect::<Result<_>>()?,
+            auto_bridge_policy: state.auto_bridge_policy.into(),
+            sweep_reserve: state.sweep_reserve,
+            message_nonce: state.message_nonce,
+            governor: state.governor.into(),
+            lookup_table: state.lookup_table,
+        })
     }
 }
 
 /// The state account for the Boring Bridge Holder
-#[account]
+///
+/// Loaded zero-copy, so every field sits at its natural alignment and padding is explicit.
+/// `bump` and `state_version` keep their offsets from the Borsh layouts of earlier versions,
+/// so accounts that still need `migrate_state` can be told apart. New fields are carved out of
+/// `reserved`, so the account size stays the same and existing accounts read them as zero.
+#[account(zero_copy)]
+#[derive(InitSpace)]
 pub struct BoringState {
     creator: Pubkey,
     owner: Pubkey,
     strategist: Pubkey,
     config_hash: [u8; 32],
     bump: u8,
+    state_version: u8,
+    padding: [u8; 6],
+    /// Total amount of tokens bridged
+    total_bridged: u64,
+    /// Number of completed transfers
+    transfer_count: u64,
+    last_transfer_slot: u64,
+    last_transfer_timestamp: i64,
+    /// Totals per bridge and destination domain; pairs beyond the first `MAX_DOMAIN_TOTALS` are
+    /// not tracked
+    domain_totals: [StoredDomainTotal; MAX_DOMAIN_TOTALS],
+    /// Policy under which keepers may trigger transfers
+    auto_bridge_policy: StoredAutoBridgePolicy,
+    /// Amount of tokens a full-balance sweep leaves in the holder
+    sweep_reserve: u64,
+    /// Nonce from which the next unique message account is derived
+    message_nonce: u64,
+    /// EVM-side governor whose Hyperlane messages can execute owner actions
+    governor: StoredGovernor,
+    /// Canonical address lookup table for the holder's transactions, or the default pubkey
+    lookup_table: Pubkey,
+    reserved: [u8; 32],
+}
+
+// `InitSpace` must count every byte of the zero-copy layout, so accounts fit what is loaded.
+const _: () = assert!(BoringState::INIT_SPACE == std::mem::size_of::<BoringState>());
+
+impl BoringState {
+    /// The message nonce after the current one, used by a second message in one instruction
+    ///
+    /// # Errors
+    /// * `CustomError::InvalidState` - If the nonce is exhausted
+    fn next_message_nonce(&self) -> Result<u64> {
+        Ok(self
+            .message_nonce
+            .checked_add(1)
+            .ok_or(CustomError::InvalidState)?)
+    }
+
+    /// Adds a completed transfer to the bridging statistics
+    ///
+    /// Statistics saturate instead of failing, so they can never block a transfer. Domain
+    /// totals are keyed by bridge as well, since each bridge numbers chains its own way.
+    ///
+    /// # Returns
+    /// * `u64` - The total bridged to `destination_domain` through `bridge_kind`, or 0 if the
+    ///   pair is not tracked
+    fn record_transfer(
+        &mut self,
+        bridge_kind: BridgeKind,
+        destination_domain: u32,
+        amount: u64,
+        slot: u64,
+        timestamp: i64,
+    ) -> u64 {
+        self.total_bridged = self.total_bridged.saturating_add(amount);
+        self.transfer_count = self.transfer_count.saturating_add(1);
+        self.last_transfer_slot = slot;
+        self.last_transfer_timestamp = timestamp;
+
+        let slot = self
+            .domain_totals
+            .iter()
+            .position(|total| {
+                total.transfer_count > 0
+                    && total.domain == destination_domain
+                    && total.bridge_kind == bridge_kind as u8
+            })
+            .or_else(|| {
+                self.domain_totals
+                    .iter()
+                    .position(|total| total.transfer_count == 0)
+            });
+        match slot {
+            Some(index) => {
+                let total = &mut self.domain_totals[index];
+                total.domain = destination_domain;
+                total.bridge_kind = bridge_kind as u8;
+                total.total_bridged = total.total_bridged.saturating_add(amount);
+                total.transfer_count = total.transfer_count.saturating_add(1);
+                total.total_bridged
+            }
+            None => 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod record_transfer_tests {
+    use bytemuck::Zeroable;
+
+    use super::*;
+
+    #[test]
+    fn keys_domain_totals_by_bridge_kind_and_domain() {
+        // Domain 10 is Optimism on Hyperlane but Fantom on Wormhole, so each bridge keeps its
+        // own total. Pairs beyond `MAX_DOMAIN_TOTALS` are not tracked and report 0.
+        let cases = [
+            (BridgeKind::HyperlaneWarpRoute, 10, 100, 100),
+            (BridgeKind::WormholeNtt, 10, 5, 5),
+            (BridgeKind::WormholeNtt, 10, 5, 10),
+            (BridgeKind::CircleCctp, 10, 7, 7),
+            (BridgeKind::LayerZeroOft, 10, 3, 3),
+            (BridgeKind::HyperlaneWarpRoute, 10, 50, 150),
+            (BridgeKind::HyperlaneWarpRoute, 11, 1, 0),
+        ];
+
+        let mut state = BoringState::zeroed();
+        for (slot, (bridge_kind, domain, amount, expected)) in cases.into_iter().enumerate() {
+            let slot = slot as u64;
+            assert_eq!(
+                state.record_transfer(bridge_kind, domain, amount, slot, slot as i64),
+                expected,
+                "{bridge_kind:?} domain {domain}"
+            );
+        }
+
+        let totals = state
+            .domain_totals
+            .map(|total| DomainTotal::try_from(total).unwrap());
+        let expected = [
+            (BridgeKind::HyperlaneWarpRoute, 150, 2),
+            (BridgeKind::WormholeNtt, 10, 2),
+            (BridgeKind::CircleCctp, 7, 1),
+            (BridgeKind::LayerZeroOft, 3, 1),
+        ];
+        for (total, (bridge_kind, total_bridged, transfer_count)) in totals.iter().zip(expected) {
+            assert_eq!(total.bridge_kind, bridge_kind);
+            assert_eq!(total.domain, 10);
+            assert_eq!(total.total_bridged, total_bridged);
+            assert_eq!(total.transfer_count, transfer_count);
+        }
+        assert_eq!(state.total_bridged, 171);
+        assert_eq!(state.transfer_count, 7);
+        assert_eq!(state.last_transfer_slot, 6);
+    }
+
+    #[test]
+    fn rejects_unknown_stored_bridge_kinds() {
+        let mut total = StoredDomainTotal::from(DomainTotal::default());
+        total.bridge_kind = 4;
+        assert!(DomainTotal::try_from(total).is_err());
+    }
+}
+
+/// Number of bridge and destination domain pairs tracked in `BoringState::domain_totals`
+pub const MAX_DOMAIN_TOTALS: usize = 4;
+
+/// Owner-configured policy under which anyone may trigger a transfer with `crank_transfer_remote`
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Default, Debug)]
+pub struct AutoBridgePolicy {
+    pub enabled: bool,
+    /// Minimum holder balance before a transfer may be triggered
+    pub min_balance: u64,
+    /// Fixed amount to bridge, or 0 to bridge the full balance less the sweep reserve
+    pub amount: u64,
+    /// Minimum number of seconds since the previous transfer, positive when enabled
+    pub min_interval_seconds: i64,
+    /// Lamports paid to the keeper from the fee vault for each transfer
+    pub keeper_bounty_lamports: u64,
+}
+
+/// Owner-configured sender of Hyperlane messages that can execute owner actions
+///
+/// Governance messages are delivered through the holder's inbound mailbox and can update the
+/// configuration, update the strategist or transfer ownership.
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Default, Debug)]
+pub struct Governor {
+    pub enabled: bool,
+    /// The origin domain of the governor
+    pub origin: u32,
+    /// The 32-byte governor address on the origin chain
+    pub sender: [u8; 32],
+}
+
+impl Governor {
+    /// Whether messages from `sender` on `origin` come from the governor
+    fn is_governor(&self, origin: u32, sender: &[u8; 32]) -> bool {
+        self.enabled && self.origin == origin && self.sender == *sender
+    }
+}
+
+/// Bridging totals for a single destination domain of a bridge
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Default, Debug)]
+pub struct DomainTotal {
+    /// The bridge `domain` is numbered by
+    pub bridge_kind: BridgeKind,
+    pub domain: u32,
+    pub total_bridged: u64,
+    pub transfer_count: u64,
+}
+
+/// `AutoBridgePolicy` as stored in the zero-copy `BoringState`
+#[zero_copy]
+#[derive(InitSpace, Default)]
+pub struct StoredAutoBridgePolicy {
+    min_balance: u64,
+    amount: u64,
+    min_interval_seconds: i64,
+    keeper_bounty_lamports: u64,
+    /// `1` if enabled, `0` otherwise
+    enabled: u8,
+    padding: [u8; 7],
+}
+
+impl From<AutoBridgePolicy> for StoredAutoBridgePolicy {
+    fn from(policy: AutoBridgePolicy) -> Self {
+        Self {
+            min_balance: policy.min_balance,
+            amount: policy.amount,
+            min_interval_seconds: policy.min_interval_seconds,
+            keeper_bounty_lamports: policy.keeper_bounty_lamports,
+            enabled: policy.enabled.into(),
+            padding: [0; 7],
+        }
+    }
+}
+
+impl From<StoredAutoBridgePolicy> for AutoBridgePolicy {
+    fn from(policy: StoredAutoBridgePolicy) -> Self {
+        Self {
+            enabled: policy.enabled != 0,
+            min_balance: policy.min_balance,
+            amount: policy.amount,
+            min_interval_seconds: policy.min_interval_seconds,
+            keeper_bounty_lamports: policy.keeper_bounty_lamports,
+        }
+    }
+}
+
+/// `Governor` as stored in the zero-copy `BoringState`
+#[zero_copy]
+#[derive(InitSpace, Default)]
+pub struct StoredGovernor {
+    sender: [u8; 32],
+    origin: u32,
+    /// `1` if enabled, `0` otherwise
+    enabled: u8,
+    padding: [u8; 3],
+}
+
+impl From<Governor> for StoredGovernor {
+    fn from(governor: Governor) -> Self {
+        Self {
+            sender: governor.sender,
+            origin: governor.origin,
+            enabled: governor.enabled.into(),
+            padding: [0; 3],
+        }
+    }
+}
+
+impl From<StoredGovernor> for Governor {
+    fn from(governor: StoredGovernor) -> Self {
+        Self {
+            enabled: governor.enabled != 0,
+            origin: governor.origin,
+            sender: governor.sender,
+        }
+    }
+}
+
+/// `DomainTotal` as stored in the zero-copy `BoringState`
+#[zero_copy]
+#[derive(InitSpace, Default)]
+pub struct StoredDomainTotal {
+    total_bridged: u64,
+    transfer_count: u64,
+    domain: u32,
+    /// The `BridgeKind` discriminant
+    bridge_kind: u8,
+    padding: [u8; 3],
+}
+
+impl From<DomainTotal> for StoredDomainTotal {
+    fn from(total: DomainTotal) -> Self {
+        Self {
+            total_bridged: total.total_bridged,
+            transfer_count: total.transfer_count,
+            domain: total.domain,
+            bridge_kind: total.bridge_kind as u8,
+            padding: [0; 3],
+        }
+    }
+}
+
+impl TryFrom<StoredDomainTotal> for DomainTotal {
+    type Error = Error;
+
+    fn try_from(total: StoredDomainTotal) -> Result<Self> {
+        Ok(Self {
+            bridge_kind: BridgeKind::from_stored(total.bridge_kind)?,
+            domain: total.domain,
+            total_bridged: total.total_bridged,
+            transfer_count: total.transfer_count,
+        })
+    }
+}
+
+/// The full configuration for a Boring Bridge Holder, stored alongside its hash
+#[account]
+#[derive(InitSpace)]
+pub struct BoringConfig {
+    config: ConfigurationData,
+}
+
+impl BoringConfig {
+    /// Replaces the stored configuration, if the holder stores its configuration
+    ///
+    /// # Arguments
+    /// * `config_account` - The holder's `BoringConfig` PDA, which may be uninitialized
+    /// * `config` - The newly approved configuration
+    pub fn update_if_stored(
+        config_account: &AccountInfo,
+        config: &ConfigurationData,
+    ) -> Result<()> {
+        if config_account.owner != &ID || config_account.data_is_empty() {
+            return Ok(());
+        }
+        let mut data = config_account.try_borrow_mut_data()?;
+        let mut stored = BoringConfig::try_deserialize(&mut &data[..])?;
+        stored.config = config.clone();
+        stored.try_serialize(&mut &mut data[..])
+    }
+}
+
+/// A route approved by the owner for batch transfers
+#[account]
+#[derive(InitSpace)]
+pub struct BoringRoute {
+    route_id: u8,
+    bump: u8,
+    config: ConfigurationData,
+}
+
+/// How a Wormhole NTT manager sends tokens
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum NttMode {
+    /// Tokens are burned with `transfer_burn`
+    Burning,
+    /// Tokens are locked in the manager's custody with `transfer_lock`
+    Locking,
+}
+
+/// Configuration of Wormhole NTT transfers
+///
+/// Committed to its own hash, stored in the holder's `BoringNttConfig`.
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone)]
+pub struct NttConfigurationData {
+    ntt_manager_program: Pubkey,
+    /// The NTT manager's config account
+    manager_config: Pubkey,
+    mint: Pubkey,
+    /// The NTT manager's custody token account
+    custody: Pubkey,
+    outbox_rate_limit: Pubkey,
+    /// The account paid the relayer fee of each transfer
+    relayer_fee_recipient: Pubkey,
+    mode: NttMode,
+    /// The Wormhole chain ID of the destination chain
+    recipient_chain: u16,
+    /// The 32-byte recipient address on the destination chain
+    recipient_address: [u8; 32],
+}
+
+impl NttConfigurationData {
+    /// Digests the NTT configuration data into a 32-byte hash
+    ///
+    /// Domain separated like `ConfigurationData::compute_hash`, with its own domain so an NTT
+    /// hash never matches a configuration of another bridge.
+    pub(crate) fn compute_hash(&self, boring_account: &Pubkey) -> [u8; 32] {
+        let mut data = Vec::new();
+        self.serialize(&mut data).unwrap();
+        hashv(&[
+            NTT_CONFIG_HASH_DOMAIN,
+            &[BRIDGE_CONFIG_HASH_VERSION],
+            crate::ID.as_ref(),
+            boring_account.as_ref(),
+            &data,
+        ])
+        .to_bytes()
+    }
+}
+
+/// Configuration of LayerZero V2 OFT sends
+///
+/// Committed to its own hash, stored in the holder's `BoringOftConfig`.
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone)]
+pub struct OftConfigurationData {
+    oft_program: Pubkey,
+    /// The LayerZero endpoint program the OFT sends through
+    endpoint_program: Pubkey,
+    oft_store: Pubkey,
+    /// The OFT peer of the destination endpoint
+    peer: Pubkey,
+    token_escrow: Pubkey,
+    token_mint: Pubkey,
+    /// The LayerZero endpoint ID of the destination chain
+    dst_eid: u32,
+    /// The 32-byte recipient address on the destination chain
+    to: [u8; 32],
+}
+
+impl OftConfigurationData {
+    /// Digests the OFT configuration data into a 32-byte hash
+    ///
+    /// Domain separated like `ConfigurationData::compute_hash`, with its own domain so an OFT
+    /// hash never matches a configuration of another bridge.
+    pub(crate) fn compute_hash(&self, boring_account: &Pubkey) -> [u8; 32] {
+        let mut data = Vec::new();
+        self.serialize(&mut data).unwrap();
+        hashv(&[
+            OFT_CONFIG_HASH_DOMAIN,
+            &[BRIDGE_CONFIG_HASH_VERSION],
+            crate::ID.as_ref(),
+            boring_account.as_ref(),
+            &data,
+        ])
+        .to_bytes()
+    }
+}
+
+/// The approved LayerZero OFT configuration of a holder
+#[account]
+#[derive(InitSpace)]
+pub struct BoringOftConfig {
+    bump: u8,
+    /// Hash of the approved `OftConfigurationData`
+    config_hash: [u8; 32],
+}
+
+/// The approved Wormhole NTT configuration of a holder
+#[account]
+#[derive(InitSpace)]
+pub struct BoringNttConfig {
+    bump: u8,
+    /// Hash of the approved `NttConfigurationData`
+    config_hash: [u8; 32],
+}
+
+/// The approved Circle CCTP configuration of a holder
+#[account]
+#[derive(InitSpace)]
+pub struct BoringCctpConfig {
+    bump: u8,
+    /// Hash of the approved `CctpConfigurationData`
+    config_hash: [u8; 32],
+}
+
+/// Maximum number of allowed senders in `BoringInbound`
+pub const MAX_ALLOWED_SENDERS: usize = 4;
+
+/// Owner-configured Hyperlane messages a holder accepts
+#[account]
+#[derive(InitSpace)]
+pub struct BoringInbound {
+    bump: u8,
+    /// Mailbox program that delivers messages to the holder
+    mailbox: Pubkey,
+    /// ISM messages must be verified by, or `None` for the mailbox's default ISM
+    ism: Option<Pubkey>,
+    #[max_len(MAX_ALLOWED_SENDERS)]
+    allowed_senders: Vec<AllowedSender>,
+}
+
+impl BoringInbound {
+    /// Whether messages from `sender` on `origin` are accepted
+    fn is_allowed_sender(&self, origin: u32, sender: &[u8; 32]) -> bool {
+        self.allowed_senders
+            .iter()
+            .any(|allowed| allowed.origin == origin && allowed.sender == *sender)
+    }
+}
+
+/// An origin domain and sender messages are accepted from
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Debug)]
+pub struct AllowedSender {
+    pub origin: u32,
+    /// The 32-byte sender address on the origin chain
+    pub sender: [u8; 32],
+}
+
+/// Maximum number of recipients a holder may dispatch messages to
+pub const MAX_ALLOWED_RECIPIENTS: usize = 4;
+
+/// Owner-configured recipients the holder may dispatch Hyperlane messages to
+#[account]
+#[derive(InitSpace)]
+pub struct BoringDispatch {
+    bump: u8,
+    #[max_len(MAX_ALLOWED_RECIPIENTS)]
+    allowed_recipients: Vec<AllowedRecipient>,
+}
+
+impl BoringDispatch {
+    /// Whether messages may be dispatched to `recipient` on `destination_domain`
+    fn is_allowed_recipient(&self, destination_domain: u32, recipient: &[u8; 32]) -> bool {
+        self.allowed_recipients.iter().any(|allowed| {
+            allowed.destination_domain == destination_domain && allowed.recipient == *recipient
+        })
+    }
+}
+
+/// A destination domain and recipient messages may be dispatched to
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Debug)]
+pub struct AllowedRecipient {
+    pub destination_domain: u32,
+    /// The 32-byte recipient address on the destination chain
+    pub recipient: [u8; 32],
+}
+
+/// The ISM verifying Hyperlane messages delivered to this program
+#[account]
+#[derive(InitSpace)]
+pub struct RecipientIsm {
+    bump: u8,
+    /// ISM program, or `None` to use the mailbox's default ISM
+    ism: Option<Pubkey>,
+}
+
+/// A single leg of a batch transfer
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct BatchLeg {
+    pub route_id: u8,
+    /// The amount of tokens to transfer, or `SWEEP_ALL` to transfer the full balance
+    pub amount: u64,
 }
 
 // Errors
@@ -466,4 +3530,56 @@ pub enum CustomError {
     Unauthorized,
     #[msg("Invalid Configuration")]
     InvalidConfiguration,
+    #[msg("Invalid State")]
+    InvalidState,
+    #[msg("State Already Migrated")]
+    StateAlreadyMigrated,
+    #[msg("Auto Bridge Disabled")]
+    AutoBridgeDisabled,
+    #[msg("Auto Bridge Too Soon")]
+    AutoBridgeTooSoon,
+    #[msg("Auto Bridge Below Threshold")]
+    AutoBridgeBelowThreshold,
+    #[msg("Invalid Auto Bridge Policy")]
+    InvalidAutoBridgePolicy,
+    #[msg("Fee Vault Insufficient")]
+    FeeVaultInsufficient,
+    #[msg("Insufficient Balance")]
+    InsufficientBalance,
+    #[msg("Invalid Route")]
+    InvalidRoute,
+    #[msg("Invalid Batch Accounts")]
+    InvalidBatchAccounts,
+    #[msg("Gas Payment Too High")]
+    GasPaymentTooHigh,
+    #[msg("Invalid IGP Account")]
+    InvalidIgpAccount,
+    #[msg("Invalid Decimals")]
+    InvalidDecimals,
+    #[msg("Too Many Allowed Senders")]
+    TooManyAllowedSenders,
+    #[msg("Invalid Message")]
+    InvalidMessage,
+    #[msg("Unauthorized Sender")]
+    UnauthorizedSender,
+    /// No longer returned, since configurations can only name a warp route. Kept so the codes of
+    /// later errors don't change.
+    #[msg("Unsupported Bridge Kind")]
+    UnsupportedBridgeKind,
+    #[msg("Too Many Allowed Recipients")]
+    TooManyAllowedRecipients,
+    #[msg("Recipient Not Allowed")]
+    RecipientNotAllowed,
+    #[msg("Amount Loses Precision")]
+    AmountLosesPrecision,
+    #[msg("Invalid Recipient")]
+    InvalidRecipient,
+    #[msg("Gas Quote Overflow")]
+    GasQuoteOverflow,
+    #[msg("ISM Not Approved")]
+    IsmNotApproved,
+    #[msg("Tokens Not Received")]
+    TokensNotReceived,
+    #[msg("State Not Migrated")]
+    StateNotMigrated,
 }
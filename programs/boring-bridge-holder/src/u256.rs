@@ -0,0 +1,134 @@
+use anchor_lang::prelude::*;
+
+/// A 256-bit unsigned integer in the Borsh layout of Hyperlane's `U256`: four little-endian
+/// `u64` limbs, least significant first
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct U256([u64; 4]);
+
+impl U256 {
+    /// Multiplies by a `u64`, returning `None` on overflow
+    pub(crate) fn checked_mul_u64(self, factor: u64) -> Option<Self> {
+        let mut limbs = [0u64; 4];
+        let mut carry = 0u128;
+        for (limb, value) in limbs.iter_mut().zip(self.0) {
+            let product = u128::from(value) * u128::from(factor) + carry;
+            *limb = product as u64;
+            carry = product >> 64;
+        }
+        (carry == 0).then_some(Self(limbs))
+    }
+
+    /// Multiplies by another `U256`, returning `None` on overflow
+    pub(crate) fn checked_mul(self, rhs: Self) -> Option<Self> {
+        let mut limbs = [0u64; 4];
+        for (i, &left) in self.0.iter().enumerate() {
+            if left == 0 {
+                continue;
+            }
+            // Limbs of `rhs` that would land past the 256th bit must be zero.
+            if rhs.0[4 - i..].iter().any(|&right| right != 0) {
+                return None;
+            }
+            let mut carry = 0u128;
+            for (j, &right) in rhs.0[..4 - i].iter().enumerate() {
+                let product =
+                    u128::from(left) * u128::from(right) + u128::from(limbs[i + j]) + carry;
+                limbs[i + j] = product as u64;
+                carry = product >> 64;
+            }
+            if carry != 0 {
+                return None;
+            }
+        }
+        Some(Self(limbs))
+    }
+
+    /// Divides by a `u64`, rounding down, returning `None` for a zero divisor
+    pub(crate) fn checked_div_u64(self, divisor: u64) -> Option<Self> {
+        if divisor == 0 {
+            return None;
+        }
+        let mut limbs = [0u64; 4];
+        let mut remainder = 0u128;
+        for (limb, value) in limbs.iter_mut().zip(self.0).rev() {
+            let dividend = (remainder << 64) | u128::from(value);
+            *limb = (dividend / u128::from(divisor)) as u64;
+            remainder = dividend % u128::from(divisor);
+        }
+        Some(Self(limbs))
+    }
+
+    /// The 32-byte little-endian encoding, as the warp route reads `amount_or_id`
+    pub fn to_le_bytes(self) -> [u8; 32] {
+        let mut bytes = [0u8; 32];
+        for (chunk, limb) in bytes.chunks_exact_mut(8).zip(self.0) {
+            chunk.copy_from_slice(&limb.to_le_bytes());
+        }
+        bytes
+    }
+}
+
+impl From<u64> for U256 {
+    fn from(value: u64) -> Self {
+        Self([value, 0, 0, 0])
+    }
+}
+
+impl From<u128> for U256 {
+    fn from(value: u128) -> Self {
+        Self([value as u64, (value >> 64) as u64, 0, 0])
+    }
+}
+
+impl TryFrom<U256> for u64 {
+    type Error = ProgramError;
+
+    fn try_from(value: U256) -> std::result::Result<Self, Self::Error> {
+        match value.0 {
+            [low, 0, 0, 0] => Ok(low),
+            _ => Err(ProgramError::ArithmeticOverflow),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_amount_or_id_as_256_bit_little_endian() {
+        assert_eq!(U256::from(0u64).to_le_bytes(), [0; 32]);
+
+        let bytes = U256::from(0x0102_0304_0506_0708u64).to_le_bytes();
+        assert_eq!(bytes[..8], [8, 7, 6, 5, 4, 3, 2, 1]);
+        assert_eq!(bytes[8..], [0; 24]);
+
+        let bytes = U256::from(u64::MAX).to_le_bytes();
+        assert_eq!(bytes[..8], [0xff; 8]);
+        assert_eq!(bytes[8..], [0; 24]);
+    }
+
+    #[test]
+    fn multiplies_and_divides_across_limbs() {
+        let rate = U256::from(10_000_000_000_000_000_000u128);
+        let product = U256::from(u128::MAX).checked_mul(rate).unwrap();
+        assert_eq!(
+            product.checked_div_u64(10_000_000_000_000_000_000),
+            Some(U256::from(u128::MAX))
+        );
+        assert_eq!(U256::from(7u64).checked_div_u64(2), Some(U256::from(3u64)));
+        assert_eq!(U256::from(7u64).checked_div_u64(0), None);
+
+        // 2^128 * 2^128 is the first product past 256 bits.
+        let half = U256::from(u128::MAX).checked_mul_u64(1).unwrap();
+        assert!(half.checked_mul(half).is_some());
+        let two_128 = U256([0, 0, 1, 0]);
+        assert_eq!(two_128.checked_mul(two_128), None);
+    }
+
+    #[test]
+    fn narrows_to_u64_only_when_it_fits() {
+        assert_eq!(u64::try_from(U256::from(u64::MAX)), Ok(u64::MAX));
+        assert!(u64::try_from(U256::from(u128::from(u64::MAX) + 1)).is_err());
+    }
+}
@@ -0,0 +1,101 @@
+//! Mock of Circle's CCTP TokenMessengerMinter for tests
+//!
+//! `deposit_for_burn` takes the accounts and parameters of the real instruction, burns the
+//! tokens and records the burn in the message account instead of sending a CCTP message.
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Burn, Mint, TokenAccount, TokenInterface};
+
+declare_id!("GXzjEdSte3xr8V8Udc6BgA4G13Ygs6kfq1iYd3VNHzAF");
+
+#[program]
+pub mod mock_token_messenger_minter {
+    use super::*;
+
+    pub fn deposit_for_burn(
+        ctx: Context<DepositForBurn>,
+        params: DepositForBurnParams,
+    ) -> Result<()> {
+        token_interface::burn(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Burn {
+                    mint: ctx.accounts.burn_token_mint.to_account_info(),
+                    from: ctx.accounts.burn_token_account.to_account_info(),
+                    authority: ctx.accounts.owner.to_account_info(),
+                },
+            ),
+            params.amount,
+        )?;
+
+        let message = &mut ctx.accounts.message_sent_event_data;
+        message.rent_payer = ctx.accounts.event_rent_payer.key();
+        message.depositor = ctx.accounts.owner.key();
+        message.amount = params.amount;
+        message.destination_domain = params.destination_domain;
+        message.mint_recipient = params.mint_recipient;
+
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct DepositForBurnParams {
+    pub amount: u64,
+    pub destination_domain: u32,
+    pub mint_recipient: Pubkey,
+}
+
+/// The burn recorded by the mock in place of a CCTP message
+#[account]
+#[derive(InitSpace)]
+pub struct MessageSent {
+    pub rent_payer: Pubkey,
+    pub depositor: Pubkey,
+    pub amount: u64,
+    pub destination_domain: u32,
+    pub mint_recipient: Pubkey,
+}
+
+#[derive(Accounts)]
+pub struct DepositForBurn<'info> {
+    pub owner: Signer<'info>,
+    #[account(mut)]
+    pub event_rent_payer: Signer<'info>,
+    /// CHECK: Unused by the mock
+    #[account(seeds = [b"sender_authority"], bump)]
+    pub sender_authority_pda: UncheckedAccount<'info>,
+    #[account(mut, token::authority = owner)]
+    pub burn_token_account: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: Unused by the mock
+    #[account(mut)]
+    pub message_transmitter: UncheckedAccount<'info>,
+    /// CHECK: Unused by the mock
+    pub token_messenger: UncheckedAccount<'info>,
+    /// CHECK: Unused by the mock
+    pub remote_token_messenger: UncheckedAccount<'info>,
+    /// CHECK: Unused by the mock
+    pub token_minter: UncheckedAccount<'info>,
+    /// CHECK: Unused by the mock
+    #[account(mut)]
+    pub local_token: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub burn_token_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        init,
+        payer = event_rent_payer,
+        space = 8 + MessageSent::INIT_SPACE
+    )]
+    pub message_sent_event_data: Account<'info, MessageSent>,
+    /// CHECK: Unused by the mock
+    pub message_transmitter_program: UncheckedAccount<'info>,
+    /// CHECK: Unused by the mock
+    pub token_messenger_minter_program: UncheckedAccount<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    /// CHECK: Unused by the mock
+    #[account(seeds = [b"__event_authority"], bump)]
+    pub event_authority: UncheckedAccount<'info>,
+    /// CHECK: Unused by the mock
+    pub program: UncheckedAccount<'info>,
+}
@@ -0,0 +1,123 @@
+import { BoringBridgeHolder } from "../target/types/boring_bridge_holder";
+import { Program } from "@coral-xyz/anchor";
+import { AddressLookupTableProgram, TransactionInstruction, TransactionMessage, VersionedTransaction } from "@solana/web3.js";
+import 'dotenv/config';
+import { readFileSync } from 'fs';
+import { join } from 'path';
+
+// To run this do this
+// ts-node scripts/create_lookup_table.ts
+
+const anchor = require("@coral-xyz/anchor");
+const provider = anchor.AnchorProvider.env();
+anchor.setProvider(provider);
+
+// Get program ID and wallet from provider
+const program = anchor.workspace.BoringBridgeHolder as Program<BoringBridgeHolder>;
+const connection = provider.connection;
+
+console.log("Creating lookup table...");
+
+// Signs and sends a v0 transaction from the wallet
+async function sendV0(instructions: TransactionInstruction[]): Promise<string> {
+  const { blockhash } = await connection.getLatestBlockhash();
+  const message = new TransactionMessage({
+    payerKey: provider.wallet.publicKey,
+    recentBlockhash: blockhash,
+    instructions,
+  }).compileToV0Message();
+  const tx = await provider.wallet.signTransaction(new VersionedTransaction(message));
+  const signature = await connection.sendTransaction(tx);
+  const result = await connection.confirmTransaction(signature);
+  if (result.value.err) {
+    throw new Error(`Transaction ${signature} failed: ${JSON.stringify(result.value.err)}`);
+  }
+  return signature;
+}
+
+try {
+  const creator = provider.wallet;
+  const owner = provider.wallet;
+
+  // Read the config file
+  const config = JSON.parse(readFileSync(join(__dirname, 'config.json'), 'utf8'));
+
+  const ATA_PROGRAM_ID = new anchor.web3.PublicKey(config.ATA_PROGRAM_ID);
+  const token2022Program = new anchor.web3.PublicKey(config.token2022Program);
+  const mint = new anchor.web3.PublicKey(config.mint);
+
+  // Find the boring account PDA
+  const [boringAccount] = anchor.web3.PublicKey.findProgramAddressSync(
+    [
+      Buffer.from("boring_state"),
+      creator.publicKey.toBuffer()
+    ],
+    program.programId
+  );
+
+  const [boringAccountAta] = anchor.web3.PublicKey.findProgramAddressSync(
+    [
+      boringAccount.toBuffer(),
+      token2022Program.toBuffer(),
+      mint.toBuffer(),
+    ],
+    ATA_PROGRAM_ID
+  );
+
+  // Every address of transfer_remote that doesn't change between transfers: the static
+  // addresses of the configuration, the holder and its token account, and the programs.
+  // The unique message and the PDAs derived from it change with each transfer.
+  const addresses = [
+    program.programId,
+    anchor.web3.SystemProgram.programId,
+    boringAccount,
+    boringAccountAta,
+    ...[
+      config.targetProgram,
+      config.noop,
+      config.tokenPda,
+      config.mailboxProgram,
+      config.mailboxOutbox,
+      config.messageDispatchAuthority,
+      config.igpProgram,
+      config.igpProgramData,
+      config.igpAccount,
+      config.tokenSender,
+      config.token2022Program,
+      config.mintAuth,
+      config.mint,
+    ].map(address => new anchor.web3.PublicKey(address)),
+  ];
+
+  // Create the table and add the addresses
+  const slot = await connection.getSlot("finalized");
+  const [createIx, lookupTable] = AddressLookupTableProgram.createLookupTable({
+    authority: owner.publicKey,
+    payer: owner.publicKey,
+    recentSlot: slot,
+  });
+  const extendIx = AddressLookupTableProgram.extendLookupTable({
+    lookupTable,
+    authority: owner.publicKey,
+    payer: owner.publicKey,
+    addresses,
+  });
+  const createTx = await sendV0([createIx, extendIx]);
+  console.log("Lookup table created: ", lookupTable.toString(), createTx);
+
+  // Record the table in the holder so clients can discover it
+  const recordIx = await program.methods
+    .setLookupTable(lookupTable)
+    .accounts({
+      // @ts-ignore
+      boringAccount: boringAccount,
+      signer: owner.publicKey,
+    })
+    .instruction();
+  const recordTx = await sendV0([recordIx]);
+  console.log("Lookup table recorded: ", recordTx);
+
+} catch (error) {
+  console.error("Create lookup table failed:", error);
+  throw error;
+}
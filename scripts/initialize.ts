@@ -29,6 +29,7 @@ try {
     Buffer.from(evmAddressHex, 'hex') // 20 bytes of address
   ]);
   const evmRecipient = Array.from(evmRecipientBuffer);
+  const recipient = { evm20: [evmRecipient] };
 
   // Initialize configParams
   let configParams = {
@@ -45,8 +46,11 @@ try {
     token2022Program: new anchor.web3.PublicKey(config.token2022Program),
     mintAuth: new anchor.web3.PublicKey(config.mintAuth),
     destinationDomain: new anchor.BN(config.destinationDomain),
-    evmRecipient: evmRecipient,
+    recipient,
     decimals: new anchor.BN(config.decimals),
+    remoteDecimals: config.remoteDecimals,
+    bridgeKind: { hyperlaneWarpRoute: {} },
+    mint: new anchor.web3.PublicKey(config.mint),
   }
 
   // Find the boring account PDA
@@ -1,6 +1,6 @@
 import { BoringBridgeHolder } from "../target/types/boring_bridge_holder";
 import { Program } from "@coral-xyz/anchor";
-import { ComputeBudgetProgram } from "@solana/web3.js";
+import { ComputeBudgetProgram, TransactionMessage, VersionedTransaction } from "@solana/web3.js";
 import 'dotenv/config';
 import { readFileSync } from 'fs';
 import { join } from 'path';
@@ -20,6 +20,8 @@ console.log("Transferring remote...");
 try {
   // The amount to transfer
   let amount = new anchor.BN(100000); // 0.0001 weETHs with 9 decimals
+  // The most lamports the strategist may spend on gas payment and message rent
+  const maxGasPaymentLamports = new anchor.BN(50_000_000); // 0.05 ETH
   const creator = new anchor.web3.PublicKey('DuheUFDBEGh1xKKvCvcTPQwA8eR3oo58kzVpB54TW5TP');
 
   const strategist = provider.wallet;
@@ -36,6 +38,7 @@ try {
     Buffer.from(evmAddressHex, 'hex') // 20 bytes of address
   ]);
   const evmRecipient = Array.from(evmRecipientBuffer);
+  const recipient = { evm20: [evmRecipient] };
   const destinationDomain = new anchor.BN(config.destinationDomain);
   const decimals = new anchor.BN(config.decimals);
 
@@ -54,8 +57,10 @@ try {
     token2022Program: new anchor.web3.PublicKey(config.token2022Program),
     mintAuth: new anchor.web3.PublicKey(config.mintAuth),
     destinationDomain,
-    evmRecipient,
+    recipient,
     decimals,
+    remoteDecimals: config.remoteDecimals,
+    mint: new anchor.web3.PublicKey(config.mint),
   };
 
   // Find the boring account PDA
@@ -67,7 +72,16 @@ try {
     program.programId
   );
 
-  const uniqueMessage = anchor.web3.Keypair.generate();
+  // The unique message is a PDA derived from the holder's current message nonce
+  const { messageNonce, lookupTable } = await program.account.boringState.fetch(boringAccount);
+  const [uniqueMessage] = anchor.web3.PublicKey.findProgramAddressSync(
+    [
+      Buffer.from("unique_message"),
+      boringAccount.toBuffer(),
+      messageNonce.toArrayLike(Buffer, "le", 8)
+    ],
+    program.programId
+  );
 
   const [messageStoragePda] = anchor.web3.PublicKey.findProgramAddressSync(
     [
@@ -75,7 +89,7 @@ try {
       Buffer.from("-"),
       Buffer.from("dispatched_message"),
       Buffer.from("-"),
-      uniqueMessage.publicKey.toBuffer()
+      uniqueMessage.toBuffer()
     ],
     configParams.mailboxProgram
   );
@@ -86,7 +100,7 @@ try {
       Buffer.from("-"),
       Buffer.from("gas_payment"),
       Buffer.from("-"),
-      uniqueMessage.publicKey.toBuffer()
+      uniqueMessage.toBuffer()
     ],
     configParams.igpProgram
   );
@@ -95,7 +109,7 @@ try {
     [
       boringAccount.toBuffer(),
       configParams.token2022Program.toBuffer(),
-      configParams.mintAuth.toBuffer(),
+      configParams.mint.toBuffer(),
     ],
     ATA_PROGRAM_ID
   );
@@ -104,58 +118,78 @@ try {
     [
       strategist.publicKey.toBuffer(),
       configParams.token2022Program.toBuffer(),
-      configParams.mintAuth.toBuffer(),
+      configParams.mint.toBuffer(),
     ],
     ATA_PROGRAM_ID
   );
 
-  program.methods
+  const transferRemoteIx = await program.methods
     .transferRemote(
       destinationDomain,
-      evmRecipient,
-      decimals,
+      recipient,
+      config.remoteDecimals,
       amount,
+      maxGasPaymentLamports,
     )
     .accounts({
       // @ts-ignore
-      boringAccount: boringAccount,
-      signer: strategist.publicKey,
-      targetProgram: configParams.targetProgram,
-      systemProgram: anchor.web3.SystemProgram.programId,
-      noop: configParams.noop,
-      tokenPda: configParams.tokenPda,
-      mailboxProgram: configParams.mailboxProgram,
-      mailboxOutbox: configParams.mailboxOutbox,
-      messageDispatchAuthority: configParams.messageDispatchAuthority,
-      uniqueMessage: uniqueMessage.publicKey,
-      messageStoragePda,
-      igpProgram: configParams.igpProgram,
-      igpProgramData: configParams.igpProgramData,
-      gasPaymentPda,
-      igpAccount: configParams.igpAccount,
-      tokenSender: configParams.tokenSender,
-      token2022: configParams.token2022Program,
-      mintAuth: configParams.mintAuth,
-      boringAccountAta,
-      strategistAta,
+      transfer: {
+        boringAccount: boringAccount,
+        signer: strategist.publicKey,
+        targetProgram: configParams.targetProgram,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        noop: configParams.noop,
+        tokenPda: configParams.tokenPda,
+        mailboxProgram: configParams.mailboxProgram,
+        mailboxOutbox: configParams.mailboxOutbox,
+        messageDispatchAuthority: configParams.messageDispatchAuthority,
+        uniqueMessage: uniqueMessage,
+        messageStoragePda,
+        igpProgram: configParams.igpProgram,
+        igpProgramData: configParams.igpProgramData,
+        gasPaymentPda,
+        igpAccount: configParams.igpAccount,
+        tokenSender: configParams.tokenSender,
+        token2022: configParams.token2022Program,
+        mintAuth: configParams.mintAuth,
+        mint: configParams.mint,
+        boringAccountAta,
+        strategistAta,
+      },
     })
-    .signers([uniqueMessage])
-    .preInstructions([
+    .instruction();
+
+  // Build a v0 transaction against the holder's lookup table, recorded with
+  // create_lookup_table.ts, to keep the 20+ accounts of transfer_remote within the size limit
+  const connection = provider.connection;
+  const lookupTables = [];
+  if (!lookupTable.equals(anchor.web3.PublicKey.default)) {
+    const { value } = await connection.getAddressLookupTable(lookupTable);
+    if (value === null) {
+      throw new Error(`Lookup table ${lookupTable.toString()} not found`);
+    }
+    lookupTables.push(value);
+  }
+
+  const { blockhash } = await connection.getLatestBlockhash();
+  const message = new TransactionMessage({
+    payerKey: strategist.publicKey,
+    recentBlockhash: blockhash,
+    instructions: [
       ComputeBudgetProgram.setComputeUnitLimit({
         units: 400_000 // Increase compute units
-      })
-    ])
-    .rpc()
-    .then(tx => 
-      anchor.AnchorProvider.env().connection.confirmTransaction(tx)
-        .then(result => {
-          if (result.value.err) {
-            console.error("Transfer remote failed:", result.value.err);
-          } else {
-            console.log("Transfer remote successful: ", tx);
-          }
-        })
-    );
+      }),
+      transferRemoteIx,
+    ],
+  }).compileToV0Message(lookupTables);
+  const tx = await strategist.signTransaction(new VersionedTransaction(message));
+  const signature = await connection.sendTransaction(tx);
+  const result = await connection.confirmTransaction(signature);
+  if (result.value.err) {
+    console.error("Transfer remote failed:", result.value.err);
+  } else {
+    console.log("Transfer remote successful: ", signature);
+  }
 
 } catch (error) {
   console.error("Transfer remote failed:", error);
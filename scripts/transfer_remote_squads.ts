@@ -21,6 +21,8 @@ console.log("Generating transfer remote base58 encoded transaction message...");
 
   // The amount to transfer
   let amount = new anchor.BN(100000); // 0.0001 weETHs with 9 decimals
+  // The most lamports the strategist may spend on gas payment and message rent
+  const maxGasPaymentLamports = new anchor.BN(50_000_000); // 0.05 ETH
   const creator = new anchor.web3.PublicKey('DuheUFDBEGh1xKKvCvcTPQwA8eR3oo58kzVpB54TW5TP');
 
   const strategist = new anchor.web3.PublicKey("J2V6fTUnxem8WLwWiofAuptFwP3sJNeKcT8SRWDDrQ4z");
@@ -39,6 +41,7 @@ console.log("Generating transfer remote base58 encoded transaction message...");
     Buffer.from(evmAddressHex, 'hex') // 20 bytes of address
   ]);
   const evmRecipient = Array.from(evmRecipientBuffer);
+  const recipient = { evm20: [evmRecipient] };
   const destinationDomain = new anchor.BN(config.destinationDomain);
   const decimals = new anchor.BN(config.decimals);
 
@@ -57,8 +60,10 @@ console.log("Generating transfer remote base58 encoded transaction message...");
     token2022Program: new anchor.web3.PublicKey(config.token2022Program),
     mintAuth: new anchor.web3.PublicKey(config.mintAuth),
     destinationDomain,
-    evmRecipient,
+    recipient,
     decimals,
+    remoteDecimals: config.remoteDecimals,
+    mint: new anchor.web3.PublicKey(config.mint),
   };
 
   // Find the boring account PDA
@@ -70,7 +75,16 @@ console.log("Generating transfer remote base58 encoded transaction message...");
     program.programId
   );
 
-  const uniqueMessage = anchor.web3.Keypair.generate();
+  // The unique message is a PDA derived from the holder's current message nonce
+  const { messageNonce } = await program.account.boringState.fetch(boringAccount);
+  const [uniqueMessage] = anchor.web3.PublicKey.findProgramAddressSync(
+    [
+      Buffer.from("unique_message"),
+      boringAccount.toBuffer(),
+      messageNonce.toArrayLike(Buffer, "le", 8)
+    ],
+    program.programId
+  );
 
   const [messageStoragePda] = anchor.web3.PublicKey.findProgramAddressSync(
     [
@@ -78,7 +92,7 @@ console.log("Generating transfer remote base58 encoded transaction message...");
       Buffer.from("-"),
       Buffer.from("dispatched_message"),
       Buffer.from("-"),
-      uniqueMessage.publicKey.toBuffer()
+      uniqueMessage.toBuffer()
     ],
     configParams.mailboxProgram
   );
@@ -89,7 +103,7 @@ console.log("Generating transfer remote base58 encoded transaction message...");
       Buffer.from("-"),
       Buffer.from("gas_payment"),
       Buffer.from("-"),
-      uniqueMessage.publicKey.toBuffer()
+      uniqueMessage.toBuffer()
     ],
     configParams.igpProgram
   );
@@ -98,7 +112,7 @@ console.log("Generating transfer remote base58 encoded transaction message...");
     [
       boringAccount.toBuffer(),
       configParams.token2022Program.toBuffer(),
-      configParams.mintAuth.toBuffer(),
+      configParams.mint.toBuffer(),
     ],
     ATA_PROGRAM_ID
   );
@@ -107,39 +121,43 @@ console.log("Generating transfer remote base58 encoded transaction message...");
     [
       strategist.toBuffer(),
       configParams.token2022Program.toBuffer(),
-      configParams.mintAuth.toBuffer(),
+      configParams.mint.toBuffer(),
     ],
     ATA_PROGRAM_ID
   );
   const tx = await program.methods
     .transferRemote(
       destinationDomain,
-      evmRecipient,
-      decimals,
+      recipient,
+      config.remoteDecimals,
       amount,
+      maxGasPaymentLamports,
     )
     .accounts({
       // @ts-ignore
-      boringAccount: boringAccount,
-      signer: strategist.publicKey,
-      targetProgram: configParams.targetProgram,
-      systemProgram: anchor.web3.SystemProgram.programId,
-      noop: configParams.noop,
-      tokenPda: configParams.tokenPda,
-      mailboxProgram: configParams.mailboxProgram,
-      mailboxOutbox: configParams.mailboxOutbox,
-      messageDispatchAuthority: configParams.messageDispatchAuthority,
-      uniqueMessage: uniqueMessage.publicKey,
-      messageStoragePda,
-      igpProgram: configParams.igpProgram,
-      igpProgramData: configParams.igpProgramData,
-      gasPaymentPda,
-      igpAccount: configParams.igpAccount,
-      tokenSender: configParams.tokenSender,
-      token2022: configParams.token2022Program,
-      mintAuth: configParams.mintAuth,
-      boringAccountAta,
-      strategistAta,
+      transfer: {
+        boringAccount: boringAccount,
+        signer: strategist.publicKey,
+        targetProgram: configParams.targetProgram,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        noop: configParams.noop,
+        tokenPda: configParams.tokenPda,
+        mailboxProgram: configParams.mailboxProgram,
+        mailboxOutbox: configParams.mailboxOutbox,
+        messageDispatchAuthority: configParams.messageDispatchAuthority,
+        uniqueMessage: uniqueMessage,
+        messageStoragePda,
+        igpProgram: configParams.igpProgram,
+        igpProgramData: configParams.igpProgramData,
+        gasPaymentPda,
+        igpAccount: configParams.igpAccount,
+        tokenSender: configParams.tokenSender,
+        token2022: configParams.token2022Program,
+        mintAuth: configParams.mintAuth,
+        mint: configParams.mint,
+        boringAccountAta,
+        strategistAta,
+      },
     })
     .transaction();
 
@@ -39,6 +39,7 @@ async function main() {
       Buffer.from(evmAddressHex, 'hex') // 20 bytes of address
     ]);
     const evmRecipient = Array.from(evmRecipientBuffer);
+    const recipient = { evm20: [evmRecipient] };
 
     // Initialize configParams
     let newConfig = {
@@ -55,8 +56,11 @@ async function main() {
       token2022Program: new anchor.web3.PublicKey(config.token2022Program),
       mintAuth: new anchor.web3.PublicKey(config.mintAuth),
       destinationDomain: new anchor.BN(config.destinationDomain),
-      evmRecipient: evmRecipient,
+      recipient,
       decimals: new anchor.BN(config.decimals),
+      remoteDecimals: config.remoteDecimals,
+      bridgeKind: { hyperlaneWarpRoute: {} },
+      mint: new anchor.web3.PublicKey(config.mint),
     }
 
     // Create the instruction
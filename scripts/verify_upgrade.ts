@@ -10,36 +10,23 @@ anchor.setProvider(provider);
 // Get program ID and wallet from provider
 const program = anchor.workspace.BoringBridgeHolder as Program<BoringBridgeHolder>;
 
-console.log("Updating strategist...");
-  
-try {
-  const creator = provider.wallet;
-  const owner = provider.wallet;
-  const newStrategist = new anchor.web3.PublicKey("J2V6fTUnxem8WLwWiofAuptFwP3sJNeKcT8SRWDDrQ4z");
-  
-  // Find the boring account PDA
-  const [boringAccount] = anchor.web3.PublicKey.findProgramAddressSync(
-    [
-      Buffer.from("boring_state"),
-      creator.publicKey.toBuffer()
-    ],
-    program.programId
-  );
+console.log("Reading program version...");
 
-  // Update strategist
-  program.methods
-    .version()
-    .accounts({})
-    .signers([])
-    .rpc().then(tx => anchor.AnchorProvider.env().connection.confirmTransaction(tx).then(result => {
-      if (result.value.err) {
-        console.error("Version call failed:", result.value.err);
-      } else {
-        console.log("Version call successful: ", tx);
-      }
-    }));
-  
-} catch (error) {
-  console.error("Version call failed:", error);
-  throw error;
-}
\ No newline at end of file
+async function main() {
+  try {
+    // Simulate the version instruction and decode its return data
+    const version = await program.methods
+      .version()
+      .accounts({})
+      .view();
+
+    console.log(`Program version: ${version.major}.${version.minor}.${version.patch}`);
+    console.log("Git commit: ", version.gitCommit);
+    console.log("Features: ", version.features.join(", ") || "none");
+  } catch (error) {
+    console.error("Version call failed:", error);
+    throw error;
+  }
+}
+
+main();
@@ -2,13 +2,18 @@ import * as anchor from "@coral-xyz/anchor";
 import { BankrunProvider, startAnchor } from "anchor-bankrun";
 import { Program } from "@coral-xyz/anchor";
 import { BoringBridgeHolder } from "../target/types/boring_bridge_holder";
+import { MockTokenMessengerMinter } from "../target/types/mock_token_messenger_minter";
 import { expect } from "chai";
+import { createHash } from "crypto";
 import { ComputeBudgetProgram } from "@solana/web3.js";
 import {
   ACCOUNT_SIZE,
   AccountLayout,
+  MINT_SIZE,
+  MintLayout,
   getAssociatedTokenAddressSync,
-  TOKEN_2022_PROGRAM_ID
+  TOKEN_2022_PROGRAM_ID,
+  TOKEN_PROGRAM_ID
 } from "@solana/spl-token";
 import {
   AddedAccount,
@@ -51,8 +56,27 @@ describe("boring-bridge-holder", () => {
     Buffer.alloc(12, 0),
     Buffer.from(evmAddressHex, 'hex')
   ]);
+  const recipient = { evm20: [Array.from(evmRecipient)] };
   const decimals = new anchor.BN(6);
+  // The remote token has the same decimals, so legacy configuration hashes can be migrated.
+  const remoteDecimals = 6;
+  // Version of the configuration hash preimage layout
+  const CONFIG_HASH_VERSION = 5;
   const amountToTransfer = 1_000_000_000;
+  const maxGasPaymentLamports = new anchor.BN(100_000_000);
+  // Compute units a default build's `transfer_remote` is estimated to consume: about 35,000 in
+  // the holder itself (account checks, the configuration hash and the Token-2022 transfer) and
+  // about 100,000 in the warp route's burn, mailbox dispatch and IGP payment. The figure is an
+  // estimate that has not been measured yet; replace it with the `computeUnitsConsumed` this
+  // test reports once it runs.
+  const transferRemoteEstimatedComputeUnits = 135_000;
+  // Budget `transfer_remote` must stay within: the estimate plus about 10% headroom, well below
+  // the default limit of 200,000 of a single instruction, so a regression in the holder's own
+  // cost fails the test.
+  const transferRemoteComputeBudget = 150_000;
+  // Compute unit limit requested by instructions that transfer more than once, such as batches
+  // and transfers with a companion message.
+  const multiTransferComputeUnitLimit = 400_000;
 
   // Array of accounts to clone from mainnet
   const ACCOUNTS_TO_CLONE = [
@@ -69,18 +93,18 @@ describe("boring-bridge-holder", () => {
     client: BanksClient,
     payer: Keypair,
     instruction: TransactionInstruction,
-    additionalSigners: Keypair[] = []
+    additionalSigners: Keypair[] = [],
+    computeUnitLimit?: number
   ): Promise<BanksTransactionResultWithMeta> {
     const tx = new Transaction();
     const [latestBlockhash] = await client.getLatestBlockhash();
     tx.recentBlockhash = latestBlockhash;
     tx.add(instruction);
     tx.feePayer = payer.publicKey;
-    tx.add(
-      ComputeBudgetProgram.setComputeUnitLimit({
-        units: 400_000,
-      })
-    );
+    // Without a limit the instruction gets the default 200k compute units.
+    if (computeUnitLimit !== undefined) {
+      tx.add(ComputeBudgetProgram.setComputeUnitLimit({ units: computeUnitLimit }));
+    }
     tx.sign(payer, ...additionalSigners);
     return await client.tryProcessTransaction(tx);
   }
@@ -89,7 +113,8 @@ describe("boring-bridge-holder", () => {
     context: ProgramTestContext,
     mintAccount: PublicKey,
     owner: PublicKey,
-    amount: number
+    amount: number,
+    tokenProgram: PublicKey = TOKEN_2022_PROGRAM_ID
   ): Promise<PublicKey> {
     const tokenAccData = Buffer.alloc(ACCOUNT_SIZE);
     AccountLayout.encode(
@@ -109,11 +134,11 @@ describe("boring-bridge-holder", () => {
       tokenAccData,
     );
   
-    const ata = getAssociatedTokenAddressSync(mintAccount, owner, true, TOKEN_2022_PROGRAM_ID);
+    const ata = getAssociatedTokenAddressSync(mintAccount, owner, true, tokenProgram);
     const ataAccountInfo = {
       lamports: 1_000_000_000,
       data: tokenAccData,
-      owner: TOKEN_2022_PROGRAM_ID,
+      owner: tokenProgram,
       executable: false,
     };
   
@@ -121,8 +146,120 @@ describe("boring-bridge-holder", () => {
     return ata;
   }
 
-  function generateMessagePDAs(mailboxProgram: anchor.web3.PublicKey, igpProgram: anchor.web3.PublicKey) {
-    const uniqueMessage = anchor.web3.Keypair.generate();
+  // Encodes a configuration in the preimage layout of a configuration hash version. Version 4
+  // predates the mint, the last 32 bytes, and version 3 the recipient format, the byte after
+  // the destination domain. Version 2 also predates the remote decimals, then the second to
+  // last byte, and version 1 the bridge kind, then the last byte.
+  function encodeConfig(config: any, version: number = CONFIG_HASH_VERSION): Buffer {
+    let encoded = program.coder.types.encode("configurationData", config);
+    if (version >= 5) {
+      return encoded;
+    }
+    encoded = encoded.subarray(0, -32);
+    if (version >= 4) {
+      return encoded;
+    }
+    const recipientOffset = 12 * 32 + 4;
+    return Buffer.concat([
+      encoded.subarray(0, recipientOffset),
+      encoded.subarray(recipientOffset + 1, -2),
+      version >= 3 ? encoded.subarray(-2, -1) : Buffer.alloc(0),
+      version >= 2 ? encoded.subarray(-1) : Buffer.alloc(0),
+    ]);
+  }
+
+  function computeConfigHash(holder: PublicKey, config: any, version: number = CONFIG_HASH_VERSION): Buffer {
+    const configBytes = encodeConfig(config, version);
+    return createHash("sha256")
+      .update(Buffer.from("boring_bridge_holder:configuration"))
+      .update(Buffer.from([version]))
+      .update(program.programId.toBuffer())
+      .update(holder.toBuffer())
+      .update(configBytes)
+      .digest();
+  }
+
+  // Builds the raw data of a version 1 BoringState account, which predates the version byte.
+  function buildV1BoringStateFixture(
+    creator: PublicKey,
+    owner: PublicKey,
+    strategist: PublicKey,
+    configHash: Buffer,
+    bump: number
+  ): Buffer {
+    const discriminator = createHash("sha256").update("account:BoringState").digest().subarray(0, 8);
+    return Buffer.concat([
+      discriminator,
+      creator.toBuffer(),
+      owner.toBuffer(),
+      strategist.toBuffer(),
+      configHash,
+      Buffer.from([bump]),
+    ]);
+  }
+
+  // Builds the raw data of a version 7 BoringState account, the last Borsh layout before
+  // zero-copy loading, with one domain total, an enabled governor and a message nonce.
+  function buildV7BoringStateFixture(
+    creator: PublicKey,
+    owner: PublicKey,
+    strategist: PublicKey,
+    configHash: Buffer,
+    bump: number,
+    domain: number,
+    totalBridged: number,
+    governorOrigin: number,
+    governorSender: Buffer,
+    messageNonce: number
+  ): Buffer {
+    const u32 = (value: number) => new anchor.BN(value).toArrayLike(Buffer, "le", 4);
+    const u64 = (value: number) => new anchor.BN(value).toArrayLike(Buffer, "le", 8);
+    const domainTotals = [
+      Buffer.concat([u32(domain), u64(totalBridged), u64(1)]),
+      Buffer.alloc(3 * (4 + 8 + 8)),
+    ];
+    return Buffer.concat([
+      buildV1BoringStateFixture(creator, owner, strategist, configHash, bump),
+      Buffer.from([7]),
+      // Total bridged, transfer count, last transfer slot and timestamp.
+      u64(totalBridged),
+      u64(1),
+      u64(0),
+      u64(0),
+      ...domainTotals,
+      // A disabled auto-bridge policy.
+      Buffer.alloc(1 + 8 * 4),
+      // Sweep reserve and message nonce.
+      u64(0),
+      u64(messageNonce),
+      Buffer.from([1]),
+      u32(governorOrigin),
+      governorSender,
+      Buffer.alloc(58),
+    ]);
+  }
+
+  async function messageNonce(holder: PublicKey = boringAccount): Promise<anchor.BN> {
+    const info = await client.getAccount(holder);
+    const state = program.coder.accounts.decode("boringState", Buffer.from(info.data));
+    return state.messageNonce;
+  }
+
+  function uniqueMessagePDA(holder: PublicKey, nonce: anchor.BN): PublicKey {
+    const [uniqueMessage] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("unique_message"),
+        holder.toBuffer(),
+        nonce.toArrayLike(Buffer, "le", 8)
+      ],
+      program.programId
+    );
+    return uniqueMessage;
+  }
+
+  async function generateMessagePDAs(mailboxProgram: anchor.web3.PublicKey, igpProgram: anchor.web3.PublicKey) {
+    // The unique message is derived from the holder's current message nonce.
+    const uniqueMessage = uniqueMessagePDA(boringAccount, await messageNonce());
     
     const [messageStoragePda] = anchor.web3.PublicKey.findProgramAddressSync(
       [
@@ -130,7 +267,7 @@ describe("boring-bridge-holder", () => {
         Buffer.from("-"),
         Buffer.from("dispatched_message"),
         Buffer.from("-"),
-        uniqueMessage.publicKey.toBuffer()
+        uniqueMessage.toBuffer()
       ],
       mailboxProgram
     );
@@ -141,7 +278,7 @@ describe("boring-bridge-holder", () => {
         Buffer.from("-"),
         Buffer.from("gas_payment"),
         Buffer.from("-"),
-        uniqueMessage.publicKey.toBuffer()
+        uniqueMessage.toBuffer()
       ],
       igpProgram
     );
@@ -233,8 +370,12 @@ describe("boring-bridge-holder", () => {
       token2022Program: new anchor.web3.PublicKey("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb"),
       mintAuth: new anchor.web3.PublicKey("AKEWE7Bgh87GPp171b4cJPSSZfmZwQ3KaqYqXoKLNAEE"),
       destinationDomain: destinationDomain,
-      evmRecipient: evmRecipient,
+      recipient,
       decimals: decimals,
+      remoteDecimals,
+      bridgeKind: { hyperlaneWarpRoute: {} },
+      // The test route is synthetic, so its mint authority is the mint.
+      mint: new anchor.web3.PublicKey("AKEWE7Bgh87GPp171b4cJPSSZfmZwQ3KaqYqXoKLNAEE"),
     }
     // Find PDAs
     let bump;
@@ -283,6 +424,10 @@ describe("boring-bridge-holder", () => {
     // Verify the config hash is not all zeros
     const isAllZeros = programBoringAccount.configHash.every(byte => byte === 0);
     expect(isAllZeros).to.be.false;
+    // Verify the state uses the current layout
+    expect(programBoringAccount.stateVersion).to.equal(10);
+    // Verify the config hash is bound to this program and holder
+    expect(Buffer.from(programBoringAccount.configHash)).to.deep.equal(computeConfigHash(boringAccount, configParams));
   });
 
   it("Can transfer ownership", async () => {
@@ -423,6 +568,446 @@ describe("boring-bridge-holder", () => {
     expect(updatedConfigHash2).to.deep.equal(existingConfigHash);
   });
 
+  // Encodes a zero-copy BoringState account owned by `owner` with empty statistics, overriding
+  // the given fields.
+  async function encodeBoringState(fields: any): Promise<Buffer> {
+    return program.coder.accounts.encode("boringState", {
+      owner: owner.publicKey,
+      strategist: strategist.publicKey,
+      stateVersion: 10,
+      padding: Array(6).fill(0),
+      totalBridged: new anchor.BN(0),
+      transferCount: new anchor.BN(0),
+      lastTransferSlot: new anchor.BN(0),
+      lastTransferTimestamp: new anchor.BN(0),
+      domainTotals: Array(4).fill({
+        totalBridged: new anchor.BN(0),
+        transferCount: new anchor.BN(0),
+        domain: 0,
+        bridgeKind: 0,
+        padding: Array(3).fill(0),
+      }),
+      autoBridgePolicy: {
+        minBalance: new anchor.BN(0),
+        amount: new anchor.BN(0),
+        minIntervalSeconds: new anchor.BN(0),
+        keeperBountyLamports: new anchor.BN(0),
+        enabled: 0,
+        padding: Array(7).fill(0),
+      },
+      sweepReserve: new anchor.BN(0),
+      messageNonce: new anchor.BN(0),
+      governor: { sender: Array(32).fill(0), origin: 0, enabled: 0, padding: Array(3).fill(0) },
+      lookupTable: PublicKey.default,
+      reserved: Array(32).fill(0),
+      ...fields,
+    });
+  }
+
+  // Creates a holder account owned by `owner` that stores the config hash `configHash` computes
+  // for its address, and returns the address.
+  async function setHolderWithConfigHash(configHash: (holder: PublicKey) => Buffer): Promise<PublicKey> {
+    const legacyCreator = anchor.web3.Keypair.generate();
+    const [legacyAccount, legacyBump] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("boring_state"),
+        legacyCreator.publicKey.toBuffer()
+      ],
+      program.programId
+    );
+    const legacyData = await encodeBoringState({
+      creator: legacyCreator.publicKey,
+      configHash: Array.from(configHash(legacyAccount)),
+      bump: legacyBump,
+    });
+    context.setAccount(legacyAccount, {
+      lamports: 1_000_000_000,
+      data: legacyData,
+      owner: program.programId,
+      executable: false,
+    });
+    return legacyAccount;
+  }
+
+  it("Can migrate a legacy configuration hash", async () => {
+    // Create a holder account that still stores the legacy, unversioned config hash, which
+    // predates the version 1 layout.
+    const legacyHash = createHash("sha256").update(encodeConfig(configParams, 1)).digest();
+    const legacyAccount = await setHolderWithConfigHash(() => legacyHash);
+
+    const ix0 = await program.methods
+      // @ts-ignore
+      .migrateConfigurationHash(configParams)
+      .accounts({
+        // @ts-ignore
+        boringAccount: legacyAccount,
+        signer: strategist.publicKey,
+      })
+      .signers([strategist])
+      .instruction();
+
+    let txResult0 = await createAndProcessTransaction(client, creator, ix0, [creator, strategist]);
+
+    // Expect the tx to succeed.
+    expect(txResult0.result).to.be.null;
+
+    // The migrated hash is bound to the legacy holder, so it differs from the main holder's hash.
+    const migratedHash = Buffer.from((await program.account.boringState.fetch(legacyAccount)).configHash);
+    expect(migratedHash).to.deep.equal(computeConfigHash(legacyAccount, configParams));
+    const mainHash = Buffer.from((await program.account.boringState.fetch(boringAccount)).configHash);
+    expect(migratedHash).to.not.deep.equal(mainHash);
+
+    // Migrating again fails since the stored hash is no longer a legacy hash.
+    const ix1 = await program.methods
+      // @ts-ignore
+      .migrateConfigurationHash(configParams)
+      .accounts({
+        // @ts-ignore
+        boringAccount: legacyAccount,
+        signer: strategist.publicKey,
+      })
+      .signers([strategist])
+      .instruction();
+
+    let txResult1 = await createAndProcessTransaction(client, creator, ix1, [creator, strategist]);
+
+    // Expect the tx to fail.
+    expect(txResult1.result).to.exist;
+    const errorLog = txResult1.meta.logMessages.find(log =>
+      log.includes("Error Code: InvalidConfiguration")
+    )
+    expect(errorLog).to.exist;
+  });
+
+  it("Can migrate configuration hashes of earlier versions", async () => {
+    // Versions 2, 3 and 4 predate the remote decimals, the recipient format and the mint
+    // respectively.
+    for (const version of [2, 3, 4]) {
+      const previousAccount = await setHolderWithConfigHash(holder => computeConfigHash(holder, configParams, version));
+
+      const ix = await program.methods
+        // @ts-ignore
+        .migrateConfigurationHash(configParams)
+        .accounts({
+          // @ts-ignore
+          boringAccount: previousAccount,
+          signer: owner.publicKey,
+        })
+        .signers([owner])
+        .instruction();
+      let txResult = await createAndProcessTransaction(client, creator, ix, [creator, owner]);
+      expect(txResult.result).to.be.null;
+
+      const migratedHash = Buffer.from((await program.account.boringState.fetch(previousAccount)).configHash);
+      expect(migratedHash).to.deep.equal(computeConfigHash(previousAccount, configParams));
+    }
+  });
+
+  it("Cannot migrate a configuration hash without the owner or strategist", async () => {
+    const legacyHash = createHash("sha256").update(encodeConfig(configParams, 1)).digest();
+    const legacyAccount = await setHolderWithConfigHash(() => legacyHash);
+
+    const ix = await program.methods
+      // @ts-ignore
+      .migrateConfigurationHash(configParams)
+      .accounts({
+        // @ts-ignore
+        boringAccount: legacyAccount,
+        signer: creator.publicKey,
+      })
+      .signers([])
+      .instruction();
+    let txResult = await createAndProcessTransaction(client, creator, ix, [creator]);
+    expect(txResult.result).to.exist;
+    expect(txResult.meta.logMessages.find(log => log.includes("Error Code: Unauthorized"))).to.exist;
+  });
+
+  it("Cannot migrate a configuration hash of a non-EVM recipient as an EVM address", async () => {
+    // Before version 4 the recipient was a bare 32-byte word, so a full 32-byte recipient
+    // matches its version 3 hash only when passed as `Evm20`, which it is not.
+    const bytes32Config = {
+      ...configParams,
+      recipient: { evm20: [Array.from(Buffer.alloc(32, 9))] },
+    };
+    const previousAccount = await setHolderWithConfigHash(holder => computeConfigHash(holder, bytes32Config, 3));
+
+    const ix = await program.methods
+      // @ts-ignore
+      .migrateConfigurationHash(bytes32Config)
+      .accounts({
+        // @ts-ignore
+        boringAccount: previousAccount,
+        signer: owner.publicKey,
+      })
+      .signers([owner])
+      .instruction();
+    let txResult = await createAndProcessTransaction(client, creator, ix, [creator, owner]);
+    expect(txResult.result).to.exist;
+    expect(txResult.meta.logMessages.find(log => log.includes("Error Code: InvalidRecipient"))).to.exist;
+  });
+
+  it("Cannot migrate a version 4 configuration hash to a mint other than the mint authority", async () => {
+    // Before version 5 the mint was the mint authority, so a version 4 hash can't approve a
+    // separate mint.
+    const previousAccount = await setHolderWithConfigHash(holder => computeConfigHash(holder, configParams, 4));
+    const collateralConfig = { ...configParams, mint: Keypair.generate().publicKey };
+
+    const ix = await program.methods
+      // @ts-ignore
+      .migrateConfigurationHash(collateralConfig)
+      .accounts({
+        // @ts-ignore
+        boringAccount: previousAccount,
+        signer: owner.publicKey,
+      })
+      .signers([owner])
+      .instruction();
+    let txResult = await createAndProcessTransaction(client, creator, ix, [creator, owner]);
+    expect(txResult.result).to.exist;
+    expect(txResult.meta.logMessages.find(log => log.includes("Error Code: InvalidConfiguration"))).to.exist;
+  });
+
+  it("Can migrate a v1 state account", async () => {
+    // Load a holder account using the version 1 layout.
+    const v1Creator = anchor.web3.Keypair.generate();
+    const [v1Account, v1Bump] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("boring_state"),
+        v1Creator.publicKey.toBuffer()
+      ],
+      program.programId
+    );
+    const v1ConfigHash = computeConfigHash(v1Account, configParams);
+    const v1Data = buildV1BoringStateFixture(v1Creator.publicKey, owner.publicKey, strategist.publicKey, v1ConfigHash, v1Bump);
+    expect(v1Data.length).to.equal(137);
+    context.setAccount(v1Account, {
+      lamports: 1_000_000_000,
+      data: v1Data,
+      owner: program.programId,
+      executable: false,
+    });
+
+    // The v1 account cannot be used until it is migrated.
+    const ix0 = await program.methods
+      .updateStrategist(strategist.publicKey)
+      .accounts({
+        // @ts-ignore
+        boringAccount: v1Account,
+        signer: owner.publicKey,
+      })
+      .signers([owner])
+      .instruction();
+    let txResult0 = await createAndProcessTransaction(client, creator, ix0, [creator, owner]);
+    expect(txResult0.result).to.exist;
+    expect(txResult0.meta.logMessages.find(log => log.includes("Error Code: StateNotMigrated"))).to.exist;
+
+    // Only the owner can migrate.
+    const randomUser = anchor.web3.Keypair.generate();
+    context.setAccount(randomUser.publicKey, {
+      lamports: 1_000_000_000,
+      data: Buffer.alloc(0),
+      owner: anchor.web3.SystemProgram.programId,
+      executable: false,
+    });
+    const ix1 = await program.methods
+      .migrateState()
+      .accounts({
+        boringAccount: v1Account,
+        signer: randomUser.publicKey,
+      })
+      .signers([randomUser])
+      .instruction();
+    let txResult1 = await createAndProcessTransaction(client, creator, ix1, [creator, randomUser]);
+    expect(txResult1.result).to.exist;
+    expect(txResult1.meta.logMessages.find(log => log.includes("Error Code: Unauthorized"))).to.exist;
+
+    // Migrate as the owner.
+    const ix2 = await program.methods
+      .migrateState()
+      .accounts({
+        boringAccount: v1Account,
+        signer: owner.publicKey,
+      })
+      .signers([owner])
+      .instruction();
+    let txResult2 = await createAndProcessTransaction(client, creator, ix2, [creator, owner]);
+    expect(txResult2.result).to.be.null;
+
+    // All v1 fields are preserved and the version is set.
+    const migrated = await program.account.boringState.fetch(v1Account);
+    expect(migrated.creator.equals(v1Creator.publicKey)).to.be.true;
+    expect(migrated.owner.equals(owner.publicKey)).to.be.true;
+    expect(migrated.strategist.equals(strategist.publicKey)).to.be.true;
+    expect(Buffer.from(migrated.configHash)).to.deep.equal(v1ConfigHash);
+    expect(migrated.bump).to.equal(v1Bump);
+    expect(migrated.stateVersion).to.equal(10);
+    const migratedInfo = await client.getAccount(v1Account);
+    expect(migratedInfo.data.length).to.equal(8 + 424);
+
+    // Migrating again fails.
+    const ix3 = await program.methods
+      .migrateState()
+      .accounts({
+        boringAccount: v1Account,
+        signer: owner.publicKey,
+      })
+      .signers([owner])
+      .instruction();
+    let txResult3 = await createAndProcessTransaction(client, creator, ix3, [creator, owner]);
+    expect(txResult3.result).to.exist;
+    expect(txResult3.meta.logMessages.find(log => log.includes("Error Code: StateAlreadyMigrated"))).to.exist;
+  });
+
+  it("Can migrate a v7 state account", async () => {
+    // Load a holder account using the version 7 Borsh layout.
+    const v7Creator = anchor.web3.Keypair.generate();
+    const [v7Account, v7Bump] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("boring_state"),
+        v7Creator.publicKey.toBuffer()
+      ],
+      program.programId
+    );
+    const v7ConfigHash = computeConfigHash(v7Account, configParams);
+    const governorSender = Buffer.alloc(32, 7);
+    const v7Data = buildV7BoringStateFixture(
+      v7Creator.publicKey,
+      owner.publicKey,
+      strategist.publicKey,
+      v7ConfigHash,
+      v7Bump,
+      destinationDomain.toNumber(),
+      amountToTransfer,
+      1,
+      governorSender,
+      5
+    );
+    expect(v7Data.length).to.equal(394);
+    context.setAccount(v7Account, {
+      lamports: 1_000_000_000,
+      data: v7Data,
+      owner: program.programId,
+      executable: false,
+    });
+
+    // The v7 account cannot be loaded zero-copy until it is migrated.
+    const ix0 = await program.methods
+      .updateStrategist(strategist.publicKey)
+      .accounts({
+        // @ts-ignore
+        boringAccount: v7Account,
+        signer: owner.publicKey,
+      })
+      .signers([owner])
+      .instruction();
+    let txResult0 = await createAndProcessTransaction(client, creator, ix0, [creator, owner]);
+    expect(txResult0.result).to.exist;
+    expect(txResult0.meta.logMessages.find(log => log.includes("Error Code: StateNotMigrated"))).to.exist;
+
+    const ix1 = await program.methods
+      .migrateState()
+      .accounts({
+        boringAccount: v7Account,
+        signer: owner.publicKey,
+      })
+      .signers([owner])
+      .instruction();
+    let txResult1 = await createAndProcessTransaction(client, creator, ix1, [creator, owner]);
+    expect(txResult1.result).to.be.null;
+
+    // All v7 fields are preserved in the zero-copy layout.
+    const migrated = await program.account.boringState.fetch(v7Account);
+    expect(migrated.creator.equals(v7Creator.publicKey)).to.be.true;
+    expect(migrated.owner.equals(owner.publicKey)).to.be.true;
+    expect(Buffer.from(migrated.configHash)).to.deep.equal(v7ConfigHash);
+    expect(migrated.bump).to.equal(v7Bump);
+    expect(migrated.stateVersion).to.equal(10);
+    expect(migrated.totalBridged.toNumber()).to.equal(amountToTransfer);
+    expect(migrated.transferCount.toNumber()).to.equal(1);
+    // Domain totals did not record the bridge, so they are cleared.
+    expect(migrated.domainTotals.every(total => total.transferCount.toNumber() === 0)).to.be.true;
+    expect(migrated.messageNonce.toNumber()).to.equal(5);
+    expect(migrated.governor.enabled).to.equal(1);
+    expect(migrated.governor.origin).to.equal(1);
+    expect(Buffer.from(migrated.governor.sender)).to.deep.equal(governorSender);
+    const migratedInfo = await client.getAccount(v7Account);
+    expect(migratedInfo.data.length).to.equal(8 + 424);
+
+    // The migrated account can be used.
+    const ix2 = await program.methods
+      .updateStrategist(strategist.publicKey)
+      .accounts({
+        // @ts-ignore
+        boringAccount: v7Account,
+        signer: owner.publicKey,
+      })
+      .signers([owner])
+      .instruction();
+    let txResult2 = await createAndProcessTransaction(client, creator, ix2, [creator, owner]);
+    expect(txResult2.result).to.be.null;
+  });
+
+  it("Can migrate a v9 state account", async () => {
+    // Load a zero-copy holder account from before domain totals recorded the bridge kind.
+    const v9Creator = anchor.web3.Keypair.generate();
+    const [v9Account, v9Bump] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("boring_state"),
+        v9Creator.publicKey.toBuffer()
+      ],
+      program.programId
+    );
+    const v9Data = await encodeBoringState({
+      creator: v9Creator.publicKey,
+      configHash: Array.from(computeConfigHash(v9Account, configParams)),
+      bump: v9Bump,
+      stateVersion: 9,
+      totalBridged: new anchor.BN(amountToTransfer),
+      transferCount: new anchor.BN(1),
+      domainTotals: [
+        {
+          totalBridged: new anchor.BN(amountToTransfer),
+          transferCount: new anchor.BN(1),
+          domain: destinationDomain.toNumber(),
+          bridgeKind: 0,
+          padding: Array(3).fill(0),
+        },
+        ...Array(3).fill({
+          totalBridged: new anchor.BN(0),
+          transferCount: new anchor.BN(0),
+          domain: 0,
+          bridgeKind: 0,
+          padding: Array(3).fill(0),
+        }),
+      ],
+    });
+    context.setAccount(v9Account, {
+      lamports: 1_000_000_000,
+      data: v9Data,
+      owner: program.programId,
+      executable: false,
+    });
+
+    const ix = await program.methods
+      .migrateState()
+      .accounts({
+        boringAccount: v9Account,
+        signer: owner.publicKey,
+      })
+      .signers([owner])
+      .instruction();
+    let txResult = await createAndProcessTransaction(client, creator, ix, [creator, owner]);
+    expect(txResult.result).to.be.null;
+
+    // The overall statistics are kept, but the domain totals are cleared since they did not
+    // record which bridge numbered their domains.
+    const migrated = await program.account.boringState.fetch(v9Account);
+    expect(migrated.stateVersion).to.equal(10);
+    expect(migrated.totalBridged.toNumber()).to.equal(amountToTransfer);
+    expect(migrated.transferCount.toNumber()).to.equal(1);
+    expect(migrated.domainTotals.every(total => total.transferCount.toNumber() === 0)).to.be.true;
+  });
+
   it("Cannot re initialize", async () => {
     const ix = await program.methods
       .initialize(
@@ -532,6 +1117,50 @@ describe("boring-bridge-holder", () => {
     expect(txResult.meta.logMessages[txResult.meta.logMessages.length - 1]).to.include("failed");
   });
 
+  it("Cannot approve a warp route configuration without Token-2022", async () => {
+    const ix = await program.methods
+      // @ts-ignore
+      .updateConfiguration({ ...configParams, token2022Program: TOKEN_PROGRAM_ID })
+      .accounts({
+        // @ts-ignore
+        boringAccount: boringAccount,
+        signer: owner.publicKey,
+      })
+      .signers([owner])
+      .instruction();
+    let txResult = await createAndProcessTransaction(client, creator, ix, [creator, owner]);
+
+    // Expect the tx to fail.
+    expect(txResult.result).to.exist;
+    const errorLog = txResult.meta.logMessages.find(log =>
+      log.includes("Error Code: InvalidConfiguration")
+    )
+    expect(errorLog).to.exist;
+  });
+
+  it("Cannot approve an EVM recipient wider than 20 bytes", async () => {
+    const paddedRecipient = Array.from(evmRecipient);
+    paddedRecipient[0] = 1;
+    const ix = await program.methods
+      // @ts-ignore
+      .updateConfiguration({ ...configParams, recipient: { evm20: [paddedRecipient] } })
+      .accounts({
+        // @ts-ignore
+        boringAccount: boringAccount,
+        signer: owner.publicKey,
+      })
+      .signers([owner])
+      .instruction();
+    let txResult = await createAndProcessTransaction(client, creator, ix, [creator, owner]);
+
+    // Expect the tx to fail.
+    expect(txResult.result).to.exist;
+    const errorLog = txResult.meta.logMessages.find(log =>
+      log.includes("Error Code: InvalidRecipient")
+    )
+    expect(errorLog).to.exist;
+  });
+
   it("Only strategist can transfer remote", async () => {
     // Change the strategist.
     const newStrategist = anchor.web3.Keypair.generate();
@@ -550,41 +1179,44 @@ describe("boring-bridge-holder", () => {
     // Expect the tx to succeed.
     expect(txResult0.result).to.be.null;
 
-    // Generate message keypair and pdas.
-    const { uniqueMessage, messageStoragePda, gasPaymentPda } = generateMessagePDAs(configParams.mailboxProgram, configParams.igpProgram);
+    // Derive message pdas.
+    const { uniqueMessage, messageStoragePda, gasPaymentPda } = await generateMessagePDAs(configParams.mailboxProgram, configParams.igpProgram);
 
     const amount = new anchor.BN(1000);
 
     // Should fail when called by old strategist
     const ix1 = await program.methods
       // @ts-ignore
-      .transferRemote(destinationDomain, evmRecipient, decimals, amount)
+      .transferRemote(destinationDomain, recipient, remoteDecimals, amount, maxGasPaymentLamports)
       .accounts({
         // @ts-ignore
-        boringAccount: boringAccount,
-        signer: strategist.publicKey,
-        targetProgram: configParams.targetProgram,
-        systemProgram: anchor.web3.SystemProgram.programId,
-        noop: configParams.noop,
-        tokenPda: configParams.tokenPda,
-        mailboxProgram: configParams.mailboxProgram,
-        mailboxOutbox: configParams.mailboxOutbox,
-        messageDispatchAuthority: configParams.messageDispatchAuthority,
-        uniqueMessage: uniqueMessage.publicKey,
-        messageStoragePda: messageStoragePda,
-        igpProgram: configParams.igpProgram,
-        igpProgramData: configParams.igpProgramData,
-        gasPaymentPda: gasPaymentPda,
-        igpAccount: configParams.igpAccount,
-        tokenSender: configParams.tokenSender,
-        token2022: configParams.token2022Program,
-        mintAuth: configParams.mintAuth,
-        boringAccountAta: boringAccountAta,
-        strategistAta: strategistAta,
+        transfer: {
+          boringAccount: boringAccount,
+          signer: strategist.publicKey,
+          targetProgram: configParams.targetProgram,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          noop: configParams.noop,
+          tokenPda: configParams.tokenPda,
+          mailboxProgram: configParams.mailboxProgram,
+          mailboxOutbox: configParams.mailboxOutbox,
+          messageDispatchAuthority: configParams.messageDispatchAuthority,
+          uniqueMessage: uniqueMessage,
+          messageStoragePda: messageStoragePda,
+          igpProgram: configParams.igpProgram,
+          igpProgramData: configParams.igpProgramData,
+          gasPaymentPda: gasPaymentPda,
+          igpAccount: configParams.igpAccount,
+          tokenSender: configParams.tokenSender,
+          token2022: configParams.token2022Program,
+          mintAuth: configParams.mintAuth,
+          mint: configParams.mint,
+          boringAccountAta: boringAccountAta,
+          strategistAta: strategistAta,
+        },
       })
-      .signers([strategist, uniqueMessage])
+      .signers([strategist])
       .instruction();
-    let txResult1 = await createAndProcessTransaction(client, creator, ix1, [creator, strategist, uniqueMessage]);
+    let txResult1 = await createAndProcessTransaction(client, creator, ix1, [creator, strategist]);
 
     // Expect the tx to fail.
     expect(txResult1.result).to.exist;
@@ -614,8 +1246,8 @@ describe("boring-bridge-holder", () => {
   });
 
   it("Strategist cannot transfer remote with invalid config", async () => {
-    // Generate message keypair and pdas.
-    const { uniqueMessage, messageStoragePda, gasPaymentPda } = generateMessagePDAs(configParams.mailboxProgram, configParams.igpProgram);
+    // Derive message pdas.
+    const { uniqueMessage, messageStoragePda, gasPaymentPda } = await generateMessagePDAs(configParams.mailboxProgram, configParams.igpProgram);
 
     const amount = new anchor.BN(1000);
 
@@ -624,33 +1256,36 @@ describe("boring-bridge-holder", () => {
 
     const ix = await program.methods
       // @ts-ignore
-      .transferRemote(destinationDomain, evmRecipient, decimals, amount)
+      .transferRemote(destinationDomain, recipient, remoteDecimals, amount, maxGasPaymentLamports)
       .accounts({
         // @ts-ignore
-        boringAccount: boringAccount,
-        signer: strategist.publicKey,
-        targetProgram: invalidTargetProgram, // Using different target program
-        systemProgram: anchor.web3.SystemProgram.programId,
-        noop: configParams.noop,
-        tokenPda: configParams.tokenPda,
-        mailboxProgram: configParams.mailboxProgram,
-        mailboxOutbox: configParams.mailboxOutbox,
-        messageDispatchAuthority: configParams.messageDispatchAuthority,
-        uniqueMessage: uniqueMessage.publicKey,
-        messageStoragePda: messageStoragePda,
-        igpProgram: configParams.igpProgram,
-        igpProgramData: configParams.igpProgramData,
-        gasPaymentPda: gasPaymentPda,
-        igpAccount: configParams.igpAccount,
-        tokenSender: configParams.tokenSender,
-        token2022: configParams.token2022Program,
-        mintAuth: configParams.mintAuth,
-        boringAccountAta: boringAccountAta,
-        strategistAta: strategistAta,
+        transfer: {
+          boringAccount: boringAccount,
+          signer: strategist.publicKey,
+          targetProgram: invalidTargetProgram, // Using different target program
+          systemProgram: anchor.web3.SystemProgram.programId,
+          noop: configParams.noop,
+          tokenPda: configParams.tokenPda,
+          mailboxProgram: configParams.mailboxProgram,
+          mailboxOutbox: configParams.mailboxOutbox,
+          messageDispatchAuthority: configParams.messageDispatchAuthority,
+          uniqueMessage: uniqueMessage,
+          messageStoragePda: messageStoragePda,
+          igpProgram: configParams.igpProgram,
+          igpProgramData: configParams.igpProgramData,
+          gasPaymentPda: gasPaymentPda,
+          igpAccount: configParams.igpAccount,
+          tokenSender: configParams.tokenSender,
+          token2022: configParams.token2022Program,
+          mintAuth: configParams.mintAuth,
+          mint: configParams.mint,
+          boringAccountAta: boringAccountAta,
+          strategistAta: strategistAta,
+        },
       })
-      .signers([strategist, uniqueMessage])
+      .signers([strategist])
       .instruction();
-    let txResult = await createAndProcessTransaction(client, creator, ix, [creator, strategist, uniqueMessage]);
+    let txResult = await createAndProcessTransaction(client, creator, ix, [creator, strategist]);
 
     // Expect the tx to fail.
     expect(txResult.result).to.exist;
@@ -664,14 +1299,14 @@ describe("boring-bridge-holder", () => {
   });
 
   it("Fails with malformed message storage PDA", async () => {
-    // Generate message keypair and pdas.
-    let { uniqueMessage, messageStoragePda, gasPaymentPda } = generateMessagePDAs(configParams.mailboxProgram, configParams.igpProgram);
+    // Derive message pdas.
+    let { uniqueMessage, messageStoragePda, gasPaymentPda } = await generateMessagePDAs(configParams.mailboxProgram, configParams.igpProgram);
     
     // Overwrite message storage PDA with one using wrong seeds.
     [messageStoragePda] = anchor.web3.PublicKey.findProgramAddressSync(
       [
         Buffer.from("wrong_seed"),
-        uniqueMessage.publicKey.toBuffer()
+        uniqueMessage.toBuffer()
       ],
       configParams.mailboxProgram
     );
@@ -680,33 +1315,36 @@ describe("boring-bridge-holder", () => {
 
     const ix = await program.methods
       // @ts-ignore
-      .transferRemote(destinationDomain, evmRecipient, decimals, amount)
+      .transferRemote(destinationDomain, recipient, remoteDecimals, amount, maxGasPaymentLamports)
       .accounts({
         // @ts-ignore
-        boringAccount: boringAccount,
-        signer: strategist.publicKey,
-        targetProgram: configParams.targetProgram,
-        systemProgram: anchor.web3.SystemProgram.programId,
-        noop: configParams.noop,
-        tokenPda: configParams.tokenPda,
-        mailboxProgram: configParams.mailboxProgram,
-        mailboxOutbox: configParams.mailboxOutbox,
-        messageDispatchAuthority: configParams.messageDispatchAuthority,
-        uniqueMessage: uniqueMessage.publicKey,
-        messageStoragePda: messageStoragePda, // Using incorrect PDA
-        igpProgram: configParams.igpProgram,
-        igpProgramData: configParams.igpProgramData,
-        gasPaymentPda: gasPaymentPda,
-        igpAccount: configParams.igpAccount,
-        tokenSender: configParams.tokenSender,
-        token2022: configParams.token2022Program,
-        mintAuth: configParams.mintAuth,
-        boringAccountAta: boringAccountAta,
-        strategistAta: strategistAta,
+        transfer: {
+          boringAccount: boringAccount,
+          signer: strategist.publicKey,
+          targetProgram: configParams.targetProgram,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          noop: configParams.noop,
+          tokenPda: configParams.tokenPda,
+          mailboxProgram: configParams.mailboxProgram,
+          mailboxOutbox: configParams.mailboxOutbox,
+          messageDispatchAuthority: configParams.messageDispatchAuthority,
+          uniqueMessage: uniqueMessage,
+          messageStoragePda: messageStoragePda, // Using incorrect PDA
+          igpProgram: configParams.igpProgram,
+          igpProgramData: configParams.igpProgramData,
+          gasPaymentPda: gasPaymentPda,
+          igpAccount: configParams.igpAccount,
+          tokenSender: configParams.tokenSender,
+          token2022: configParams.token2022Program,
+          mintAuth: configParams.mintAuth,
+          mint: configParams.mint,
+          boringAccountAta: boringAccountAta,
+          strategistAta: strategistAta,
+        },
       })
-      .signers([strategist, uniqueMessage])
+      .signers([strategist])
       .instruction();
-    let txResult = await createAndProcessTransaction(client, creator, ix, [creator, strategist, uniqueMessage]);
+    let txResult = await createAndProcessTransaction(client, creator, ix, [creator, strategist]);
 
     // Expect the tx to fail.
     expect(txResult.result).to.exist;
@@ -721,14 +1359,14 @@ describe("boring-bridge-holder", () => {
   });
 
   it("Fails with malformed gas payment PDA", async () => {
-    // Generate message keypair and pdas.
-    let { uniqueMessage, messageStoragePda, gasPaymentPda } = generateMessagePDAs(configParams.mailboxProgram, configParams.igpProgram);
+    // Derive message pdas.
+    let { uniqueMessage, messageStoragePda, gasPaymentPda } = await generateMessagePDAs(configParams.mailboxProgram, configParams.igpProgram);
 
     // Overwrite gas payment PDA with one using wrong seeds.
     [gasPaymentPda] = anchor.web3.PublicKey.findProgramAddressSync(
       [
         Buffer.from("wrong_seed"),
-        uniqueMessage.publicKey.toBuffer()
+        uniqueMessage.toBuffer()
       ],
       configParams.igpProgram
     );
@@ -737,9 +1375,10 @@ describe("boring-bridge-holder", () => {
 
     const ix = await program.methods
       // @ts-ignore
-      .transferRemote(destinationDomain, evmRecipient, decimals, amount)
+      .transferRemote(destinationDomain, recipient, remoteDecimals, amount, maxGasPaymentLamports)
       .accounts({
-          // @ts-ignore
+        // @ts-ignore
+        transfer: {
           boringAccount: boringAccount,
           signer: strategist.publicKey,
           targetProgram: configParams.targetProgram,
@@ -749,7 +1388,7 @@ describe("boring-bridge-holder", () => {
           mailboxProgram: configParams.mailboxProgram,
           mailboxOutbox: configParams.mailboxOutbox,
           messageDispatchAuthority: configParams.messageDispatchAuthority,
-          uniqueMessage: uniqueMessage.publicKey,
+          uniqueMessage: uniqueMessage,
           messageStoragePda: messageStoragePda,
           igpProgram: configParams.igpProgram,
           igpProgramData: configParams.igpProgramData,
@@ -758,12 +1397,14 @@ describe("boring-bridge-holder", () => {
           tokenSender: configParams.tokenSender,
           token2022: configParams.token2022Program,
           mintAuth: configParams.mintAuth,
+          mint: configParams.mint,
           boringAccountAta: boringAccountAta,
           strategistAta: strategistAta,
+        },
         })
-        .signers([strategist, uniqueMessage])
+        .signers([strategist])
       .instruction();
-    let txResult = await createAndProcessTransaction(client, creator, ix, [creator, strategist, uniqueMessage]);
+    let txResult = await createAndProcessTransaction(client, creator, ix, [creator, strategist]);
 
     // Expect the tx to fail.
     expect(txResult.result).to.exist;
@@ -778,40 +1419,43 @@ describe("boring-bridge-holder", () => {
   });
 
   it("Fails with malformed boring account ATA", async () => {
-    // Generate message keypair and pdas.
-    const { uniqueMessage, messageStoragePda, gasPaymentPda } = generateMessagePDAs(configParams.mailboxProgram, configParams.igpProgram);
+    // Derive message pdas.
+    const { uniqueMessage, messageStoragePda, gasPaymentPda } = await generateMessagePDAs(configParams.mailboxProgram, configParams.igpProgram);
 
     const amount = new anchor.BN(1000);
 
     const ix = await program.methods
       // @ts-ignore
-      .transferRemote(destinationDomain, evmRecipient, decimals, amount)
+      .transferRemote(destinationDomain, recipient, remoteDecimals, amount, maxGasPaymentLamports)
       .accounts({
         // @ts-ignore
-        boringAccount: boringAccount,
-        signer: strategist.publicKey,
-        targetProgram: configParams.targetProgram,
-        systemProgram: anchor.web3.SystemProgram.programId,
-        noop: configParams.noop,
-        tokenPda: configParams.tokenPda,
-        mailboxProgram: configParams.mailboxProgram,
-        mailboxOutbox: configParams.mailboxOutbox,
-        messageDispatchAuthority: configParams.messageDispatchAuthority,
-        uniqueMessage: uniqueMessage.publicKey,
-        messageStoragePda: messageStoragePda,
-        igpProgram: configParams.igpProgram,
-        igpProgramData: configParams.igpProgramData,
-        gasPaymentPda: gasPaymentPda,
-        igpAccount: configParams.igpAccount,
-        tokenSender: configParams.tokenSender,
-        token2022: configParams.token2022Program,
-        mintAuth: configParams.mintAuth,
-        boringAccountAta: strategistAta, // Using incorrect ATA
-        strategistAta: strategistAta,
+        transfer: {
+          boringAccount: boringAccount,
+          signer: strategist.publicKey,
+          targetProgram: configParams.targetProgram,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          noop: configParams.noop,
+          tokenPda: configParams.tokenPda,
+          mailboxProgram: configParams.mailboxProgram,
+          mailboxOutbox: configParams.mailboxOutbox,
+          messageDispatchAuthority: configParams.messageDispatchAuthority,
+          uniqueMessage: uniqueMessage,
+          messageStoragePda: messageStoragePda,
+          igpProgram: configParams.igpProgram,
+          igpProgramData: configParams.igpProgramData,
+          gasPaymentPda: gasPaymentPda,
+          igpAccount: configParams.igpAccount,
+          tokenSender: configParams.tokenSender,
+          token2022: configParams.token2022Program,
+          mintAuth: configParams.mintAuth,
+          mint: configParams.mint,
+          boringAccountAta: strategistAta, // Using incorrect ATA
+          strategistAta: strategistAta,
+        },
       })
-      .signers([strategist, uniqueMessage])
+      .signers([strategist])
       .instruction();
-    let txResult = await createAndProcessTransaction(client, creator, ix, [creator, strategist, uniqueMessage]);
+    let txResult = await createAndProcessTransaction(client, creator, ix, [creator, strategist]);
 
     // Expect the tx to fail.
     expect(txResult.result).to.exist;
@@ -826,40 +1470,43 @@ describe("boring-bridge-holder", () => {
   });
 
   it("Fails with malformed strategist ATA", async () => {
-    // Generate message keypair and pdas.
-    const { uniqueMessage, messageStoragePda, gasPaymentPda } = generateMessagePDAs(configParams.mailboxProgram, configParams.igpProgram);
+    // Derive message pdas.
+    const { uniqueMessage, messageStoragePda, gasPaymentPda } = await generateMessagePDAs(configParams.mailboxProgram, configParams.igpProgram);
 
     const amount = new anchor.BN(1000);
 
     const ix = await program.methods
       // @ts-ignore
-      .transferRemote(destinationDomain, evmRecipient, decimals, amount)
+      .transferRemote(destinationDomain, recipient, remoteDecimals, amount, maxGasPaymentLamports)
       .accounts({
         // @ts-ignore
-        boringAccount: boringAccount,
-        signer: strategist.publicKey,
-        targetProgram: configParams.targetProgram,
-        systemProgram: anchor.web3.SystemProgram.programId,
-        noop: configParams.noop,
-        tokenPda: configParams.tokenPda,
-        mailboxProgram: configParams.mailboxProgram,
-        mailboxOutbox: configParams.mailboxOutbox,
-        messageDispatchAuthority: configParams.messageDispatchAuthority,
-        uniqueMessage: uniqueMessage.publicKey,
-        messageStoragePda: messageStoragePda,
-        igpProgram: configParams.igpProgram,
-        igpProgramData: configParams.igpProgramData,
-        gasPaymentPda: gasPaymentPda,
-        igpAccount: configParams.igpAccount,
-        tokenSender: configParams.tokenSender,
-        token2022: configParams.token2022Program,
-        mintAuth: configParams.mintAuth,
-        boringAccountAta: boringAccountAta,
-        strategistAta: boringAccountAta, // Using incorrect ATA
+        transfer: {
+          boringAccount: boringAccount,
+          signer: strategist.publicKey,
+          targetProgram: configParams.targetProgram,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          noop: configParams.noop,
+          tokenPda: configParams.tokenPda,
+          mailboxProgram: configParams.mailboxProgram,
+          mailboxOutbox: configParams.mailboxOutbox,
+          messageDispatchAuthority: configParams.messageDispatchAuthority,
+          uniqueMessage: uniqueMessage,
+          messageStoragePda: messageStoragePda,
+          igpProgram: configParams.igpProgram,
+          igpProgramData: configParams.igpProgramData,
+          gasPaymentPda: gasPaymentPda,
+          igpAccount: configParams.igpAccount,
+          tokenSender: configParams.tokenSender,
+          token2022: configParams.token2022Program,
+          mintAuth: configParams.mintAuth,
+          mint: configParams.mint,
+          boringAccountAta: boringAccountAta,
+          strategistAta: boringAccountAta, // Using incorrect ATA
+        },
       })
-      .signers([strategist, uniqueMessage])
+      .signers([strategist])
       .instruction();
-    let txResult = await createAndProcessTransaction(client, creator, ix, [creator, strategist, uniqueMessage]);
+    let txResult = await createAndProcessTransaction(client, creator, ix, [creator, strategist]);
 
     // Expect the tx to fail.
     expect(txResult.result).to.exist;
@@ -873,62 +1520,1922 @@ describe("boring-bridge-holder", () => {
     expect(txResult.meta.logMessages[txResult.meta.logMessages.length - 1]).to.include("failed");
   });
 
-  it("Can transfer remote tokens", async () => {
-    // Generate message keypair and pdas.
-    const { uniqueMessage, messageStoragePda, gasPaymentPda } = generateMessagePDAs(configParams.mailboxProgram, configParams.igpProgram);
+  it("Fails with unique message not derived from the current nonce", async () => {
+    // Derive the unique message from a nonce other than the current one.
+    const uniqueMessage = uniqueMessagePDA(boringAccount, (await messageNonce()).addn(1));
+    const [messageStoragePda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("hyperlane"),
+        Buffer.from("-"),
+        Buffer.from("dispatched_message"),
+        Buffer.from("-"),
+        uniqueMessage.toBuffer()
+      ],
+      configParams.mailboxProgram
+    );
+    const [gasPaymentPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("hyperlane_igp"),
+        Buffer.from("-"),
+        Buffer.from("gas_payment"),
+        Buffer.from("-"),
+        uniqueMessage.toBuffer()
+      ],
+      configParams.igpProgram
+    );
 
-    // Set transfer amount.
-    const amount = new anchor.BN(amountToTransfer);
+    const amount = new anchor.BN(1000);
 
-    // Execute the transfer
     const ix = await program.methods
       // @ts-ignore
-      .transferRemote(destinationDomain, evmRecipient, decimals, amount)
+      .transferRemote(destinationDomain, recipient, remoteDecimals, amount, maxGasPaymentLamports)
       .accounts({
         // @ts-ignore
-        boringAccount: boringAccount,
-        signer: strategist.publicKey,
-        targetProgram: configParams.targetProgram,
-        systemProgram: anchor.web3.SystemProgram.programId,
-        noop: configParams.noop,
-        tokenPda: configParams.tokenPda,
-        mailboxProgram: configParams.mailboxProgram,
-        mailboxOutbox: configParams.mailboxOutbox,
-        messageDispatchAuthority: configParams.messageDispatchAuthority,
-        uniqueMessage: uniqueMessage.publicKey,
-        messageStoragePda: messageStoragePda,
-        igpProgram: configParams.igpProgram,
-        igpProgramData: configParams.igpProgramData,
-        gasPaymentPda: gasPaymentPda,
-        igpAccount: configParams.igpAccount,
-        tokenSender: configParams.tokenSender,
-        token2022: configParams.token2022Program,
-        mintAuth: configParams.mintAuth,
-        boringAccountAta: boringAccountAta,
-        strategistAta: strategistAta,
+        transfer: {
+          boringAccount: boringAccount,
+          signer: strategist.publicKey,
+          targetProgram: configParams.targetProgram,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          noop: configParams.noop,
+          tokenPda: configParams.tokenPda,
+          mailboxProgram: configParams.mailboxProgram,
+          mailboxOutbox: configParams.mailboxOutbox,
+          messageDispatchAuthority: configParams.messageDispatchAuthority,
+          uniqueMessage: uniqueMessage, // Using a future nonce
+          messageStoragePda: messageStoragePda,
+          igpProgram: configParams.igpProgram,
+          igpProgramData: configParams.igpProgramData,
+          gasPaymentPda: gasPaymentPda,
+          igpAccount: configParams.igpAccount,
+          tokenSender: configParams.tokenSender,
+          token2022: configParams.token2022Program,
+          mintAuth: configParams.mintAuth,
+          mint: configParams.mint,
+          boringAccountAta: boringAccountAta,
+          strategistAta: strategistAta,
+        },
       })
-      .signers([strategist, uniqueMessage])
+      .signers([strategist])
       .instruction();
-    let txResult = await createAndProcessTransaction(client, creator, ix, [creator, strategist, uniqueMessage]);
+    let txResult = await createAndProcessTransaction(client, creator, ix, [creator, strategist]);
 
-    // Expect the tx to succeed.
-    expect(txResult.result).to.be.null;
-
-    // Format the EVM recipient for log matching (first 4 and last 4 chars)
-    const formattedRecipient =  "0x0000…" + evmAddressRaw.slice(-4);
+    // Expect the tx to fail.
+    expect(txResult.result).to.exist;
+    const errorLog = txResult.meta.logMessages.find(log =>
+      log.includes("unique_message") &&
+      log.includes("ConstraintSeeds")
+    )
+    expect(errorLog).to.exist;
 
-    // Check for the expected log message
+    // Last log should indicate failure.
+    expect(txResult.meta.logMessages[txResult.meta.logMessages.length - 1]).to.include("failed");
+  });
+
+  it("Fails when the gas payment exceeds the maximum", async () => {
+    // Derive message pdas.
+    const { uniqueMessage, messageStoragePda, gasPaymentPda } = await generateMessagePDAs(configParams.mailboxProgram, configParams.igpProgram);
+
+    const amount = new anchor.BN(1000);
+
+    const ix = await program.methods
+      // @ts-ignore
+      .transferRemote(destinationDomain, recipient, remoteDecimals, amount, new anchor.BN(1)) // Allowing a single lamport
+      .accounts({
+        // @ts-ignore
+        transfer: {
+          boringAccount: boringAccount,
+          signer: strategist.publicKey,
+          targetProgram: configParams.targetProgram,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          noop: configParams.noop,
+          tokenPda: configParams.tokenPda,
+          mailboxProgram: configParams.mailboxProgram,
+          mailboxOutbox: configParams.mailboxOutbox,
+          messageDispatchAuthority: configParams.messageDispatchAuthority,
+          uniqueMessage: uniqueMessage,
+          messageStoragePda: messageStoragePda,
+          igpProgram: configParams.igpProgram,
+          igpProgramData: configParams.igpProgramData,
+          gasPaymentPda: gasPaymentPda,
+          igpAccount: configParams.igpAccount,
+          tokenSender: configParams.tokenSender,
+          token2022: configParams.token2022Program,
+          mintAuth: configParams.mintAuth,
+          mint: configParams.mint,
+          boringAccountAta: boringAccountAta,
+          strategistAta: strategistAta,
+        },
+      })
+      .signers([strategist])
+      .instruction();
+    let txResult = await createAndProcessTransaction(client, creator, ix, [creator, strategist]);
+
+    // Expect the tx to fail.
+    expect(txResult.result).to.exist;
+    const errorLog = txResult.meta.logMessages.find(log =>
+      log.includes("Error Code: GasPaymentTooHigh")
+    )
+    expect(errorLog).to.exist;
+
+    // Last log should indicate failure.
+    expect(txResult.meta.logMessages[txResult.meta.logMessages.length - 1]).to.include("failed");
+  });
+
+  it("Can transfer remote tokens", async () => {
+    // Derive message pdas.
+    const { uniqueMessage, messageStoragePda, gasPaymentPda } = await generateMessagePDAs(configParams.mailboxProgram, configParams.igpProgram);
+
+    // Set transfer amount.
+    const amount = new anchor.BN(amountToTransfer);
+
+    // Execute the transfer
+    const ix = await program.methods
+      // @ts-ignore
+      .transferRemote(destinationDomain, recipient, remoteDecimals, amount, maxGasPaymentLamports)
+      .accounts({
+        // @ts-ignore
+        transfer: {
+          boringAccount: boringAccount,
+          signer: strategist.publicKey,
+          targetProgram: configParams.targetProgram,
+          systemProgram: anchor.web3.SystemProgram.programId,
+          noop: configParams.noop,
+          tokenPda: configParams.tokenPda,
+          mailboxProgram: configParams.mailboxProgram,
+          mailboxOutbox: configParams.mailboxOutbox,
+          messageDispatchAuthority: configParams.messageDispatchAuthority,
+          uniqueMessage: uniqueMessage,
+          messageStoragePda: messageStoragePda,
+          igpProgram: configParams.igpProgram,
+          igpProgramData: configParams.igpProgramData,
+          gasPaymentPda: gasPaymentPda,
+          igpAccount: configParams.igpAccount,
+          tokenSender: configParams.tokenSender,
+          token2022: configParams.token2022Program,
+          mintAuth: configParams.mintAuth,
+          mint: configParams.mint,
+          boringAccountAta: boringAccountAta,
+          strategistAta: strategistAta,
+        },
+      })
+      .signers([strategist])
+      .instruction();
+    let txResult = await createAndProcessTransaction(client, creator, ix, [creator, strategist]);
+
+    // Expect the tx to succeed.
+    expect(txResult.result).to.be.null;
+
+    // Check the transfer stays within its compute budget, without the diagnostic logs of
+    // `verbose-logs` builds.
+    const computeUnitsConsumed = Number(txResult.meta.computeUnitsConsumed);
+    console.log(`transfer_remote consumed ${computeUnitsConsumed} compute units (estimated ${transferRemoteEstimatedComputeUnits})`);
+    expect(computeUnitsConsumed).to.be.at.most(transferRemoteComputeBudget);
+    expect(txResult.meta.logMessages.find(log => log.includes("Verifying signer"))).to.not.exist;
+
+    // Format the EVM recipient for log matching (first 4 and last 4 chars)
+    const formattedRecipient =  "0x0000…" + evmAddressRaw.slice(-4);
+
+    // Check for the expected log message
     const expectedLog = `Warp route transfer completed to destination: ${destinationDomain}, recipient: ${formattedRecipient}, remote_amount: ${amountToTransfer}`;
     const foundLog = txResult.meta.logMessages.find(log => log.includes(expectedLog));
     expect(foundLog).to.exist;
+
+    // Check the bridging statistics were updated
+    const programBoringAccount = await program.account.boringState.fetch(boringAccount);
+    expect(programBoringAccount.totalBridged.toNumber()).to.equal(amountToTransfer);
+    expect(programBoringAccount.transferCount.toNumber()).to.equal(1);
+    expect(programBoringAccount.lastTransferSlot.toNumber()).to.be.greaterThan(0);
+    expect(programBoringAccount.domainTotals[0].bridgeKind).to.equal(0);
+    expect(programBoringAccount.domainTotals[0].domain).to.equal(destinationDomain.toNumber());
+    expect(programBoringAccount.domainTotals[0].totalBridged.toNumber()).to.equal(amountToTransfer);
+
+    // Check the message nonce advanced past the unique message used
+    expect(programBoringAccount.messageNonce.toNumber()).to.equal(1);
+
+    // Check the statistics are included in the emitted event
+    const eventParser = new anchor.EventParser(program.programId, program.coder);
+    const events = [...eventParser.parseLogs(txResult.meta.logMessages)];
+    const transferEvent = events.find(event => event.name === "transferRemote");
+    expect(transferEvent).to.exist;
+    expect(transferEvent.data.bridgeKind).to.deep.equal({ hyperlaneWarpRoute: {} });
+    expect(transferEvent.data.destinationDomain).to.equal(destinationDomain.toNumber());
+    expect(transferEvent.data.amount.toNumber()).to.equal(amountToTransfer);
+    // The mint has no transfer fee extension
+    expect(transferEvent.data.transferFee.toNumber()).to.equal(0);
+    expect(transferEvent.data.totalBridged.toNumber()).to.equal(amountToTransfer);
+    expect(transferEvent.data.transferCount.toNumber()).to.equal(1);
+    expect(transferEvent.data.domainTotalBridged.toNumber()).to.equal(amountToTransfer);
+    expect(transferEvent.data.messageNonce.toNumber()).to.equal(0);
   });
 
-  it("Should return the correct version", async () => {
-    const ix = await program.methods.version().accounts({}).signers([]).instruction();
+  it("Can store and read configuration", async () => {
+    const [configAccount] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("boring_config"),
+        boringAccount.toBuffer()
+      ],
+      program.programId
+    );
+    const existingConfigHash = (await program.account.boringState.fetch(boringAccount)).configHash;
 
-    let txResult = await createAndProcessTransaction(client, creator, ix, []);
+    const ix0 = await program.methods
+      // @ts-ignore
+      .storeConfiguration(configParams)
+      .accounts({
+        // @ts-ignore
+        boringAccount: boringAccount,
+        configAccount: configAccount,
+        signer: owner.publicKey,
+      })
+      .signers([owner])
+      .instruction();
+    let txResult0 = await createAndProcessTransaction(client, creator, ix0, [creator, owner]);
+
+    // Expect the tx to succeed.
+    expect(txResult0.result).to.be.null;
+
+    // Storing the same configuration leaves the hash unchanged.
+    const storedConfigHash = (await program.account.boringState.fetch(boringAccount)).configHash;
+    expect(storedConfigHash).to.deep.equal(existingConfigHash);
+
+    // Read the configuration back through return data.
+    const ix1 = await program.methods
+      .getConfiguration()
+      .accounts({
+        // @ts-ignore
+        boringAccount: boringAccount,
+        configAccount: configAccount,
+      })
+      .instruction();
+    let txResult1 = await createAndProcessTransaction(client, creator, ix1, [creator]);
+    expect(txResult1.result).to.be.null;
+
+    const returned = program.coder.types.decode("configurationData", Buffer.from(txResult1.meta.returnData.data));
+    expect(returned.targetProgram.equals(configParams.targetProgram)).to.be.true;
+    expect(returned.mintAuth.equals(configParams.mintAuth)).to.be.true;
+    expect(returned.mint.equals(configParams.mint)).to.be.true;
+    expect(returned.destinationDomain).to.equal(destinationDomain.toNumber());
+    expect(Buffer.from(returned.recipient.evm20[0])).to.deep.equal(evmRecipient);
+  });
+
+  it("Only owner can store configuration", async () => {
+    const randomUser = anchor.web3.Keypair.generate();
+    context.setAccount(randomUser.publicKey, {
+      lamports: 1_000_000_000,
+      data: Buffer.alloc(0),
+      owner: anchor.web3.SystemProgram.programId,
+      executable: false,
+    });
+    const [configAccount] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("boring_config"),
+        boringAccount.toBuffer()
+      ],
+      program.programId
+    );
+
+    const ix = await program.methods
+      // @ts-ignore
+      .storeConfiguration(configParams)
+      .accounts({
+        // @ts-ignore
+        boringAccount: boringAccount,
+        configAccount: configAccount,
+        signer: randomUser.publicKey,
+      })
+      .signers([randomUser])
+      .instruction();
+    let txResult = await createAndProcessTransaction(client, creator, ix, [creator, randomUser]);
+
+    // Expect the tx to fail.
+    expect(txResult.result).to.exist;
+    const errorLog = txResult.meta.logMessages.find(log =>
+      log.includes("Error Code: Unauthorized") &&
+      log.includes("Error Message: Unauthorized")
+    )
+    expect(errorLog).to.exist;
+  });
+
+  it("Stored configuration transfer rejects accounts not in config", async () => {
+    const { uniqueMessage, messageStoragePda, gasPaymentPda } = await generateMessagePDAs(configParams.mailboxProgram, configParams.igpProgram);
+    const [configAccount] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("boring_config"),
+        boringAccount.toBuffer()
+      ],
+      program.programId
+    );
+
+    const ix = await program.methods
+      .transferRemoteStored(new anchor.BN(1000), maxGasPaymentLamports)
+      .accounts({
+        // @ts-ignore
+        boringAccount: boringAccount,
+        configAccount: configAccount,
+        signer: strategist.publicKey,
+        targetProgram: anchor.web3.Keypair.generate().publicKey, // Using different target program
+        systemProgram: anchor.web3.SystemProgram.programId,
+        noop: configParams.noop,
+        tokenPda: configParams.tokenPda,
+        mailboxProgram: configParams.mailboxProgram,
+        mailboxOutbox: configParams.mailboxOutbox,
+        messageDispatchAuthority: configParams.messageDispatchAuthority,
+        uniqueMessage: uniqueMessage,
+        messageStoragePda: messageStoragePda,
+        igpProgram: configParams.igpProgram,
+        igpProgramData: configParams.igpProgramData,
+        gasPaymentPda: gasPaymentPda,
+        igpAccount: configParams.igpAccount,
+        tokenSender: configParams.tokenSender,
+        token2022: configParams.token2022Program,
+        mintAuth: configParams.mintAuth,
+        mint: configParams.mint,
+        boringAccountAta: boringAccountAta,
+        strategistAta: strategistAta,
+      })
+      .signers([strategist])
+      .instruction();
+    let txResult = await createAndProcessTransaction(client, creator, ix, [creator, strategist]);
+
+    // Expect the tx to fail.
+    expect(txResult.result).to.exist;
+    const errorLog = txResult.meta.logMessages.find(log =>
+      log.includes("target_program") &&
+      log.includes("ConstraintAddress")
+    )
+    expect(errorLog).to.exist;
+  });
+
+  it("Stored transfer fails when gas payment exceeds the maximum", async () => {
+    const { uniqueMessage, messageStoragePda, gasPaymentPda } = await generateMessagePDAs(configParams.mailboxProgram, configParams.igpProgram);
+    const [configAccount] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("boring_config"),
+        boringAccount.toBuffer()
+      ],
+      program.programId
+    );
+
+    const ix = await program.methods
+      .transferRemoteStored(new anchor.BN(1000), new anchor.BN(1)) // Allowing a single lamport
+      .accounts({
+        // @ts-ignore
+        boringAccount: boringAccount,
+        configAccount: configAccount,
+        signer: strategist.publicKey,
+        targetProgram: configParams.targetProgram,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        noop: configParams.noop,
+        tokenPda: configParams.tokenPda,
+        mailboxProgram: configParams.mailboxProgram,
+        mailboxOutbox: configParams.mailboxOutbox,
+        messageDispatchAuthority: configParams.messageDispatchAuthority,
+        uniqueMessage: uniqueMessage,
+        messageStoragePda: messageStoragePda,
+        igpProgram: configParams.igpProgram,
+        igpProgramData: configParams.igpProgramData,
+        gasPaymentPda: gasPaymentPda,
+        igpAccount: configParams.igpAccount,
+        tokenSender: configParams.tokenSender,
+        token2022: configParams.token2022Program,
+        mintAuth: configParams.mintAuth,
+        mint: configParams.mint,
+        boringAccountAta: boringAccountAta,
+        strategistAta: strategistAta,
+      })
+      .signers([strategist])
+      .instruction();
+    let txResult = await createAndProcessTransaction(client, creator, ix, [creator, strategist]);
+
+    // Expect the tx to fail.
+    expect(txResult.result).to.exist;
+    const errorLog = txResult.meta.logMessages.find(log =>
+      log.includes("Error Code: GasPaymentTooHigh")
+    )
+    expect(errorLog).to.exist;
+  });
+
+  it("Can transfer remote tokens using stored configuration", async () => {
+    // Refill the holder, since the previous test bridged its whole balance.
+    boringAccountAta = await setupATA(context, configParams.mintAuth, boringAccount, amountToTransfer);
+
+    const { uniqueMessage, messageStoragePda, gasPaymentPda } = await generateMessagePDAs(configParams.mailboxProgram, configParams.igpProgram);
+    const [configAccount] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("boring_config"),
+        boringAccount.toBuffer()
+      ],
+      program.programId
+    );
+
+    const ix = await program.methods
+      .transferRemoteStored(new anchor.BN(amountToTransfer), maxGasPaymentLamports)
+      .accounts({
+        // @ts-ignore
+        boringAccount: boringAccount,
+        configAccount: configAccount,
+        signer: strategist.publicKey,
+        targetProgram: configParams.targetProgram,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        noop: configParams.noop,
+        tokenPda: configParams.tokenPda,
+        mailboxProgram: configParams.mailboxProgram,
+        mailboxOutbox: configParams.mailboxOutbox,
+        messageDispatchAuthority: configParams.messageDispatchAuthority,
+        uniqueMessage: uniqueMessage,
+        messageStoragePda: messageStoragePda,
+        igpProgram: configParams.igpProgram,
+        igpProgramData: configParams.igpProgramData,
+        gasPaymentPda: gasPaymentPda,
+        igpAccount: configParams.igpAccount,
+        tokenSender: configParams.tokenSender,
+        token2022: configParams.token2022Program,
+        mintAuth: configParams.mintAuth,
+        mint: configParams.mint,
+        boringAccountAta: boringAccountAta,
+        strategistAta: strategistAta,
+      })
+      .signers([strategist])
+      .instruction();
+    let txResult = await createAndProcessTransaction(client, creator, ix, [creator, strategist]);
+
+    // Expect the tx to succeed.
     expect(txResult.result).to.be.null;
-    const foundLog = txResult.meta.logMessages.find(log => log.includes("Program version: 1.0.3"));
+
+    const formattedRecipient =  "0x0000…" + evmAddressRaw.slice(-4);
+    const expectedLog = `Warp route transfer completed to destination: ${destinationDomain}, recipient: ${formattedRecipient}, remote_amount: ${amountToTransfer}`;
+    const foundLog = txResult.meta.logMessages.find(log => log.includes(expectedLog));
     expect(foundLog).to.exist;
   });
+
+  it("Updating the configuration updates the stored configuration", async () => {
+    const [configAccount] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("boring_config"),
+        boringAccount.toBuffer()
+      ],
+      program.programId
+    );
+    const update = async (noop: anchor.web3.PublicKey) => {
+      const ix = await program.methods
+        // @ts-ignore
+        .updateConfiguration({ ...configParams, noop })
+        .accounts({
+          // @ts-ignore
+          boringAccount: boringAccount,
+          configAccount: configAccount,
+          signer: owner.publicKey,
+        })
+        .signers([owner])
+        .instruction();
+      return createAndProcessTransaction(client, creator, ix, [creator, owner]);
+    };
+
+    const newNoop = anchor.web3.Keypair.generate().publicKey;
+    let txResult = await update(newNoop);
+    expect(txResult.result).to.be.null;
+    let stored = await program.account.boringConfig.fetch(configAccount);
+    expect(stored.config.noop.equals(newNoop)).to.be.true;
+
+    // Restore the original configuration, which the stored configuration follows.
+    txResult = await update(configParams.noop);
+    expect(txResult.result).to.be.null;
+    stored = await program.account.boringConfig.fetch(configAccount);
+    expect(stored.config.noop.equals(configParams.noop)).to.be.true;
+  });
+
+  it("Should return the correct version", async () => {
+    const ix = await program.methods.version().accounts({}).signers([]).instruction();
+
+    let txResult = await createAndProcessTransaction(client, creator, ix, []);
+    expect(txResult.result).to.be.null;
+
+    // The version is only logged by builds with the `verbose-logs` feature.
+    const foundLog = txResult.meta.logMessages.find(log => log.includes("Program version: 1.1.0"));
+    expect(foundLog).to.not.exist;
+
+    const version = program.coder.types.decode("versionInfo", Buffer.from(txResult.meta.returnData.data));
+    expect(version.major).to.equal(1);
+    expect(version.minor).to.equal(1);
+    expect(version.patch).to.equal(0);
+    expect(version.gitCommit).to.not.be.empty;
+    expect(version.features).to.not.include("verbose-logs");
+    expect(version.features).to.not.include("default");
+  });
+
+  it("Should return the holder state", async () => {
+    const ix = await program.methods
+      .getState()
+      .accounts({
+        // @ts-ignore
+        boringAccount: boringAccount,
+      })
+      .instruction();
+
+    let txResult = await createAndProcessTransaction(client, creator, ix, []);
+    expect(txResult.result).to.be.null;
+
+    const state = program.coder.types.decode("stateView", Buffer.from(txResult.meta.returnData.data));
+    const programBoringAccount = await program.account.boringState.fetch(boringAccount);
+    expect(state.creator.equals(creator.publicKey)).to.be.true;
+    expect(state.owner.equals(owner.publicKey)).to.be.true;
+    expect(state.strategist.equals(strategist.publicKey)).to.be.true;
+    expect(state.configHash).to.deep.equal(programBoringAccount.configHash);
+    expect(state.stateVersion).to.equal(10);
+    // Both the hashed and stored configuration transfers are counted
+    expect(state.totalBridged.toNumber()).to.equal(2 * amountToTransfer);
+    expect(state.transferCount.toNumber()).to.equal(2);
+    expect(state.domainTotals.length).to.equal(1);
+    expect(state.domainTotals[0].bridgeKind).to.deep.equal({ hyperlaneWarpRoute: {} });
+    expect(state.domainTotals[0].domain).to.equal(destinationDomain.toNumber());
+    expect(state.domainTotals[0].totalBridged.toNumber()).to.equal(2 * amountToTransfer);
+  });
+
+  it("Can quote the gas payment", async () => {
+    const quote = async (overheadIgp: PublicKey | null) => {
+      const ix = await program.methods
+        .quoteGasPayment(destinationDomain.toNumber(), new anchor.BN(100_000))
+        .accounts({
+          igpProgram: configParams.igpProgram,
+          igp: configParams.tokenSender,
+          overheadIgp,
+        })
+        .instruction();
+      let txResult = await createAndProcessTransaction(client, creator, ix, []);
+      expect(txResult.result).to.be.null;
+      return new anchor.BN(Buffer.from(txResult.meta.returnData.data), "le");
+    };
+
+    const withoutOverhead = await quote(null);
+    const withOverhead = await quote(configParams.igpAccount);
+    expect(withoutOverhead.toNumber()).to.be.greaterThan(0);
+    expect(withOverhead.gte(withoutOverhead)).to.be.true;
+  });
+
+  it("Fails to quote the gas payment with an overhead IGP wrapping another IGP", async () => {
+    const ix = await program.methods
+      .quoteGasPayment(destinationDomain.toNumber(), new anchor.BN(100_000))
+      .accounts({
+        igpProgram: configParams.igpProgram,
+        igp: configParams.igpAccount, // Using the overhead IGP as the IGP
+        overheadIgp: configParams.igpAccount,
+      })
+      .instruction();
+    let txResult = await createAndProcessTransaction(client, creator, ix, []);
+
+    // Expect the tx to fail.
+    expect(txResult.result).to.exist;
+    const errorLog = txResult.meta.logMessages.find(log =>
+      log.includes("Error Code: InvalidIgpAccount")
+    )
+    expect(errorLog).to.exist;
+  });
+
+  describe("auto bridge", () => {
+    const keeper = anchor.web3.Keypair.generate();
+    let keeperAta: PublicKey;
+    let feeVault: PublicKey;
+    const keeperBounty = 1_000_000;
+
+    async function crankInstruction(): Promise<TransactionInstruction> {
+      const { uniqueMessage, messageStoragePda, gasPaymentPda } = await generateMessagePDAs(configParams.mailboxProgram, configParams.igpProgram);
+      const ix = await program.methods
+        // @ts-ignore
+        .crankTransferRemote(destinationDomain, recipient, remoteDecimals, maxGasPaymentLamports)
+        .accounts({
+          // @ts-ignore
+          transfer: {
+            boringAccount: boringAccount,
+            signer: keeper.publicKey,
+            targetProgram: configParams.targetProgram,
+            systemProgram: anchor.web3.SystemProgram.programId,
+            noop: configParams.noop,
+            tokenPda: configParams.tokenPda,
+            mailboxProgram: configParams.mailboxProgram,
+            mailboxOutbox: configParams.mailboxOutbox,
+            messageDispatchAuthority: configParams.messageDispatchAuthority,
+            uniqueMessage: uniqueMessage,
+            messageStoragePda: messageStoragePda,
+            igpProgram: configParams.igpProgram,
+            igpProgramData: configParams.igpProgramData,
+            gasPaymentPda: gasPaymentPda,
+            igpAccount: configParams.igpAccount,
+            tokenSender: configParams.tokenSender,
+            token2022: configParams.token2022Program,
+            mintAuth: configParams.mintAuth,
+            mint: configParams.mint,
+            boringAccountAta: boringAccountAta,
+            strategistAta: keeperAta,
+          },
+          feeVault: feeVault,
+        })
+        .signers([keeper])
+        .instruction();
+      return ix;
+    }
+
+    async function updatePolicy(signer: Keypair, policy: any): Promise<BanksTransactionResultWithMeta> {
+      const ix = await program.methods
+        .updateAutoBridgePolicy(policy)
+        .accounts({
+          // @ts-ignore
+          boringAccount: boringAccount,
+          signer: signer.publicKey,
+        })
+        .signers([signer])
+        .instruction();
+      return await createAndProcessTransaction(client, creator, ix, [creator, signer]);
+    }
+
+    before(async () => {
+      context.setAccount(keeper.publicKey, {
+        lamports: 2_000_000_000,
+        data: Buffer.alloc(0),
+        owner: anchor.web3.SystemProgram.programId,
+        executable: false,
+      });
+      keeperAta = await setupATA(context, configParams.mintAuth, keeper.publicKey, 0);
+
+      [feeVault] = anchor.web3.PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("fee_vault"),
+          boringAccount.toBuffer()
+        ],
+        program.programId
+      );
+      context.setAccount(feeVault, {
+        lamports: 100_000_000,
+        data: Buffer.alloc(0),
+        owner: anchor.web3.SystemProgram.programId,
+        executable: false,
+      });
+
+      // Refill the holder.
+      boringAccountAta = await setupATA(context, configParams.mintAuth, boringAccount, amountToTransfer);
+    });
+
+    it("Keeper cannot crank while auto bridge is disabled", async () => {
+      const ix = await crankInstruction();
+      let txResult = await createAndProcessTransaction(client, creator, ix, [creator, keeper]);
+
+      // Expect the tx to fail.
+      expect(txResult.result).to.exist;
+      const errorLog = txResult.meta.logMessages.find(log =>
+        log.includes("Error Code: AutoBridgeDisabled")
+      )
+      expect(errorLog).to.exist;
+    });
+
+    it("Only owner can update auto bridge policy", async () => {
+      const policy = {
+        enabled: true,
+        minBalance: new anchor.BN(1000),
+        amount: new anchor.BN(0),
+        minIntervalSeconds: new anchor.BN(3600),
+        keeperBountyLamports: new anchor.BN(keeperBounty),
+      };
+      let txResult = await updatePolicy(keeper, policy);
+
+      // Expect the tx to fail.
+      expect(txResult.result).to.exist;
+      const errorLog = txResult.meta.logMessages.find(log =>
+        log.includes("Error Code: Unauthorized") &&
+        log.includes("Error Message: Unauthorized")
+      )
+      expect(errorLog).to.exist;
+    });
+
+    it("Owner cannot set a keeper bounty above the maximum", async () => {
+      let txResult = await updatePolicy(owner, {
+        enabled: true,
+        minBalance: new anchor.BN(1000),
+        amount: new anchor.BN(0),
+        minIntervalSeconds: new anchor.BN(3600),
+        // One lamport above MAX_KEEPER_BOUNTY_LAMPORTS.
+        keeperBountyLamports: new anchor.BN(100_000_001),
+      });
+
+      // Expect the tx to fail.
+      expect(txResult.result).to.exist;
+      const errorLog = txResult.meta.logMessages.find(log =>
+        log.includes("Error Code: InvalidAutoBridgePolicy")
+      )
+      expect(errorLog).to.exist;
+    });
+
+    it("Owner cannot enable auto bridge without a minimum interval", async () => {
+      let txResult = await updatePolicy(owner, {
+        enabled: true,
+        minBalance: new anchor.BN(1000),
+        amount: new anchor.BN(0),
+        minIntervalSeconds: new anchor.BN(0),
+        keeperBountyLamports: new anchor.BN(keeperBounty),
+      });
+
+      // Expect the tx to fail.
+      expect(txResult.result).to.exist;
+      const errorLog = txResult.meta.logMessages.find(log =>
+        log.includes("Error Code: InvalidAutoBridgePolicy")
+      )
+      expect(errorLog).to.exist;
+    });
+
+    it("Keeper cannot crank below the balance threshold", async () => {
+      let txResult0 = await updatePolicy(owner, {
+        enabled: true,
+        minBalance: new anchor.BN(amountToTransfer + 1),
+        amount: new anchor.BN(0),
+        minIntervalSeconds: new anchor.BN(3600),
+        keeperBountyLamports: new anchor.BN(keeperBounty),
+      });
+      expect(txResult0.result).to.be.null;
+
+      const ix = await crankInstruction();
+      let txResult1 = await createAndProcessTransaction(client, creator, ix, [creator, keeper]);
+
+      // Expect the tx to fail.
+      expect(txResult1.result).to.exist;
+      const errorLog = txResult1.meta.logMessages.find(log =>
+        log.includes("Error Code: AutoBridgeBelowThreshold")
+      )
+      expect(errorLog).to.exist;
+    });
+
+    it("Keeper can crank a full sweep and is paid the bounty", async () => {
+      let txResult0 = await updatePolicy(owner, {
+        enabled: true,
+        minBalance: new anchor.BN(1000),
+        amount: new anchor.BN(0),
+        minIntervalSeconds: new anchor.BN(3600),
+        keeperBountyLamports: new anchor.BN(keeperBounty),
+      });
+      expect(txResult0.result).to.be.null;
+
+      const vaultBefore = (await client.getAccount(feeVault)).lamports;
+
+      const ix = await crankInstruction();
+      let txResult1 = await createAndProcessTransaction(client, creator, ix, [creator, keeper]);
+
+      // Expect the tx to succeed.
+      expect(txResult1.result).to.be.null;
+
+      // The full balance is bridged.
+      const formattedRecipient =  "0x0000…" + evmAddressRaw.slice(-4);
+      const expectedLog = `Warp route transfer completed to destination: ${destinationDomain}, recipient: ${formattedRecipient}, remote_amount: ${amountToTransfer}`;
+      expect(txResult1.meta.logMessages.find(log => log.includes(expectedLog))).to.exist;
+      const holderAtaInfo = await client.getAccount(boringAccountAta);
+      expect(AccountLayout.decode(holderAtaInfo.data).amount).to.equal(BigInt(0));
+
+      // The keeper is paid from the fee vault.
+      const vaultAfter = (await client.getAccount(feeVault)).lamports;
+      expect(vaultBefore - vaultAfter).to.equal(keeperBounty);
+    });
+
+    it("Keeper cannot crank before the minimum interval", async () => {
+      boringAccountAta = await setupATA(context, configParams.mintAuth, boringAccount, amountToTransfer);
+
+      const ix = await crankInstruction();
+      let txResult = await createAndProcessTransaction(client, creator, ix, [creator, keeper]);
+
+      // Expect the tx to fail.
+      expect(txResult.result).to.exist;
+      const errorLog = txResult.meta.logMessages.find(log =>
+        log.includes("Error Code: AutoBridgeTooSoon")
+      )
+      expect(errorLog).to.exist;
+    });
+  });
+
+  describe("sweep", () => {
+    const SWEEP_ALL = new anchor.BN("18446744073709551615");
+    const sweepReserve = 1000;
+
+    async function sweepInstruction(): Promise<TransactionInstruction> {
+      const { uniqueMessage, messageStoragePda, gasPaymentPda } = await generateMessagePDAs(configParams.mailboxProgram, configParams.igpProgram);
+      const ix = await program.methods
+        // @ts-ignore
+        .transferRemote(destinationDomain, recipient, remoteDecimals, SWEEP_ALL, maxGasPaymentLamports)
+        .accounts({
+          // @ts-ignore
+          transfer: {
+            boringAccount: boringAccount,
+            signer: strategist.publicKey,
+            targetProgram: configParams.targetProgram,
+            systemProgram: anchor.web3.SystemProgram.programId,
+            noop: configParams.noop,
+            tokenPda: configParams.tokenPda,
+            mailboxProgram: configParams.mailboxProgram,
+            mailboxOutbox: configParams.mailboxOutbox,
+            messageDispatchAuthority: configParams.messageDispatchAuthority,
+            uniqueMessage: uniqueMessage,
+            messageStoragePda: messageStoragePda,
+            igpProgram: configParams.igpProgram,
+            igpProgramData: configParams.igpProgramData,
+            gasPaymentPda: gasPaymentPda,
+            igpAccount: configParams.igpAccount,
+            tokenSender: configParams.tokenSender,
+            token2022: configParams.token2022Program,
+            mintAuth: configParams.mintAuth,
+            mint: configParams.mint,
+            boringAccountAta: boringAccountAta,
+            strategistAta: strategistAta,
+          },
+        })
+        .signers([strategist])
+        .instruction();
+      return ix;
+    }
+
+    it("Only owner can update sweep reserve", async () => {
+      const ix = await program.methods
+        .updateSweepReserve(new anchor.BN(sweepReserve))
+        .accounts({
+          // @ts-ignore
+          boringAccount: boringAccount,
+          signer: strategist.publicKey,
+        })
+        .signers([strategist])
+        .instruction();
+      let txResult = await createAndProcessTransaction(client, creator, ix, [creator, strategist]);
+
+      // Expect the tx to fail.
+      expect(txResult.result).to.exist;
+      const errorLog = txResult.meta.logMessages.find(log =>
+        log.includes("Error Code: Unauthorized") &&
+        log.includes("Error Message: Unauthorized")
+      )
+      expect(errorLog).to.exist;
+    });
+
+    it("Can sweep the full balance leaving the reserve", async () => {
+      const ix0 = await program.methods
+        .updateSweepReserve(new anchor.BN(sweepReserve))
+        .accounts({
+          // @ts-ignore
+          boringAccount: boringAccount,
+          signer: owner.publicKey,
+        })
+        .signers([owner])
+        .instruction();
+      let txResult0 = await createAndProcessTransaction(client, creator, ix0, [creator, owner]);
+      expect(txResult0.result).to.be.null;
+
+      boringAccountAta = await setupATA(context, configParams.mintAuth, boringAccount, amountToTransfer);
+
+      const ix = await sweepInstruction();
+      let txResult1 = await createAndProcessTransaction(client, creator, ix, [creator, strategist]);
+
+      // Expect the tx to succeed.
+      expect(txResult1.result).to.be.null;
+
+      // Everything above the reserve is bridged.
+      const swept = amountToTransfer - sweepReserve;
+      const formattedRecipient =  "0x0000…" + evmAddressRaw.slice(-4);
+      const expectedLog = `Warp route transfer completed to destination: ${destinationDomain}, recipient: ${formattedRecipient}, remote_amount: ${swept}`;
+      expect(txResult1.meta.logMessages.find(log => log.includes(expectedLog))).to.exist;
+      const holderAtaInfo = await client.getAccount(boringAccountAta);
+      expect(AccountLayout.decode(holderAtaInfo.data).amount).to.equal(BigInt(sweepReserve));
+
+      // The event reports the amount actually bridged.
+      const eventParser = new anchor.EventParser(program.programId, program.coder);
+      const transferEvent = [...eventParser.parseLogs(txResult1.meta.logMessages)].find(event => event.name === "transferRemote");
+      expect(transferEvent.data.amount.toNumber()).to.equal(swept);
+    });
+
+    it("Cannot sweep when only the reserve is left", async () => {
+      const ix = await sweepInstruction();
+      let txResult = await createAndProcessTransaction(client, creator, ix, [creator, strategist]);
+
+      // Expect the tx to fail.
+      expect(txResult.result).to.exist;
+      const errorLog = txResult.meta.logMessages.find(log =>
+        log.includes("Error Code: InsufficientBalance")
+      )
+      expect(errorLog).to.exist;
+    });
+  });
+
+  describe("batch", () => {
+    function routePda(routeId: number): PublicKey {
+      const [route] = anchor.web3.PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("boring_route"),
+          boringAccount.toBuffer(),
+          Buffer.from([routeId])
+        ],
+        program.programId
+      );
+      return route;
+    }
+
+    function legAccounts(routeId: number, uniqueMessage: PublicKey, overrides: any = {}): anchor.web3.AccountMeta[] {
+      const { messageStoragePda, gasPaymentPda } = messagePDAsFor(uniqueMessage);
+      const leg = {
+        route: routePda(routeId),
+        targetProgram: configParams.targetProgram,
+        ...overrides,
+      };
+      return [
+        { pubkey: leg.route, isSigner: false, isWritable: false },
+        { pubkey: leg.targetProgram, isSigner: false, isWritable: false },
+        { pubkey: configParams.noop, isSigner: false, isWritable: false },
+        { pubkey: configParams.tokenPda, isSigner: false, isWritable: false },
+        { pubkey: configParams.mailboxProgram, isSigner: false, isWritable: false },
+        { pubkey: configParams.mailboxOutbox, isSigner: false, isWritable: true },
+        { pubkey: configParams.messageDispatchAuthority, isSigner: false, isWritable: false },
+        { pubkey: uniqueMessage, isSigner: false, isWritable: false },
+        { pubkey: messageStoragePda, isSigner: false, isWritable: true },
+        { pubkey: configParams.igpProgram, isSigner: false, isWritable: false },
+        { pubkey: configParams.igpProgramData, isSigner: false, isWritable: true },
+        { pubkey: gasPaymentPda, isSigner: false, isWritable: true },
+        { pubkey: configParams.igpAccount, isSigner: false, isWritable: false },
+        { pubkey: configParams.tokenSender, isSigner: false, isWritable: true },
+        { pubkey: configParams.token2022Program, isSigner: false, isWritable: false },
+        { pubkey: configParams.mintAuth, isSigner: false, isWritable: true },
+        { pubkey: configParams.mint, isSigner: false, isWritable: false },
+        { pubkey: boringAccountAta, isSigner: false, isWritable: true },
+        { pubkey: strategistAta, isSigner: false, isWritable: true },
+      ];
+    }
+
+    function messagePDAsFor(uniqueMessage: PublicKey) {
+      const [messageStoragePda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("hyperlane"),
+          Buffer.from("-"),
+          Buffer.from("dispatched_message"),
+          Buffer.from("-"),
+          uniqueMessage.toBuffer()
+        ],
+        configParams.mailboxProgram
+      );
+      const [gasPaymentPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("hyperlane_igp"),
+          Buffer.from("-"),
+          Buffer.from("gas_payment"),
+          Buffer.from("-"),
+          uniqueMessage.toBuffer()
+        ],
+        configParams.igpProgram
+      );
+      return { messageStoragePda, gasPaymentPda };
+    }
+
+    async function holderBalance(): Promise<bigint> {
+      const info = await client.getAccount(boringAccountAta);
+      return AccountLayout.decode(info.data).amount;
+    }
+
+    before(async () => {
+      boringAccountAta = await setupATA(context, configParams.mintAuth, boringAccount, amountToTransfer);
+
+      // Approve the same warp route under two route IDs.
+      for (const routeId of [0, 1]) {
+        const ix = await program.methods
+          // @ts-ignore
+          .setRoute(routeId, configParams)
+          .accounts({
+            // @ts-ignore
+            boringAccount: boringAccount,
+            route: routePda(routeId),
+            signer: owner.publicKey,
+          })
+          .signers([owner])
+          .instruction();
+        let txResult = await createAndProcessTransaction(client, creator, ix, [creator, owner]);
+        expect(txResult.result).to.be.null;
+      }
+    });
+
+    it("Only owner can set a route", async () => {
+      const ix = await program.methods
+        // @ts-ignore
+        .setRoute(2, configParams)
+        .accounts({
+          // @ts-ignore
+          boringAccount: boringAccount,
+          route: routePda(2),
+          signer: strategist.publicKey,
+        })
+        .signers([strategist])
+        .instruction();
+      let txResult = await createAndProcessTransaction(client, creator, ix, [creator, strategist]);
+
+      // Expect the tx to fail.
+      expect(txResult.result).to.exist;
+      const errorLog = txResult.meta.logMessages.find(log =>
+        log.includes("Error Code: Unauthorized") &&
+        log.includes("Error Message: Unauthorized")
+      )
+      expect(errorLog).to.exist;
+    });
+
+    it("Batch fails atomically if any leg fails", async () => {
+      const balanceBefore = await holderBalance();
+      // Each leg derives its unique message from the next message nonce.
+      const nonce = await messageNonce();
+      const message0 = uniqueMessagePDA(boringAccount, nonce);
+      const message1 = uniqueMessagePDA(boringAccount, nonce.addn(1));
+
+      const ix = await program.methods
+        .transferRemoteBatch([
+          { routeId: 0, amount: new anchor.BN(1000) },
+          { routeId: 1, amount: new anchor.BN(2000) },
+        ], maxGasPaymentLamports)
+        .accounts({
+          // @ts-ignore
+          boringAccount: boringAccount,
+          signer: strategist.publicKey,
+        })
+        .remainingAccounts([
+          ...legAccounts(0, message0),
+          ...legAccounts(1, message1, { targetProgram: anchor.web3.Keypair.generate().publicKey }), // Using different target program
+        ])
+        .instruction();
+      // The strategist pays fees to keep two legs within the legacy transaction size limit.
+      let txResult = await createAndProcessTransaction(client, strategist, ix, [strategist], multiTransferComputeUnitLimit);
+
+      // Expect the tx to fail, leaving the first leg's transfer undone.
+      expect(txResult.result).to.exist;
+      const errorLog = txResult.meta.logMessages.find(log =>
+        log.includes("Error Code: InvalidConfiguration")
+      )
+      expect(errorLog).to.exist;
+      expect(await holderBalance()).to.equal(balanceBefore);
+    });
+
+    it("Batch fails with mismatched remaining accounts", async () => {
+      const message0 = uniqueMessagePDA(boringAccount, await messageNonce());
+
+      const ix = await program.methods
+        .transferRemoteBatch([
+          { routeId: 0, amount: new anchor.BN(1000) },
+          { routeId: 1, amount: new anchor.BN(2000) },
+        ], maxGasPaymentLamports)
+        .accounts({
+          // @ts-ignore
+          boringAccount: boringAccount,
+          signer: strategist.publicKey,
+        })
+        .remainingAccounts(legAccounts(0, message0))
+        .instruction();
+      let txResult = await createAndProcessTransaction(client, creator, ix, [creator, strategist], multiTransferComputeUnitLimit);
+
+      // Expect the tx to fail.
+      expect(txResult.result).to.exist;
+      const errorLog = txResult.meta.logMessages.find(log =>
+        log.includes("Error Code: InvalidBatchAccounts")
+      )
+      expect(errorLog).to.exist;
+    });
+
+    it("Batch fails when a route's decimals don't match the mint", async () => {
+      const ix0 = await program.methods
+        // @ts-ignore
+        .setRoute(3, { ...configParams, decimals: decimals.addn(3) }) // Using wrong decimals
+        .accounts({
+          // @ts-ignore
+          boringAccount: boringAccount,
+          route: routePda(3),
+          signer: owner.publicKey,
+        })
+        .signers([owner])
+        .instruction();
+      let txResult0 = await createAndProcessTransaction(client, creator, ix0, [creator, owner]);
+      expect(txResult0.result).to.be.null;
+
+      const message0 = uniqueMessagePDA(boringAccount, await messageNonce());
+      const ix = await program.methods
+        .transferRemoteBatch([{ routeId: 3, amount: new anchor.BN(1000) }], maxGasPaymentLamports)
+        .accounts({
+          // @ts-ignore
+          boringAccount: boringAccount,
+          signer: strategist.publicKey,
+        })
+        .remainingAccounts(legAccounts(3, message0))
+        .instruction();
+      let txResult = await createAndProcessTransaction(client, creator, ix, [creator, strategist]);
+
+      // Expect the tx to fail.
+      expect(txResult.result).to.exist;
+      const errorLog = txResult.meta.logMessages.find(log =>
+        log.includes("Error Code: InvalidDecimals")
+      )
+      expect(errorLog).to.exist;
+    });
+
+    it("Can transfer remote across several routes", async () => {
+      const balanceBefore = await holderBalance();
+      // Each leg derives its unique message from the next message nonce.
+      const nonce = await messageNonce();
+      const message0 = uniqueMessagePDA(boringAccount, nonce);
+      const message1 = uniqueMessagePDA(boringAccount, nonce.addn(1));
+
+      const ix = await program.methods
+        .transferRemoteBatch([
+          { routeId: 0, amount: new anchor.BN(1000) },
+          { routeId: 1, amount: new anchor.BN(2000) },
+        ], maxGasPaymentLamports)
+        .accounts({
+          // @ts-ignore
+          boringAccount: boringAccount,
+          signer: strategist.publicKey,
+        })
+        .remainingAccounts([
+          ...legAccounts(0, message0),
+          ...legAccounts(1, message1),
+        ])
+        .instruction();
+      // The strategist pays fees to keep two legs within the legacy transaction size limit.
+      let txResult = await createAndProcessTransaction(client, strategist, ix, [strategist], multiTransferComputeUnitLimit);
+
+      // Expect the tx to succeed.
+      expect(txResult.result).to.be.null;
+
+      // Each leg dispatches its own warp route transfer.
+      const formattedRecipient =  "0x0000…" + evmAddressRaw.slice(-4);
+      for (const amount of [1000, 2000]) {
+        const expectedLog = `Warp route transfer completed to destination: ${destinationDomain}, recipient: ${formattedRecipient}, remote_amount: ${amount}`;
+        expect(txResult.meta.logMessages.find(log => log.includes(expectedLog))).to.exist;
+      }
+      expect(await holderBalance()).to.equal(balanceBefore - BigInt(3000));
+    });
+  });
+
+  describe("inbound messages", () => {
+    const HANDLE_DISCRIMINATOR = createHash("sha256").update("hyperlane-message-recipient:handle").digest().subarray(0, 8);
+    const HANDLE_ACCOUNT_METAS_DISCRIMINATOR = createHash("sha256").update("hyperlane-message-recipient:handle-account-metas").digest().subarray(0, 8);
+    const ISM_DISCRIMINATOR = createHash("sha256").update("hyperlane-message-recipient:interchain-security-module").digest().subarray(0, 8);
+    const originDomain = 1;
+
+    function inboundPda(): PublicKey {
+      const [inbound] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("boring_inbound"), boringAccount.toBuffer()],
+        program.programId
+      );
+      return inbound;
+    }
+
+    function recipientIsmPda(): PublicKey {
+      const [recipientIsm] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("recipient_ism")],
+        program.programId
+      );
+      return recipientIsm;
+    }
+
+    // Borsh encoded `HandleInstruction` with a message of `kind` addressed to the holder
+    function handleData(discriminator: Buffer, kind: number, body: Buffer): Buffer {
+      const message = Buffer.concat([Buffer.from([kind]), boringAccount.toBuffer(), body]);
+      const origin = Buffer.alloc(4);
+      origin.writeUInt32LE(originDomain);
+      const length = Buffer.alloc(4);
+      length.writeUInt32LE(message.length);
+      return Buffer.concat([discriminator, origin, evmRecipient, length, message]);
+    }
+
+    function tokensSentData(discriminator: Buffer, amount: number): Buffer {
+      const body = Buffer.alloc(8);
+      body.writeBigUInt64BE(BigInt(amount));
+      return handleData(discriminator, 0, Buffer.concat([body, configParams.mintAuth.toBuffer()]));
+    }
+
+    async function setInboundConfig(signer: Keypair, ism: PublicKey | null = null): Promise<BanksTransactionResultWithMeta> {
+      const ix = await program.methods
+        .setInboundConfig(HYPERLANE_MAILBOX_PROGRAM_ID, ism, [{ origin: originDomain, sender: Array.from(evmRecipient) }])
+        .accounts({
+          // @ts-ignore
+          boringAccount: boringAccount,
+          inbound: inboundPda(),
+          signer: signer.publicKey,
+        })
+        .signers([signer])
+        .instruction();
+      return createAndProcessTransaction(client, creator, ix, [creator, signer]);
+    }
+
+    it("Only owner can set the inbound configuration", async () => {
+      let txResult = await setInboundConfig(strategist);
+
+      // Expect the tx to fail.
+      expect(txResult.result).to.exist;
+      const errorLog = txResult.meta.logMessages.find(log =>
+        log.includes("Error Code: Unauthorized")
+      )
+      expect(errorLog).to.exist;
+
+      txResult = await setInboundConfig(owner);
+      expect(txResult.result).to.be.null;
+      const inbound = await program.account.boringInbound.fetch(inboundPda());
+      expect(inbound.mailbox.equals(HYPERLANE_MAILBOX_PROGRAM_ID)).to.be.true;
+      expect(inbound.ism).to.be.null;
+      expect(inbound.allowedSenders.length).to.equal(1);
+    });
+
+    it("Owner can choose the ISM of the holder", async () => {
+      const ism = Keypair.generate().publicKey;
+      let txResult = await setInboundConfig(owner, ism);
+      expect(txResult.result).to.be.null;
+      let inbound = await program.account.boringInbound.fetch(inboundPda());
+      expect(inbound.ism.equals(ism)).to.be.true;
+
+      // Back to the mailbox's default ISM
+      txResult = await setInboundConfig(owner);
+      expect(txResult.result).to.be.null;
+      inbound = await program.account.boringInbound.fetch(inboundPda());
+      expect(inbound.ism).to.be.null;
+    });
+
+    it("Reports the default ISM when none is set", async () => {
+      const ix = new TransactionInstruction({
+        programId: program.programId,
+        keys: [{ pubkey: recipientIsmPda(), isSigner: false, isWritable: false }],
+        data: ISM_DISCRIMINATOR,
+      });
+      let txResult = await createAndProcessTransaction(client, creator, ix, []);
+      expect(txResult.result).to.be.null;
+      // `None` followed by the trailing byte
+      expect(Buffer.from(txResult.meta.returnData.data)).to.deep.equal(Buffer.from([0, 255]));
+    });
+
+    it("Reports the accounts of handle", async () => {
+      const ix = new TransactionInstruction({
+        programId: program.programId,
+        keys: [],
+        data: tokensSentData(HANDLE_ACCOUNT_METAS_DISCRIMINATOR, 1000),
+      });
+      let txResult = await createAndProcessTransaction(client, creator, ix, []);
+      expect(txResult.result).to.be.null;
+
+      const returnData = Buffer.from(txResult.meta.returnData.data);
+      expect(returnData.readUInt32LE(0)).to.equal(4);
+      expect(new PublicKey(returnData.subarray(4, 36)).equals(boringAccount)).to.be.true;
+      expect(new PublicKey(returnData.subarray(38, 70)).equals(inboundPda())).to.be.true;
+      expect(new PublicKey(returnData.subarray(72, 104)).equals(recipientIsmPda())).to.be.true;
+      // The holder's token account, whose balance must cover the sent amount
+      expect(new PublicKey(returnData.subarray(106, 138)).equals(boringAccountAta)).to.be.true;
+      // The holder is read-only for tokens sent messages
+      expect(returnData[37]).to.equal(0);
+    });
+
+    it("Only owner can update the governor", async () => {
+      const governor = { enabled: true, origin: originDomain, sender: Array.from(evmRecipient) };
+      const update = async (signer: Keypair) => {
+        const ix = await program.methods
+          .updateGovernor(governor)
+          .accounts({
+            // @ts-ignore
+            boringAccount: boringAccount,
+            signer: signer.publicKey,
+          })
+          .signers([signer])
+          .instruction();
+        return createAndProcessTransaction(client, creator, ix, [creator, signer]);
+      };
+
+      let txResult = await update(strategist);
+
+      // Expect the tx to fail.
+      expect(txResult.result).to.exist;
+      const errorLog = txResult.meta.logMessages.find(log =>
+        log.includes("Error Code: Unauthorized")
+      )
+      expect(errorLog).to.exist;
+
+      txResult = await update(owner);
+      expect(txResult.result).to.be.null;
+      const programBoringAccount = await program.account.boringState.fetch(boringAccount);
+      expect(programBoringAccount.governor.enabled).to.equal(1);
+      expect(programBoringAccount.governor.origin).to.equal(originDomain);
+      expect(programBoringAccount.governor.sender).to.deep.equal(Array.from(evmRecipient));
+    });
+
+    it("Reports the holder as writable for governance messages", async () => {
+      const ix = new TransactionInstruction({
+        programId: program.programId,
+        keys: [],
+        data: handleData(HANDLE_ACCOUNT_METAS_DISCRIMINATOR, 2, strategist.publicKey.toBuffer()),
+      });
+      let txResult = await createAndProcessTransaction(client, creator, ix, []);
+      expect(txResult.result).to.be.null;
+
+      const returnData = Buffer.from(txResult.meta.returnData.data);
+      expect(new PublicKey(returnData.subarray(4, 36)).equals(boringAccount)).to.be.true;
+      expect(returnData[37]).to.equal(1);
+    });
+
+    it("Reports the stored configuration as writable for configuration updates", async () => {
+      const config = program.coder.types.encode("configurationData", configParams);
+      const ix = new TransactionInstruction({
+        programId: program.programId,
+        keys: [],
+        data: handleData(HANDLE_ACCOUNT_METAS_DISCRIMINATOR, 1, config),
+      });
+      let txResult = await createAndProcessTransaction(client, creator, ix, []);
+      expect(txResult.result).to.be.null;
+
+      const [configAccount] = PublicKey.findProgramAddressSync(
+        [Buffer.from("boring_config"), boringAccount.toBuffer()],
+        program.programId
+      );
+      const returnData = Buffer.from(txResult.meta.returnData.data);
+      expect(returnData.readUInt32LE(0)).to.equal(4);
+      expect(new PublicKey(returnData.subarray(106, 138)).equals(configAccount)).to.be.true;
+      expect(returnData[139]).to.equal(1);
+    });
+
+    it("Only the mailbox can deliver governance messages", async () => {
+      const ix = new TransactionInstruction({
+        programId: program.programId,
+        keys: [
+          { pubkey: creator.publicKey, isSigner: true, isWritable: false },
+          { pubkey: boringAccount, isSigner: false, isWritable: true },
+          { pubkey: inboundPda(), isSigner: false, isWritable: false },
+          { pubkey: recipientIsmPda(), isSigner: false, isWritable: false },
+        ],
+        data: handleData(HANDLE_DISCRIMINATOR, 3, creator.publicKey.toBuffer()),
+      });
+      let txResult = await createAndProcessTransaction(client, creator, ix, []);
+
+      // Expect the tx to fail.
+      expect(txResult.result).to.exist;
+      const errorLog = txResult.meta.logMessages.find(log =>
+        log.includes("Error Code: Unauthorized")
+      )
+      expect(errorLog).to.exist;
+      const programBoringAccount = await program.account.boringState.fetch(boringAccount);
+      expect(programBoringAccount.owner.equals(owner.publicKey)).to.be.true;
+    });
+
+    it("Only the mailbox can deliver messages", async () => {
+      const ix = new TransactionInstruction({
+        programId: program.programId,
+        keys: [
+          { pubkey: creator.publicKey, isSigner: true, isWritable: false },
+          { pubkey: boringAccount, isSigner: false, isWritable: false },
+          { pubkey: inboundPda(), isSigner: false, isWritable: false },
+          { pubkey: recipientIsmPda(), isSigner: false, isWritable: false },
+          { pubkey: boringAccountAta, isSigner: false, isWritable: false },
+        ],
+        data: tokensSentData(HANDLE_DISCRIMINATOR, 1000),
+      });
+      let txResult = await createAndProcessTransaction(client, creator, ix, []);
+
+      // Expect the tx to fail.
+      expect(txResult.result).to.exist;
+      const errorLog = txResult.meta.logMessages.find(log =>
+        log.includes("Error Code: Unauthorized")
+      )
+      expect(errorLog).to.exist;
+    });
+  });
+
+  describe("cctp", () => {
+    let mockTokenMessengerMinter: Program<MockTokenMessengerMinter>;
+    let cctpConfig: any;
+    const usdcMint = Keypair.generate().publicKey;
+    const cctpDestinationDomain = 0;
+    let holderUsdcAta: PublicKey;
+
+    function cctpConfigPda(): PublicKey {
+      const [config] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("boring_cctp_config"), boringAccount.toBuffer()],
+        program.programId
+      );
+      return config;
+    }
+
+    async function depositForBurnInstruction(mintRecipient: number[], amount: anchor.BN): Promise<TransactionInstruction> {
+      const messageSentEventData = uniqueMessagePDA(boringAccount, await messageNonce());
+      return program.methods
+        .depositForBurn(cctpDestinationDomain, mintRecipient, amount)
+        .accounts({
+          // @ts-ignore
+          boringAccount: boringAccount,
+          cctpConfig: cctpConfigPda(),
+          signer: strategist.publicKey,
+          boringAccountAta: holderUsdcAta,
+          messageTransmitter: cctpConfig.messageTransmitter,
+          tokenMessenger: cctpConfig.tokenMessenger,
+          remoteTokenMessenger: cctpConfig.remoteTokenMessenger,
+          tokenMinter: cctpConfig.tokenMinter,
+          localToken: cctpConfig.localToken,
+          burnTokenMint: usdcMint,
+          messageSentEventData,
+          messageTransmitterProgram: cctpConfig.messageTransmitterProgram,
+          tokenMessengerMinterProgram: cctpConfig.tokenMessengerMinterProgram,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([strategist])
+        .instruction();
+    }
+
+    before(async () => {
+      mockTokenMessengerMinter = anchor.workspace.MockTokenMessengerMinter as Program<MockTokenMessengerMinter>;
+
+      const mintData = Buffer.alloc(MINT_SIZE);
+      MintLayout.encode(
+        {
+          mintAuthorityOption: 0,
+          mintAuthority: PublicKey.default,
+          supply: BigInt(amountToTransfer),
+          decimals: 6,
+          isInitialized: true,
+          freezeAuthorityOption: 0,
+          freezeAuthority: PublicKey.default,
+        },
+        mintData,
+      );
+      context.setAccount(usdcMint, {
+        lamports: 1_000_000_000,
+        data: mintData,
+        owner: TOKEN_PROGRAM_ID,
+        executable: false,
+      });
+      holderUsdcAta = await setupATA(context, usdcMint, boringAccount, amountToTransfer, TOKEN_PROGRAM_ID);
+
+      cctpConfig = {
+        tokenMessengerMinterProgram: mockTokenMessengerMinter.programId,
+        messageTransmitterProgram: Keypair.generate().publicKey,
+        messageTransmitter: Keypair.generate().publicKey,
+        tokenMessenger: Keypair.generate().publicKey,
+        remoteTokenMessenger: Keypair.generate().publicKey,
+        tokenMinter: Keypair.generate().publicKey,
+        localToken: Keypair.generate().publicKey,
+        burnTokenMint: usdcMint,
+        destinationDomain: cctpDestinationDomain,
+        mintRecipient: Array.from(evmRecipient),
+      };
+    });
+
+    it("Only owner can update the CCTP configuration", async () => {
+      const update = async (signer: Keypair) => {
+        const ix = await program.methods
+          .updateCctpConfiguration(cctpConfig)
+          .accounts({
+            // @ts-ignore
+            boringAccount: boringAccount,
+            cctpConfig: cctpConfigPda(),
+            signer: signer.publicKey,
+          })
+          .signers([signer])
+          .instruction();
+        return createAndProcessTransaction(client, creator, ix, [creator, signer]);
+      };
+
+      let txResult = await update(strategist);
+
+      // Expect the tx to fail.
+      expect(txResult.result).to.exist;
+      const errorLog = txResult.meta.logMessages.find(log =>
+        log.includes("Error Code: Unauthorized")
+      )
+      expect(errorLog).to.exist;
+
+      txResult = await update(owner);
+      expect(txResult.result).to.be.null;
+    });
+
+    it("Deposit for burn rejects a recipient not in the CCTP configuration", async () => {
+      const otherRecipient = Array.from(Keypair.generate().publicKey.toBuffer());
+      const ix = await depositForBurnInstruction(otherRecipient, new anchor.BN(1000));
+      let txResult = await createAndProcessTransaction(client, creator, ix, [creator, strategist]);
+
+      // Expect the tx to fail.
+      expect(txResult.result).to.exist;
+      const errorLog = txResult.meta.logMessages.find(log =>
+        log.includes("Error Code: InvalidConfiguration")
+      )
+      expect(errorLog).to.exist;
+    });
+
+    it("Can burn tokens from the holder through CCTP", async () => {
+      const nonce = await messageNonce();
+      const ix = await depositForBurnInstruction(Array.from(evmRecipient), new anchor.BN(1000));
+      let txResult = await createAndProcessTransaction(client, creator, ix, [creator, strategist]);
+      expect(txResult.result).to.be.null;
+
+      // The holder burned the tokens itself, and the message account is the nonce-derived PDA.
+      const holderAta = AccountLayout.decode((await client.getAccount(holderUsdcAta)).data);
+      expect(holderAta.amount).to.equal(BigInt(amountToTransfer - 1000));
+      const message = await mockTokenMessengerMinter.account.messageSent.fetch(uniqueMessagePDA(boringAccount, nonce));
+      expect(message.depositor.equals(boringAccount)).to.be.true;
+      expect(message.rentPayer.equals(strategist.publicKey)).to.be.true;
+      expect(message.amount.toNumber()).to.equal(1000);
+      expect(message.destinationDomain).to.equal(cctpDestinationDomain);
+      expect(Array.from(message.mintRecipient.toBuffer())).to.deep.equal(Array.from(evmRecipient));
+      expect((await messageNonce()).eq(nonce.addn(1))).to.be.true;
+
+      // The transfer is recorded under its CCTP domain, apart from Hyperlane domains.
+      const eventParser = new anchor.EventParser(program.programId, program.coder);
+      const transferEvent = [...eventParser.parseLogs(txResult.meta.logMessages)].find(event => event.name === "transferRemote");
+      expect(transferEvent.data.bridgeKind).to.deep.equal({ circleCctp: {} });
+      expect(transferEvent.data.destinationDomain).to.equal(cctpDestinationDomain);
+      expect(transferEvent.data.domainTotalBridged.toNumber()).to.equal(1000);
+      const programBoringAccount = await program.account.boringState.fetch(boringAccount);
+      const cctpTotal = programBoringAccount.domainTotals.find(total => total.bridgeKind === 1 && total.transferCount.toNumber() > 0);
+      expect(cctpTotal.domain).to.equal(cctpDestinationDomain);
+      expect(cctpTotal.totalBridged.toNumber()).to.equal(1000);
+    });
+  });
+
+  describe("wormhole ntt", () => {
+    const nttManagerProgram = Keypair.generate().publicKey;
+    const nttRecipientChain = 2;
+    let nttConfig: any;
+
+    function nttConfigPda(): PublicKey {
+      const [config] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("boring_ntt_config"), boringAccount.toBuffer()],
+        program.programId
+      );
+      return config;
+    }
+
+    function nttManagerPda(seeds: Buffer[]): PublicKey {
+      const [pda] = anchor.web3.PublicKey.findProgramAddressSync(seeds, nttManagerProgram);
+      return pda;
+    }
+
+    before(async () => {
+      nttConfig = {
+        nttManagerProgram,
+        managerConfig: Keypair.generate().publicKey,
+        mint: configParams.mintAuth,
+        custody: Keypair.generate().publicKey,
+        outboxRateLimit: Keypair.generate().publicKey,
+        relayerFeeRecipient: Keypair.generate().publicKey,
+        mode: { burning: {} },
+        recipientChain: nttRecipientChain,
+        recipientAddress: Array.from(evmRecipient),
+      };
+    });
+
+    it("Only owner can update the NTT configuration", async () => {
+      const update = async (signer: Keypair) => {
+        const ix = await program.methods
+          .updateNttConfiguration(nttConfig)
+          .accounts({
+            // @ts-ignore
+            boringAccount: boringAccount,
+            nttConfig: nttConfigPda(),
+            signer: signer.publicKey,
+          })
+          .signers([signer])
+          .instruction();
+        return createAndProcessTransaction(client, creator, ix, [creator, signer]);
+      };
+
+      let txResult = await update(strategist);
+
+      // Expect the tx to fail.
+      expect(txResult.result).to.exist;
+      const errorLog = txResult.meta.logMessages.find(log =>
+        log.includes("Error Code: Unauthorized")
+      )
+      expect(errorLog).to.exist;
+
+      txResult = await update(owner);
+      expect(txResult.result).to.be.null;
+    });
+
+    it("Transfer remote NTT rejects a recipient not in the NTT configuration", async () => {
+      const chainSeed = Buffer.alloc(2);
+      chainSeed.writeUInt16BE(nttRecipientChain);
+      const otherRecipient = Array.from(Keypair.generate().publicKey.toBuffer());
+      const ix = await program.methods
+        .transferRemoteNtt({ burning: {} }, nttRecipientChain, otherRecipient, new anchor.BN(1000), new anchor.BN(0))
+        .accounts({
+          // @ts-ignore
+          boringAccount: boringAccount,
+          nttConfig: nttConfigPda(),
+          signer: strategist.publicKey,
+          nttManagerProgram,
+          managerConfig: nttConfig.managerConfig,
+          mint: nttConfig.mint,
+          boringAccountAta: boringAccountAta,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          outboxItem: uniqueMessagePDA(boringAccount, await messageNonce()),
+          outboxRateLimit: nttConfig.outboxRateLimit,
+          custody: nttConfig.custody,
+          inboxRateLimit: nttManagerPda([Buffer.from("inbox_rate_limit"), chainSeed]),
+          peer: nttManagerPda([Buffer.from("peer"), chainSeed]),
+          sessionAuthority: Keypair.generate().publicKey,
+          tokenAuthority: nttManagerPda([Buffer.from("token_authority")]),
+          relayerFeeRecipient: nttConfig.relayerFeeRecipient,
+        })
+        .signers([strategist])
+        .instruction();
+      let txResult = await createAndProcessTransaction(client, creator, ix, [creator, strategist]);
+
+      // Expect the tx to fail.
+      expect(txResult.result).to.exist;
+      const errorLog = txResult.meta.logMessages.find(log =>
+        log.includes("Error Code: InvalidConfiguration")
+      )
+      expect(errorLog).to.exist;
+    });
+  });
+
+  describe("layerzero oft", () => {
+    const oftProgram = Keypair.generate().publicKey;
+    const oftDstEid = 30101;
+    let oftConfig: any;
+
+    function oftConfigPda(): PublicKey {
+      const [config] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("boring_oft_config"), boringAccount.toBuffer()],
+        program.programId
+      );
+      return config;
+    }
+
+    before(async () => {
+      oftConfig = {
+        oftProgram,
+        endpointProgram: Keypair.generate().publicKey,
+        oftStore: Keypair.generate().publicKey,
+        peer: Keypair.generate().publicKey,
+        tokenEscrow: Keypair.generate().publicKey,
+        tokenMint: configParams.mintAuth,
+        dstEid: oftDstEid,
+        to: Array.from(evmRecipient),
+      };
+    });
+
+    it("Only owner can update the OFT configuration", async () => {
+      const update = async (signer: Keypair) => {
+        const ix = await program.methods
+          .updateOftConfiguration(oftConfig)
+          .accounts({
+            // @ts-ignore
+            boringAccount: boringAccount,
+            oftConfig: oftConfigPda(),
+            signer: signer.publicKey,
+          })
+          .signers([signer])
+          .instruction();
+        return createAndProcessTransaction(client, creator, ix, [creator, signer]);
+      };
+
+      let txResult = await update(strategist);
+
+      // Expect the tx to fail.
+      expect(txResult.result).to.exist;
+      const errorLog = txResult.meta.logMessages.find(log =>
+        log.includes("Error Code: Unauthorized")
+      )
+      expect(errorLog).to.exist;
+
+      txResult = await update(owner);
+      expect(txResult.result).to.be.null;
+    });
+
+    it("Transfer remote OFT rejects a recipient not in the OFT configuration", async () => {
+      const [eventAuthority] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("__event_authority")],
+        oftProgram
+      );
+      const otherRecipient = Array.from(Keypair.generate().publicKey.toBuffer());
+      const ix = await program.methods
+        .transferRemoteOft(oftDstEid, otherRecipient, new anchor.BN(1000), new anchor.BN(1000), Buffer.from([]), new anchor.BN(0))
+        .accounts({
+          // @ts-ignore
+          boringAccount: boringAccount,
+          oftConfig: oftConfigPda(),
+          signer: strategist.publicKey,
+          oftProgram,
+          endpointProgram: oftConfig.endpointProgram,
+          peer: oftConfig.peer,
+          oftStore: oftConfig.oftStore,
+          boringAccountAta: boringAccountAta,
+          tokenEscrow: oftConfig.tokenEscrow,
+          tokenMint: oftConfig.tokenMint,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          eventAuthority,
+        })
+        .signers([strategist])
+        .instruction();
+      let txResult = await createAndProcessTransaction(client, creator, ix, [creator, strategist]);
+
+      // Expect the tx to fail.
+      expect(txResult.result).to.exist;
+      const errorLog = txResult.meta.logMessages.find(log =>
+        log.includes("Error Code: InvalidConfiguration")
+      )
+      expect(errorLog).to.exist;
+    });
+  });
+
+  describe("dispatch message", () => {
+    const messageBody = Buffer.from("rebalance completed");
+
+    function dispatchPda(): PublicKey {
+      const [dispatch] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("boring_dispatch"), boringAccount.toBuffer()],
+        program.programId
+      );
+      return dispatch;
+    }
+
+    async function dispatchMessageInstruction(signer: Keypair, recipient: number[], gasAmount: anchor.BN | null): Promise<TransactionInstruction> {
+      const { uniqueMessage, messageStoragePda, gasPaymentPda } = await generateMessagePDAs(configParams.mailboxProgram, configParams.igpProgram);
+      const [configAccount] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("boring_config"), boringAccount.toBuffer()],
+        program.programId
+      );
+      return program.methods
+        .dispatchMessage(destinationDomain.toNumber(), recipient, messageBody, gasAmount, maxGasPaymentLamports)
+        .accounts({
+          // @ts-ignore
+          boringAccount: boringAccount,
+          configAccount,
+          dispatch: dispatchPda(),
+          signer: signer.publicKey,
+          noop: configParams.noop,
+          mailboxProgram: configParams.mailboxProgram,
+          mailboxOutbox: configParams.mailboxOutbox,
+          uniqueMessage,
+          messageStoragePda,
+          igpProgram: configParams.igpProgram,
+          igpProgramData: configParams.igpProgramData,
+          gasPaymentPda,
+          igpAccount: configParams.igpAccount,
+          tokenSender: configParams.tokenSender,
+        })
+        .signers([signer])
+        .instruction();
+    }
+
+    it("Only owner can set the dispatch allowlist", async () => {
+      const update = async (signer: Keypair) => {
+        const ix = await program.methods
+          .setDispatchAllowlist([{ destinationDomain: destinationDomain.toNumber(), recipient: Array.from(evmRecipient) }])
+          .accounts({
+            // @ts-ignore
+            boringAccount: boringAccount,
+            dispatch: dispatchPda(),
+            signer: signer.publicKey,
+          })
+          .signers([signer])
+          .instruction();
+        return createAndProcessTransaction(client, creator, ix, [creator, signer]);
+      };
+
+      let txResult = await update(strategist);
+
+      // Expect the tx to fail.
+      expect(txResult.result).to.exist;
+      const errorLog = txResult.meta.logMessages.find(log =>
+        log.includes("Error Code: Unauthorized")
+      )
+      expect(errorLog).to.exist;
+
+      txResult = await update(owner);
+      expect(txResult.result).to.be.null;
+    });
+
+    it("Dispatch rejects a recipient not in the allowlist", async () => {
+      const otherRecipient = Array.from(Keypair.generate().publicKey.toBuffer());
+      const ix = await dispatchMessageInstruction(strategist, otherRecipient, null);
+      let txResult = await createAndProcessTransaction(client, creator, ix, [creator, strategist]);
+
+      // Expect the tx to fail.
+      expect(txResult.result).to.exist;
+      const errorLog = txResult.meta.logMessages.find(log =>
+        log.includes("Error Code: RecipientNotAllowed")
+      )
+      expect(errorLog).to.exist;
+    });
+
+    it("Dispatch rejects signers other than the owner and strategist", async () => {
+      const randomUser = Keypair.generate();
+      context.setAccount(randomUser.publicKey, {
+        lamports: 1_000_000_000,
+        data: Buffer.alloc(0),
+        owner: anchor.web3.SystemProgram.programId,
+        executable: false,
+      });
+      const ix = await dispatchMessageInstruction(randomUser, Array.from(evmRecipient), null);
+      let txResult = await createAndProcessTransaction(client, creator, ix, [creator, randomUser]);
+
+      // Expect the tx to fail.
+      expect(txResult.result).to.exist;
+      const errorLog = txResult.meta.logMessages.find(log =>
+        log.includes("Error Code: Unauthorized")
+      )
+      expect(errorLog).to.exist;
+    });
+
+    it("Strategist can dispatch a message to an allowed recipient and pay for its gas", async () => {
+      const nonce = await messageNonce();
+      const ix = await dispatchMessageInstruction(strategist, Array.from(evmRecipient), new anchor.BN(100_000));
+      let txResult = await createAndProcessTransaction(client, creator, ix, [creator, strategist]);
+      expect(txResult.result).to.be.null;
+
+      const eventParser = new anchor.EventParser(program.programId, program.coder);
+      const dispatchedEvent = [...eventParser.parseLogs(txResult.meta.logMessages)].find(event => event.name === "messageDispatched");
+      expect(dispatchedEvent).to.exist;
+      expect(dispatchedEvent.data.destinationDomain).to.equal(destinationDomain.toNumber());
+      expect(Buffer.from(dispatchedEvent.data.recipient)).to.deep.equal(evmRecipient);
+      expect(dispatchedEvent.data.gasAmount.toNumber()).to.equal(100_000);
+      expect(dispatchedEvent.data.messageNonce.eq(nonce)).to.be.true;
+      expect((await messageNonce()).eq(nonce.addn(1))).to.be.true;
+    });
+
+    it("Transfer with payload rejects a payload recipient not in the allowlist", async () => {
+      const { uniqueMessage, messageStoragePda, gasPaymentPda } = await generateMessagePDAs(configParams.mailboxProgram, configParams.igpProgram);
+      const payloadMessage = uniqueMessagePDA(boringAccount, (await messageNonce()).addn(1));
+      const [payloadMessageStoragePda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("hyperlane"), Buffer.from("-"), Buffer.from("dispatched_message"), Buffer.from("-"), payloadMessage.toBuffer()],
+        configParams.mailboxProgram
+      );
+      const [payloadGasPaymentPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("hyperlane_igp"), Buffer.from("-"), Buffer.from("gas_payment"), Buffer.from("-"), payloadMessage.toBuffer()],
+        configParams.igpProgram
+      );
+      const otherRecipient = Array.from(Keypair.generate().publicKey.toBuffer());
+
+      const ix = await program.methods
+        .transferRemoteWithPayload(destinationDomain.toNumber(), recipient, remoteDecimals, new anchor.BN(1000), maxGasPaymentLamports, otherRecipient, Buffer.from("deposit 1"), null)
+        .accounts({
+          // @ts-ignore
+          transfer: {
+            boringAccount: boringAccount,
+            signer: strategist.publicKey,
+            targetProgram: configParams.targetProgram,
+            noop: configParams.noop,
+            tokenPda: configParams.tokenPda,
+            mailboxProgram: configParams.mailboxProgram,
+            mailboxOutbox: configParams.mailboxOutbox,
+            messageDispatchAuthority: configParams.messageDispatchAuthority,
+            uniqueMessage,
+            messageStoragePda,
+            igpProgram: configParams.igpProgram,
+            igpProgramData: configParams.igpProgramData,
+            gasPaymentPda,
+            igpAccount: configParams.igpAccount,
+            tokenSender: configParams.tokenSender,
+            token2022: configParams.token2022Program,
+            mintAuth: configParams.mintAuth,
+            mint: configParams.mint,
+            boringAccountAta: boringAccountAta,
+            strategistAta: strategistAta,
+          },
+          dispatch: dispatchPda(),
+          payloadMessage,
+          payloadMessageStoragePda,
+          payloadGasPaymentPda,
+        })
+        .signers([strategist])
+        .instruction();
+      let txResult = await createAndProcessTransaction(client, creator, ix, [creator, strategist], multiTransferComputeUnitLimit);
+
+      // Expect the tx to fail.
+      expect(txResult.result).to.exist;
+      const errorLog = txResult.meta.logMessages.find(log =>
+        log.includes("Error Code: RecipientNotAllowed")
+      )
+      expect(errorLog).to.exist;
+    });
+  });
+
+  describe("lookup table", () => {
+    it("Only owner can record the lookup table", async () => {
+      const lookupTable = anchor.web3.Keypair.generate().publicKey;
+      const setLookupTable = async (signer: Keypair) => {
+        const ix = await program.methods
+          .setLookupTable(lookupTable)
+          .accounts({
+            // @ts-ignore
+            boringAccount: boringAccount,
+            signer: signer.publicKey,
+          })
+          .signers([signer])
+          .instruction();
+        return createAndProcessTransaction(client, creator, ix, [creator, signer]);
+      };
+
+      let txResult = await setLookupTable(strategist);
+
+      // Expect the tx to fail.
+      expect(txResult.result).to.exist;
+      const errorLog = txResult.meta.logMessages.find(log =>
+        log.includes("Error Code: Unauthorized")
+      )
+      expect(errorLog).to.exist;
+
+      txResult = await setLookupTable(owner);
+      expect(txResult.result).to.be.null;
+      const programBoringAccount = await program.account.boringState.fetch(boringAccount);
+      expect(programBoringAccount.lookupTable.equals(lookupTable)).to.be.true;
+
+      const eventParser = new anchor.EventParser(program.programId, program.coder);
+      const events = [...eventParser.parseLogs(txResult.meta.logMessages)];
+      const updatedEvent = events.find(event => event.name === "lookupTableUpdated");
+      expect(updatedEvent).to.exist;
+      expect(updatedEvent.data.lookupTable.equals(lookupTable)).to.be.true;
+    });
+  });
 });